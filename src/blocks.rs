@@ -1,90 +1,169 @@
+pub mod ansible_facts;
+pub mod audio;
+pub mod autorandr;
+pub mod aws_profile;
 pub mod backlight;
 pub mod battery;
 pub mod bluetooth;
+pub mod cava;
+pub mod clipboard;
 pub mod cpu;
+pub mod crypto_price;
+pub mod currency;
 pub mod custom;
 pub mod custom_dbus;
+pub mod dbus_property;
+pub mod debug;
 pub mod disk_space;
 pub mod docker;
+pub mod docker_context;
 pub mod focused_window;
 pub mod github;
+pub mod grafana_alerts;
+pub mod headset;
+pub mod home_assistant;
+pub mod hue_light;
 pub mod hueshift;
 pub mod ibus;
+pub mod jenkins;
+pub mod journal_errors;
 pub mod kdeconnect;
 pub mod keyboard_layout;
+pub mod kubectl;
 pub mod load;
 pub mod maildir;
 pub mod memory;
+pub mod mpv;
 pub mod music;
 pub mod net;
 pub mod networkmanager;
+pub mod nix_shell;
 pub mod notify;
 #[cfg(feature = "notmuch")]
 pub mod notmuch;
+pub mod ntp;
 pub mod nvidia_gpu;
 pub mod pacman;
 pub mod pomodoro;
+pub mod prometheus;
+pub mod quota;
+pub mod remind;
+pub mod scratchpad;
+pub mod screensaver;
+pub mod screenshot;
 pub mod sound;
 pub mod speedtest;
+pub mod spotify;
+pub mod sysctl;
 pub mod taskwarrior;
 pub mod temperature;
 pub mod template;
+pub mod tf_workspace;
 pub mod time;
 pub mod toggle;
 pub mod uptime;
+pub mod vcs;
+pub mod venv;
 pub mod watson;
 pub mod weather;
+pub mod workspaces;
 pub mod xrandr;
 
+use self::ansible_facts::*;
+use self::audio::*;
+use self::autorandr::*;
+use self::aws_profile::*;
 use self::backlight::*;
 use self::battery::*;
 use self::bluetooth::*;
+use self::cava::*;
+use self::clipboard::*;
 use self::cpu::*;
+use self::crypto_price::*;
+use self::currency::*;
 use self::custom::*;
 use self::custom_dbus::*;
+use self::dbus_property::*;
+use self::debug::*;
 use self::disk_space::*;
 use self::docker::*;
+use self::docker_context::*;
 use self::focused_window::*;
 use self::github::*;
+use self::grafana_alerts::*;
+use self::headset::*;
+use self::home_assistant::*;
+use self::hue_light::*;
 use self::hueshift::*;
 use self::ibus::*;
+use self::jenkins::*;
+use self::journal_errors::*;
 use self::kdeconnect::*;
 use self::keyboard_layout::*;
+use self::kubectl::*;
 use self::load::*;
 use self::maildir::*;
 use self::memory::*;
+use self::mpv::*;
 use self::music::*;
 use self::net::*;
 use self::networkmanager::*;
+use self::nix_shell::*;
 use self::notify::*;
 #[cfg(feature = "notmuch")]
 use self::notmuch::*;
+use self::ntp::*;
 use self::nvidia_gpu::*;
 use self::pacman::*;
 use self::pomodoro::*;
+use self::prometheus::*;
+use self::quota::*;
+use self::remind::*;
+use self::scratchpad::*;
+use self::screensaver::*;
+use self::screenshot::*;
 use self::sound::*;
 use self::speedtest::*;
+use self::spotify::*;
+use self::sysctl::*;
 use self::taskwarrior::*;
 use self::temperature::*;
 use self::template::*;
+use self::tf_workspace::*;
 use self::time::*;
 use self::toggle::*;
 use self::uptime::*;
+use self::vcs::*;
+use self::venv::*;
 use self::watson::*;
 use self::weather::*;
+use self::workspaces::*;
 use self::xrandr::*;
 
-use std::time::Duration;
+use std::cmp::min;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
 
 use crossbeam_channel::Sender;
 use serde::de::Deserialize;
+use serde_derive::Deserialize;
 use toml::value::Value;
+use uuid::Uuid;
 
 use crate::config::Config;
+use crate::de::deserialize_duration;
 use crate::errors::*;
 use crate::input::I3BarEvent;
-use crate::scheduler::Task;
-use crate::widget::I3BarWidget;
+#[cfg(feature = "async_runtime")]
+use crate::runtime::AsyncBlockRunner;
+use crate::scheduler::{Task, UpdateLimits};
+use crate::subprocess::spawn_child_async_with_env;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
 
 #[derive(Clone, Debug, PartialEq)]
 pub enum Update {
@@ -129,7 +208,9 @@ pub trait Block {
     }
 }
 
-pub trait ConfigBlock: Block {
+/// A block created from its configuration, which is a `Block` or, with the `async_runtime`
+/// feature, possibly an `AsyncBlock`.
+pub trait ConfigBlock {
     type Config;
 
     /// Creates a new block from the relevant configuration.
@@ -147,55 +228,819 @@ macro_rules! block {
         let block_config: <$block_type as ConfigBlock>::Config =
             <$block_type as ConfigBlock>::Config::deserialize($block_config)
                 .configuration_error("Failed to deserialize block config.")?;
-        Ok(Box::new($block_type::new(block_config, $config, $update_request)?) as Box<dyn Block>)
+        match $update_request {
+            Some(update_request) => Ok(Some(Box::new($block_type::new(
+                block_config,
+                $config,
+                update_request,
+            )?) as Box<dyn Block>)),
+            None => Ok(None),
+        }
     }};
 }
 
+/// Like `block!`, for blocks implementing `AsyncBlock`.
+#[cfg(feature = "async_runtime")]
+macro_rules! async_block {
+    ($block_type:ident, $block_config:expr, $config:expr, $update_request:expr) => {{
+        let block_config: <$block_type as ConfigBlock>::Config =
+            <$block_type as ConfigBlock>::Config::deserialize($block_config)
+                .configuration_error("Failed to deserialize block config.")?;
+        match $update_request {
+            Some(update_request) => Ok(Some(Box::new(AsyncBlockRunner::new(
+                $block_type::new(block_config, $config, update_request.clone())?,
+                update_request,
+            )) as Box<dyn Block>)),
+            None => Ok(None),
+        }
+    }};
+}
+
+/// Wraps a block whose visibility depends on the exit status of `if_command`,
+/// which is re-run every `interval` on a thread of its own, as it may be slow.
+struct GatedBlock {
+    inner: Box<dyn Block>,
+    /// Whether the command last succeeded, set by the thread running it
+    passed: Arc<AtomicBool>,
+    visible: bool,
+}
+
+impl GatedBlock {
+    fn new(
+        inner: Box<dyn Block>,
+        command: String,
+        interval: Duration,
+        tx_update_request: Sender<Task>,
+    ) -> Self {
+        let passed = Arc::new(AtomicBool::new(false));
+        let id = inner.id().to_owned();
+        let gate = passed.clone();
+        // The block is updated when the result changes, to show or hide it.
+        thread::Builder::new()
+            .name("if_command".into())
+            .spawn(move || loop {
+                let result = run_gate_command(&command);
+                if gate.swap(result, Ordering::SeqCst) != result {
+                    let task = Task {
+                        id: id.clone(),
+                        update_time: Instant::now(),
+                    };
+                    if tx_update_request.send(task).is_err() {
+                        break;
+                    }
+                }
+                thread::sleep(interval);
+            })
+            .unwrap();
+
+        GatedBlock {
+            inner,
+            passed,
+            visible: false,
+        }
+    }
+}
+
+impl Block for GatedBlock {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.visible {
+            self.inner.view()
+        } else {
+            vec![]
+        }
+    }
+
+    /// Hidden blocks aren't updated until the command succeeds again.
+    fn update(&mut self) -> Result<Option<Update>> {
+        self.visible = self.passed.load(Ordering::SeqCst);
+        if self.visible {
+            self.inner.update()
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn signal(&mut self, signal: i32) -> Result<()> {
+        if self.visible {
+            self.inner.signal(signal)?;
+        }
+        Ok(())
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if self.visible {
+            self.inner.click(event)?;
+        }
+        Ok(())
+    }
+}
+
+/// Wraps a block whose errors are shown in its place, formatted with `format`, instead
+/// of stopping the bar. The block is updated again every `interval` until it succeeds,
+/// or with `backoff` after 1s, 2s, 4s and so on up to `interval`. After `max_retries`
+/// failed retries, the error is shown for good.
+struct ErrorHandlingBlock {
+    inner: Box<dyn Block>,
+    name: String,
+    format: FormatTemplate,
+    interval: Duration,
+    backoff: bool,
+    max_retries: Option<u32>,
+    widget: TextWidget,
+    on_error: Option<String>,
+    failed: bool,
+    /// Failed attempts since the last success
+    retries: u32,
+    given_up: bool,
+}
+
+impl ErrorHandlingBlock {
+    /// Delay before the next retry.
+    fn retry_delay(&self) -> Duration {
+        if self.backoff {
+            let delay = Duration::from_secs(1 << min(self.retries.saturating_sub(1), 31));
+            min(delay, self.interval)
+        } else {
+            self.interval
+        }
+    }
+
+    /// Shows the error of `result`, if any, and returns whether it succeeded. Only the
+    /// updates are retried, so failed clicks and signals are shown but not counted.
+    fn handle<T>(&mut self, result: Result<T>, is_update: bool) -> Result<Option<T>> {
+        match result {
+            Ok(value) => {
+                if is_update {
+                    self.failed = false;
+                    self.retries = 0;
+                }
+                Ok(Some(value))
+            }
+            Err(error) => {
+                if is_update {
+                    self.retries += 1;
+                    self.given_up = self.max_retries.map_or(false, |max| self.retries > max);
+                    if self.given_up {
+                        log::error!(target: &self.name, "{}, giving up", error);
+                    } else {
+                        log::warn!(
+                            target: &self.name,
+                            "{}, retrying in {}s",
+                            error,
+                            self.retry_delay().as_secs()
+                        );
+                    }
+                } else {
+                    log::warn!(target: &self.name, "{}", error);
+                }
+                let message = match error {
+                    BlockError(_, message) => message,
+                    error => error.to_string(),
+                };
+                // Only report the first of a series of errors.
+                if !self.failed {
+                    if let Some(ref command) = self.on_error {
+                        run_error_hook(command, &self.name, &message);
+                    }
+                }
+                let values = map!("{error}" => message);
+                self.widget
+                    .set_text(self.format.render_static_str(&values)?);
+                // With a limit, retrying is a warning until the block gives up.
+                self.widget
+                    .set_state(if self.max_retries.is_some() && !self.given_up {
+                        State::Warning
+                    } else {
+                        State::Critical
+                    });
+                self.failed = true;
+                Ok(None)
+            }
+        }
+    }
+}
+
+impl Block for ErrorHandlingBlock {
+    fn id(&self) -> &str {
+        self.inner.id()
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.failed {
+            vec![&self.widget]
+        } else {
+            self.inner.view()
+        }
+    }
+
+    fn update(&mut self) -> Result<Option<Update>> {
+        if self.given_up {
+            return Ok(None);
+        }
+        let result = self.inner.update();
+        Ok(match self.handle(result, true)? {
+            Some(update) => update,
+            None if self.given_up => None,
+            None => Some(self.retry_delay().into()),
+        })
+    }
+
+    fn signal(&mut self, signal: i32) -> Result<()> {
+        if self.given_up {
+            return Ok(());
+        }
+        let result = self.inner.signal(signal);
+        self.handle(result, false).map(|_| ())
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if self.given_up {
+            return Ok(());
+        }
+        let result = self.inner.click(event);
+        self.handle(result, false).map(|_| ())
+    }
+}
+
+/// Stands in for a block that could not be created, showing why instead of the block.
+pub struct FailedBlock {
+    id: String,
+    widget: TextWidget,
+}
+
+impl FailedBlock {
+    pub fn new(name: &str, error: Error, config: Config) -> Self {
+        let message = match error {
+            BlockError(_, message) => message,
+            error => error.to_string(),
+        };
+        if let Some(ref command) = config.behavior.on_error {
+            run_error_hook(command, name, &message);
+        }
+        FailedBlock {
+            id: Uuid::new_v4().to_simple().to_string(),
+            widget: TextWidget::new(config)
+                .with_state(State::Critical)
+                .with_text(&format!("{}: {}", name, message)),
+        }
+    }
+}
+
+impl Block for FailedBlock {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.widget]
+    }
+}
+
+/// Runs the `on_error` command of the `[behavior]` section for an error of the block `name`.
+pub fn run_error_hook(command: &str, name: &str, message: &str) {
+    let env = [("I3RS_BLOCK_NAME", name), ("I3RS_ERROR", message)];
+    if let Err(error) = spawn_child_async_with_env("sh", &["-c", command], &env) {
+        log::warn!(target: name, "failed to run on_error: {}", error);
+    }
+}
+
+fn run_gate_command(command: &str) -> bool {
+    Command::new("sh")
+        .args(&["-c", command])
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .map(|status| status.success())
+        .unwrap_or(false)
+}
+
+/// Creates the block `name` from its configuration.
+///
+/// The `if_command` and `if_command_interval` keys are shared by all blocks. If
+/// `if_command` fails at startup and no interval is given, the block is not created
+/// at all and `None` is returned. With an interval, the block is hidden for as long
+/// as the command fails.
+///
+/// If `error_format` or one of the other error keys is given, errors of the block are
+/// shown in its place and it is retried every `error_interval`, instead of stopping the
+/// bar. `error_backoff` and `max_retries` change how often and how long it is retried.
+///
+/// The shared `separator` and `separator_block_width` keys are passed to the block's
+/// widgets through its copy of `config`.
 pub fn create_block(
+    name: &str,
+    mut block_config: Value,
+    mut config: Config,
+    update_request: Sender<Task>,
+) -> Result<Option<Box<dyn Block>>> {
+    let shared_keys = take_shared_keys(&mut block_config, &mut config)?;
+
+    if let (Some(command), None) = (&shared_keys.if_command, shared_keys.if_command_interval) {
+        if !run_gate_command(command) {
+            return Ok(None);
+        }
+    }
+
+    let mut block =
+        match create_inner_block(name, block_config, config.clone(), update_request.clone()) {
+            Ok(block) => block,
+            Err(Unavailable(block, message)) => {
+                log::info!(target: &block, "leaving out the block: {}", message);
+                return Ok(None);
+            }
+            Err(err) => return Err(err),
+        };
+
+    if shared_keys.error_format.is_some()
+        || shared_keys.error_interval.is_some()
+        || shared_keys.error_backoff
+        || shared_keys.max_retries.is_some()
+    {
+        block = Box::new(ErrorHandlingBlock {
+            inner: block,
+            name: name.to_owned(),
+            format: match shared_keys.error_format {
+                Some(format) => format,
+                None => FormatTemplate::from_string(DEFAULT_ERROR_FORMAT)?,
+            },
+            interval: shared_keys
+                .error_interval
+                .unwrap_or(if shared_keys.error_backoff {
+                    DEFAULT_ERROR_MAX_BACKOFF
+                } else {
+                    DEFAULT_ERROR_INTERVAL
+                }),
+            backoff: shared_keys.error_backoff,
+            max_retries: shared_keys.max_retries,
+            on_error: config.behavior.on_error.clone(),
+            widget: TextWidget::new(config).with_state(State::Critical),
+            failed: false,
+            retries: 0,
+            given_up: false,
+        });
+    }
+
+    if let (Some(command), Some(interval)) =
+        (shared_keys.if_command, shared_keys.if_command_interval)
+    {
+        block = Box::new(GatedBlock::new(block, command, interval, update_request));
+    }
+
+    Ok(Some(block))
+}
+
+/// Checks the configuration of the block `name`, without creating it.
+pub fn check_block(name: &str, mut block_config: Value, mut config: Config) -> Result<()> {
+    take_shared_keys(&mut block_config, &mut config)?;
+    instantiate_block(name, block_config, config, None).map(|_| ())
+}
+
+/// The name of the pseudo block showing the widgets of another block again.
+pub const ALIAS_BLOCK: &str = "alias";
+
+#[derive(Deserialize, Debug)]
+#[serde(deny_unknown_fields)]
+struct AliasConfig {
+    /// The `alias_name` of the mirrored block
+    target: String,
+}
+
+/// Returns the `alias_name` of the block mirrored by the alias configured by `block_config`.
+pub fn alias_target(block_config: Value) -> Result<String> {
+    AliasConfig::deserialize(block_config)
+        .configuration_error("Failed to deserialize block config.")
+        .map(|alias| alias.target)
+}
+
+/// Removes `max_update_rate`, the number of times per second the block may be updated at
+/// most, and `throttle_ms` from `block_config`.
+pub fn take_update_limits(block_config: &mut Value) -> Result<UpdateLimits> {
+    let (rate, throttle) = match *block_config {
+        Value::Table(ref mut table) => {
+            (table.remove("max_update_rate"), table.remove("throttle_ms"))
+        }
+        _ => (None, None),
+    };
+    let min_interval = match rate {
+        Some(rate) => {
+            let rate = rate
+                .as_float()
+                .or_else(|| rate.as_integer().map(|rate| rate as f64))
+                .filter(|rate| *rate > 0.0)
+                .ok_or("expected a positive number")
+                .configuration_error("failed to parse 'max_update_rate'")?;
+            Some(Duration::from_secs_f64(1.0 / rate))
+        }
+        None => None,
+    };
+    let throttle = match throttle {
+        Some(throttle) => Some(Duration::from_millis(
+            throttle
+                .as_integer()
+                .filter(|throttle| *throttle >= 0)
+                .ok_or("expected a positive integer")
+                .configuration_error("failed to parse 'throttle_ms'")? as u64,
+        )),
+        None => None,
+    };
+
+    Ok(UpdateLimits {
+        min_interval,
+        throttle,
+    })
+}
+
+/// Removes `alias_name`, which lets aliases refer to the block, from `block_config`.
+pub fn take_alias_name(block_config: &mut Value) -> Result<Option<String>> {
+    let alias_name = match *block_config {
+        Value::Table(ref mut table) => table.remove("alias_name"),
+        _ => None,
+    };
+    match alias_name {
+        Some(name) => Ok(Some(
+            name.as_str()
+                .ok_or("expected a string")
+                .configuration_error("failed to parse 'alias_name'")?
+                .to_owned(),
+        )),
+        None => Ok(None),
+    }
+}
+
+const DEFAULT_ERROR_FORMAT: &str = "{error}";
+const DEFAULT_ERROR_INTERVAL: Duration = Duration::from_secs(5);
+const DEFAULT_ERROR_MAX_BACKOFF: Duration = Duration::from_secs(60);
+
+/// The keys shared by all blocks that are handled outside of the block itself
+struct SharedKeys {
+    if_command: Option<String>,
+    if_command_interval: Option<Duration>,
+    error_format: Option<FormatTemplate>,
+    error_interval: Option<Duration>,
+    error_backoff: bool,
+    max_retries: Option<u32>,
+}
+
+/// Removes the keys shared by all blocks from `block_config`, moving the separator
+/// settings into `config`, and returns the others.
+fn take_shared_keys(block_config: &mut Value, config: &mut Config) -> Result<SharedKeys> {
+    let (if_command, if_command_interval, error_format, error_interval, error_backoff, max_retries) =
+        match *block_config {
+            Value::Table(ref mut table) => (
+                table.remove("if_command"),
+                table.remove("if_command_interval"),
+                table.remove("error_format"),
+                table.remove("error_interval"),
+                table.remove("error_backoff"),
+                table.remove("max_retries"),
+            ),
+            _ => (None, None, None, None, None, None),
+        };
+
+    if let Value::Table(ref mut table) = *block_config {
+        // Some blocks have a `separator` string of their own, leave those alone.
+        if let Some(separator) = table.get("separator").and_then(Value::as_bool) {
+            table.remove("separator");
+            config.separator = Some(separator);
+        }
+        if let Some(width) = table.remove("separator_block_width") {
+            let width = width
+                .as_integer()
+                .filter(|width| *width >= 0)
+                .ok_or("expected a positive integer")
+                .configuration_error("failed to parse 'separator_block_width'")?;
+            config.separator_block_width = Some(width as u32);
+        }
+        if let Some(color) = table.remove("border") {
+            config.border.color = Some(
+                color
+                    .as_str()
+                    .ok_or("expected a string")
+                    .configuration_error("failed to parse 'border'")?
+                    .to_owned(),
+            );
+        }
+        let mut widths = [
+            ("border_top", &mut config.border.top),
+            ("border_bottom", &mut config.border.bottom),
+            ("border_left", &mut config.border.left),
+            ("border_right", &mut config.border.right),
+        ];
+        for (key, width) in &mut widths {
+            if let Some(value) = table.remove(*key) {
+                let value = value
+                    .as_integer()
+                    .filter(|value| *value >= 0)
+                    .ok_or("expected a positive integer")
+                    .configuration_error(&format!("failed to parse '{}'", key))?;
+                **width = Some(value as u32);
+            }
+        }
+    }
+
+    let if_command = match if_command {
+        Some(command) => Some(
+            command
+                .as_str()
+                .ok_or("expected a string")
+                .configuration_error("failed to parse 'if_command'")?
+                .to_owned(),
+        ),
+        None => None,
+    };
+    let if_command_interval = match if_command_interval {
+        Some(interval) => Some(
+            deserialize_duration(interval)
+                .configuration_error("failed to parse 'if_command_interval'")?,
+        ),
+        None => None,
+    };
+    let error_format = match error_format {
+        Some(format) => Some(
+            format
+                .as_str()
+                .ok_or("expected a string")
+                .and_then(|format| {
+                    FormatTemplate::from_string(format).map_err(|_| "invalid format")
+                })
+                .configuration_error("failed to parse 'error_format'")?,
+        ),
+        None => None,
+    };
+    let error_interval = match error_interval {
+        Some(interval) => Some(
+            deserialize_duration(interval)
+                .configuration_error("failed to parse 'error_interval'")?,
+        ),
+        None => None,
+    };
+    let error_backoff = match error_backoff {
+        Some(backoff) => backoff
+            .as_bool()
+            .ok_or("expected a boolean")
+            .configuration_error("failed to parse 'error_backoff'")?,
+        None => false,
+    };
+    let max_retries = match max_retries {
+        Some(retries) => Some(
+            retries
+                .as_integer()
+                .filter(|retries| *retries >= 0)
+                .ok_or("expected a positive integer")
+                .configuration_error("failed to parse 'max_retries'")? as u32,
+        ),
+        None => None,
+    };
+
+    Ok(SharedKeys {
+        if_command,
+        if_command_interval,
+        error_format,
+        error_interval,
+        error_backoff,
+        max_retries,
+    })
+}
+
+fn create_inner_block(
     name: &str,
     block_config: Value,
     config: Config,
     update_request: Sender<Task>,
 ) -> Result<Box<dyn Block>> {
+    instantiate_block(name, block_config, config, Some(update_request))?
+        .internal_error("create_block", "block was only checked")
+}
+
+/// Deserializes the configuration of the block `name` and creates the block. Without
+/// `update_request`, the configuration is only checked and `None` is returned.
+fn instantiate_block(
+    name: &str,
+    block_config: Value,
+    config: Config,
+    update_request: Option<Sender<Task>>,
+) -> Result<Option<Box<dyn Block>>> {
     match name {
         // Please keep these in alphabetical order.
+        "ansible_facts" => block!(AnsibleFacts, block_config, config, update_request),
+        "audio" => block!(Audio, block_config, config, update_request),
+        "autorandr" => block!(Autorandr, block_config, config, update_request),
+        "aws_profile" => block!(AwsProfile, block_config, config, update_request),
         "backlight" => block!(Backlight, block_config, config, update_request),
         "battery" => block!(Battery, block_config, config, update_request),
         "bluetooth" => block!(Bluetooth, block_config, config, update_request),
+        "cava" => block!(Cava, block_config, config, update_request),
+        "clipboard" => block!(Clipboard, block_config, config, update_request),
         "cpu" => block!(Cpu, block_config, config, update_request),
+        "crypto_price" => block!(CryptoPrice, block_config, config, update_request),
+        #[cfg(not(feature = "async_runtime"))]
+        "currency" => block!(Currency, block_config, config, update_request),
+        #[cfg(feature = "async_runtime")]
+        "currency" => async_block!(Currency, block_config, config, update_request),
         "custom" => block!(Custom, block_config, config, update_request),
         "custom_dbus" => block!(CustomDBus, block_config, config, update_request),
+        "dbus_property" => block!(DbusProperty, block_config, config, update_request),
+        "debug" => block!(Debug, block_config, config, update_request),
         "disk_space" => block!(DiskSpace, block_config, config, update_request),
         "docker" => block!(Docker, block_config, config, update_request),
+        "docker_context" => block!(DockerContext, block_config, config, update_request),
         "focused_window" => block!(FocusedWindow, block_config, config, update_request),
         "github" => block!(Github, block_config, config, update_request),
+        "grafana_alerts" => block!(GrafanaAlerts, block_config, config, update_request),
+        "headset" => block!(Headset, block_config, config, update_request),
+        "home_assistant" => block!(HomeAssistant, block_config, config, update_request),
+        "hue_light" => block!(HueLight, block_config, config, update_request),
         "ibus" => block!(IBus, block_config, config, update_request),
+        "jenkins" => block!(Jenkins, block_config, config, update_request),
+        "journal_errors" => block!(JournalErrors, block_config, config, update_request),
         "kdeconnect" => block!(KDEConnect, block_config, config, update_request),
         "keyboard_layout" => block!(KeyboardLayout, block_config, config, update_request),
+        "kubectl" => block!(Kubectl, block_config, config, update_request),
         "load" => block!(Load, block_config, config, update_request),
         "maildir" => block!(Maildir, block_config, config, update_request),
         "memory" => block!(Memory, block_config, config, update_request),
+        "mpv" => block!(Mpv, block_config, config, update_request),
         "music" => block!(Music, block_config, config, update_request),
         "net" => block!(Net, block_config, config, update_request),
         "networkmanager" => block!(NetworkManager, block_config, config, update_request),
+        "nix_shell" => block!(NixShell, block_config, config, update_request),
         "notify" => block!(Notify, block_config, config, update_request),
         #[cfg(feature = "notmuch")]
         "notmuch" => block!(Notmuch, block_config, config, update_request),
+        "ntp" => block!(Ntp, block_config, config, update_request),
         "nvidia_gpu" => block!(NvidiaGpu, block_config, config, update_request),
         "pacman" => block!(Pacman, block_config, config, update_request),
         "pomodoro" => block!(Pomodoro, block_config, config, update_request),
+        "prometheus" => block!(Prometheus, block_config, config, update_request),
+        "quota" => block!(Quota, block_config, config, update_request),
+        "remind" => block!(Remind, block_config, config, update_request),
+        "scratchpad" => block!(Scratchpad, block_config, config, update_request),
+        "screensaver" => block!(Screensaver, block_config, config, update_request),
+        "screenshot" => block!(Screenshot, block_config, config, update_request),
         "sound" => block!(Sound, block_config, config, update_request),
         "speedtest" => block!(SpeedTest, block_config, config, update_request),
+        "spotify" => block!(Spotify, block_config, config, update_request),
+        "sysctl" => block!(Sysctl, block_config, config, update_request),
         "taskwarrior" => block!(Taskwarrior, block_config, config, update_request),
         "temperature" => block!(Temperature, block_config, config, update_request),
         "template" => block!(Template, block_config, config, update_request),
+        "tf_workspace" => block!(TfWorkspace, block_config, config, update_request),
         "time" => block!(Time, block_config, config, update_request),
         "toggle" => block!(Toggle, block_config, config, update_request),
         "uptime" => block!(Uptime, block_config, config, update_request),
+        "vcs" => block!(Vcs, block_config, config, update_request),
+        "venv" => block!(Venv, block_config, config, update_request),
         "watson" => block!(Watson, block_config, config, update_request),
         "weather" => block!(Weather, block_config, config, update_request),
+        "workspaces" => block!(Workspaces, block_config, config, update_request),
         "xrandr" => block!(Xrandr, block_config, config, update_request),
         "hueshift" => block!(Hueshift, block_config, config, update_request),
         other => Err(BlockError(other.to_string(), "Unknown block!".to_string())),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A block whose updates and clicks fail while `failing` is set.
+    struct FlakyBlock {
+        failing: bool,
+    }
+
+    impl Block for FlakyBlock {
+        fn id(&self) -> &str {
+            "flaky"
+        }
+
+        fn view(&self) -> Vec<&dyn I3BarWidget> {
+            vec![]
+        }
+
+        fn update(&mut self) -> Result<Option<Update>> {
+            if self.failing {
+                Err(BlockError("flaky".to_owned(), "failed".to_owned()))
+            } else {
+                Ok(Some(Duration::from_secs(30).into()))
+            }
+        }
+
+        fn click(&mut self, _: &I3BarEvent) -> Result<()> {
+            Err(BlockError("flaky".to_owned(), "click failed".to_owned()))
+        }
+    }
+
+    fn error_handling_block(backoff: bool, max_retries: Option<u32>) -> ErrorHandlingBlock {
+        ErrorHandlingBlock {
+            inner: Box::new(FlakyBlock { failing: true }),
+            name: "flaky".to_owned(),
+            format: FormatTemplate::from_string(DEFAULT_ERROR_FORMAT).unwrap(),
+            interval: Duration::from_secs(5),
+            backoff,
+            max_retries,
+            widget: TextWidget::new(Config::default()),
+            on_error: None,
+            failed: false,
+            retries: 0,
+            given_up: false,
+        }
+    }
+
+    fn background(block: &ErrorHandlingBlock) -> String {
+        block.view()[0].get_rendered()["background"]
+            .as_str()
+            .unwrap()
+            .to_owned()
+    }
+
+    fn state_background(state: State) -> String {
+        state.theme_keys(&Config::default().theme).0.clone()
+    }
+
+    fn click_event() -> I3BarEvent {
+        I3BarEvent {
+            name: Some("flaky".to_owned()),
+            instance: None,
+            x: 0,
+            y: 0,
+            button: crate::input::MouseButton::Left,
+        }
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let mut block = error_handling_block(true, None);
+        let delays: Vec<Option<Update>> = (0..5).map(|_| block.update().unwrap()).collect();
+        let secs = |s| Some(Update::Every(Duration::from_secs(s)));
+        // Up to `interval`, 5s here.
+        assert_eq!(delays, vec![secs(1), secs(2), secs(4), secs(5), secs(5)]);
+    }
+
+    #[test]
+    fn test_retry_interval() {
+        let mut block = error_handling_block(false, None);
+        assert_eq!(
+            block.update().unwrap(),
+            Some(Update::Every(Duration::from_secs(5)))
+        );
+        assert_eq!(
+            block.update().unwrap(),
+            Some(Update::Every(Duration::from_secs(5)))
+        );
+    }
+
+    #[test]
+    fn test_max_retries() {
+        let mut block = error_handling_block(true, Some(2));
+        block.update().unwrap();
+        block.update().unwrap();
+        assert!(!block.given_up);
+        assert_eq!(background(&block), state_background(State::Warning));
+
+        // The third failure is one retry too many.
+        assert_eq!(block.update().unwrap(), None);
+        assert!(block.given_up);
+        assert_eq!(background(&block), state_background(State::Critical));
+        assert_eq!(block.update().unwrap(), None);
+    }
+
+    #[test]
+    fn test_retry_success() {
+        let mut block = error_handling_block(true, Some(2));
+        block.update().unwrap();
+        block.update().unwrap();
+        assert_eq!(block.retries, 2);
+        assert!(block.failed);
+
+        block.inner = Box::new(FlakyBlock { failing: false });
+        assert_eq!(
+            block.update().unwrap(),
+            Some(Update::Every(Duration::from_secs(30)))
+        );
+        assert_eq!(block.retries, 0);
+        assert!(!block.failed);
+    }
+
+    #[test]
+    fn test_click_errors_are_not_retries() {
+        let mut block = error_handling_block(true, Some(1));
+        for _ in 0..3 {
+            block.click(&click_event()).unwrap();
+        }
+        assert!(block.failed);
+        assert_eq!(block.retries, 0);
+        assert!(!block.given_up);
+
+        assert_eq!(
+            block.update().unwrap(),
+            Some(Update::Every(Duration::from_secs(1)))
+        );
+        assert_eq!(block.retries, 1);
+        assert_eq!(background(&block), state_background(State::Warning));
+    }
+}
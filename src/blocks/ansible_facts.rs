@@ -0,0 +1,137 @@
+use std::env;
+use std::fs::read_to_string;
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+pub struct AnsibleFacts {
+    id: String,
+    text: TextWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    fact_path: String,
+    fact_key: String,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AnsibleFactsConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "AnsibleFactsConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Cached facts file, or a command printing the facts as JSON
+    pub fact_path: String,
+
+    /// Dot-separated path of the fact, e.g. "ansible_default_ipv4.address"
+    pub fact_key: String,
+
+    /// Format override
+    #[serde(default = "AnsibleFactsConfig::default_format")]
+    pub format: String,
+}
+
+impl AnsibleFactsConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(600)
+    }
+
+    fn default_format() -> String {
+        "{value}".to_owned()
+    }
+}
+
+/// Looks up the dot-separated `key` in `facts`, where numeric parts index into lists.
+/// Output of `ansible -m setup --tree` wraps the facts in an `ansible_facts` object,
+/// which is skipped.
+fn lookup<'a>(facts: &'a Value, key: &str) -> Option<&'a Value> {
+    let facts = facts.get("ansible_facts").unwrap_or(facts);
+    key.split('.').try_fold(facts, |value, part| match value {
+        Value::Array(items) => items.get(part.parse::<usize>().ok()?),
+        _ => value.get(part),
+    })
+}
+
+impl ConfigBlock for AnsibleFacts {
+    type Config = AnsibleFactsConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        let fact_path = if block_config.fact_path.starts_with("~/") {
+            format!(
+                "{}{}",
+                env::var("HOME").unwrap_or_default(),
+                &block_config.fact_path[1..]
+            )
+        } else {
+            block_config.fact_path
+        };
+
+        Ok(AnsibleFacts {
+            id: Uuid::new_v4().to_simple().to_string(),
+            text: TextWidget::new(config),
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("ansible_facts", "Invalid format specified")?,
+            fact_path,
+            fact_key: block_config.fact_key,
+        })
+    }
+}
+
+impl AnsibleFacts {
+    fn read_facts(&self) -> Result<Value> {
+        let facts = if Path::new(&self.fact_path).is_file() {
+            read_to_string(&self.fact_path)
+                .block_error("ansible_facts", "failed to read the facts file")?
+        } else {
+            let output = Command::new("sh")
+                .args(&["-c", &self.fact_path])
+                .output()
+                .block_error("ansible_facts", "failed to run the facts command")?;
+            String::from_utf8_lossy(&output.stdout).into_owned()
+        };
+
+        serde_json::from_str(&facts).block_error("ansible_facts", "facts are not valid JSON")
+    }
+}
+
+impl Block for AnsibleFacts {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let facts = self.read_facts()?;
+        let value = match lookup(&facts, &self.fact_key) {
+            Some(Value::String(value)) => value.clone(),
+            Some(Value::Null) | None => "?".to_owned(),
+            Some(value) => value.to_string(),
+        };
+
+        let values = map!("{value}" => value);
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -0,0 +1,255 @@
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::sound::{
+    new_device, volume_icon, AlsaOptions, DeviceKind, SoundDevice, SoundDriver,
+};
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::{handle_scroll, FormatTemplate};
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+/// A sink or source of the block, with the widget showing it.
+struct Channel {
+    kind: DeviceKind,
+    device: Box<dyn SoundDevice>,
+    text: ButtonWidget,
+    id: String,
+}
+
+pub struct Audio {
+    id: String,
+    sink: Channel,
+    source: Channel,
+    format: FormatTemplate,
+    step_width: u32,
+    show_volume_when_muted: bool,
+    max_vol: Option<u32>,
+    config: Config,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AudioConfig {
+    /// ALSA / PulseAudio sound device name
+    #[serde(default)]
+    pub driver: SoundDriver,
+
+    /// PulseAudio sink name, or ALSA playback control name (default is "Master")
+    #[serde(default = "AudioConfig::default_sink_name")]
+    pub sink_name: Option<String>,
+
+    /// PulseAudio source name, or ALSA capture control name (default is "Capture")
+    #[serde(default = "AudioConfig::default_source_name")]
+    pub source_name: Option<String>,
+
+    /// ALSA device name, usually in the form "hw:#" where # is the number of the card desired (default is "default")
+    #[serde(default = "AudioConfig::default_device")]
+    pub device: Option<String>,
+
+    /// Use the mapped volume for evaluating the percentage representation like alsamixer
+    #[serde(default = "AudioConfig::default_natural_mapping")]
+    pub natural_mapping: bool,
+
+    /// How often to poll the volume with ALSA if `alsactl monitor` can't be used
+    #[serde(
+        default = "AudioConfig::default_alsa_poll_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub alsa_poll_interval: Duration,
+
+    /// Minimum time between two updates caused by `alsactl monitor` events, in milliseconds
+    #[serde(default = "AudioConfig::default_monitor_debounce_ms")]
+    pub monitor_debounce_ms: u64,
+
+    /// The steps volume is in/decreased (When greater than 50 it gets limited to 50)
+    #[serde(default = "AudioConfig::default_step_width")]
+    pub step_width: u32,
+
+    /// Format string used for both the sink and the source.
+    /// placeholders: {volume}
+    #[serde(default = "AudioConfig::default_format")]
+    pub format: String,
+
+    #[serde(default = "AudioConfig::default_show_volume_when_muted")]
+    pub show_volume_when_muted: bool,
+
+    #[serde(default = "AudioConfig::default_max_vol")]
+    pub max_vol: Option<u32>,
+}
+
+impl AudioConfig {
+    fn default_sink_name() -> Option<String> {
+        None
+    }
+
+    fn default_source_name() -> Option<String> {
+        None
+    }
+
+    fn default_device() -> Option<String> {
+        None
+    }
+
+    fn default_natural_mapping() -> bool {
+        false
+    }
+
+    fn default_alsa_poll_interval() -> Duration {
+        Duration::from_secs(2)
+    }
+
+    fn default_monitor_debounce_ms() -> u64 {
+        250
+    }
+
+    fn default_step_width() -> u32 {
+        5
+    }
+
+    fn default_format() -> String {
+        "{volume}%".into()
+    }
+
+    fn default_show_volume_when_muted() -> bool {
+        false
+    }
+
+    fn default_max_vol() -> Option<u32> {
+        None
+    }
+}
+
+impl Audio {
+    fn display(&mut self) -> Result<()> {
+        for channel in [&mut self.sink, &mut self.source].iter_mut() {
+            channel.device.get_info()?;
+
+            let volume = channel.device.volume();
+            let values = map!("{volume}" => format!("{:02}", volume));
+            let text = self.format.render_static_str(&values)?;
+
+            if channel.device.muted() {
+                channel.text.set_icon(&volume_icon(channel.kind, 0));
+                if self.show_volume_when_muted {
+                    channel.text.set_text(text);
+                } else {
+                    channel.text.set_text("");
+                }
+                channel.text.set_state(State::Warning);
+            } else {
+                channel.text.set_icon(&volume_icon(channel.kind, volume));
+                channel.text.set_text(text);
+                channel.text.set_state(State::Idle);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl ConfigBlock for Audio {
+    type Config = AudioConfig;
+
+    fn new(
+        block_config: Self::Config,
+        config: Config,
+        tx_update_request: Sender<Task>,
+    ) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let sink_id = Uuid::new_v4().to_simple().to_string();
+        let source_id = Uuid::new_v4().to_simple().to_string();
+
+        let alsa = AlsaOptions {
+            device: block_config.device,
+            natural_mapping: block_config.natural_mapping,
+            poll_interval: block_config.alsa_poll_interval,
+            monitor_debounce: Duration::from_millis(block_config.monitor_debounce_ms),
+            lazy_init: false,
+        };
+        let mut sink = new_device(
+            block_config.driver,
+            DeviceKind::Sink,
+            block_config.sink_name,
+            alsa.clone(),
+        )?;
+        let source = new_device(
+            block_config.driver,
+            DeviceKind::Source,
+            block_config.source_name,
+            alsa,
+        )?;
+
+        // Both drivers report changes of any device, so monitoring one is enough.
+        sink.monitor(id.clone(), tx_update_request)?;
+
+        Ok(Audio {
+            sink: Channel {
+                kind: DeviceKind::Sink,
+                device: sink,
+                text: ButtonWidget::new(config.clone(), &sink_id).with_icon("volume_empty"),
+                id: sink_id,
+            },
+            source: Channel {
+                kind: DeviceKind::Source,
+                device: source,
+                text: ButtonWidget::new(config.clone(), &source_id).with_icon("microphone_empty"),
+                id: source_id,
+            },
+            id,
+            format: FormatTemplate::from_string(&block_config.format)?,
+            step_width: block_config.step_width.min(50),
+            show_volume_when_muted: block_config.show_volume_when_muted,
+            max_vol: block_config.max_vol,
+            config,
+        })
+    }
+}
+
+impl Block for Audio {
+    fn update(&mut self) -> Result<Option<Update>> {
+        self.display()?;
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.sink.text, &self.source.text]
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        let channel = if e.matches_name(&self.sink.id) {
+            &mut self.sink
+        } else if e.matches_name(&self.source.id) {
+            &mut self.source
+        } else {
+            return Ok(());
+        };
+
+        match e.button {
+            MouseButton::Right => channel.device.toggle()?,
+            _ => {
+                let device = &mut channel.device;
+                let max_vol = self.max_vol;
+                handle_scroll(
+                    self.config.scrolling,
+                    e,
+                    i64::from(self.step_width),
+                    |step| device.set_volume(step as i32, max_vol),
+                )?;
+            }
+        }
+        self.display()
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
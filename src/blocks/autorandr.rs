@@ -0,0 +1,183 @@
+use std::env;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum AutorandrDriver {
+    Auto,
+    Autorandr,
+    Kanshi,
+}
+
+impl Default for AutorandrDriver {
+    fn default() -> Self {
+        AutorandrDriver::Auto
+    }
+}
+
+pub struct Autorandr {
+    id: String,
+    text: ButtonWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    driver: AutorandrDriver,
+    menu_command: String,
+    tx_update_request: Sender<Task>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AutorandrConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "AutorandrConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "AutorandrConfig::default_format")]
+    pub format: String,
+
+    /// Which program manages the profiles
+    #[serde(default)]
+    pub driver: AutorandrDriver,
+
+    /// Command reading profile names on stdin and printing the selected one
+    #[serde(default = "AutorandrConfig::default_menu_command")]
+    pub menu_command: String,
+}
+
+impl AutorandrConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_format() -> String {
+        "{profile}".to_owned()
+    }
+
+    fn default_menu_command() -> String {
+        "rofi -dmenu -p profile".to_owned()
+    }
+}
+
+impl ConfigBlock for Autorandr {
+    type Config = AutorandrConfig;
+
+    fn new(
+        block_config: Self::Config,
+        config: Config,
+        tx_update_request: Sender<Task>,
+    ) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        // kanshi is the Wayland counterpart of autorandr.
+        let driver = match block_config.driver {
+            AutorandrDriver::Auto if env::var("WAYLAND_DISPLAY").is_ok() => AutorandrDriver::Kanshi,
+            AutorandrDriver::Auto => AutorandrDriver::Autorandr,
+            driver => driver,
+        };
+
+        Ok(Autorandr {
+            text: ButtonWidget::new(config, &id).with_icon("xrandr"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("autorandr", "Invalid format specified")?,
+            driver,
+            menu_command: block_config.menu_command,
+            tx_update_request,
+        })
+    }
+}
+
+impl Block for Autorandr {
+    fn update(&mut self) -> Result<Option<Update>> {
+        // kanshi can't be asked for the active profile, so the block only offers reloading it.
+        if self.driver == AutorandrDriver::Kanshi {
+            let values = map!("{profile}" => String::new());
+            self.text.set_text(self.format.render_static_str(&values)?);
+            return Ok(None);
+        }
+
+        let output = Command::new("autorandr")
+            .arg("--current")
+            .output()
+            .block_error("autorandr", "failed to run autorandr")?;
+        // Several profiles can match the current setup, the first one is shown.
+        let profile = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .next()
+            .unwrap_or_default()
+            .trim()
+            .to_owned();
+
+        if output.status.success() && !profile.is_empty() {
+            self.text.set_state(if profile == "default" {
+                State::Warning
+            } else {
+                State::Idle
+            });
+            let values = map!("{profile}" => profile);
+            self.text.set_text(self.format.render_static_str(&values)?);
+        } else {
+            self.text.set_text("×".to_owned());
+            self.text.set_state(State::Warning);
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if e.matches_name(self.id()) && e.button == MouseButton::Left {
+            let command = match self.driver {
+                AutorandrDriver::Kanshi => "kanshictl reload".to_owned(),
+                _ => format!(
+                    "profile=$(autorandr --list | {}) && autorandr --load \"$profile\"",
+                    self.menu_command
+                ),
+            };
+            let id = self.id.clone();
+            let tx_update_request = self.tx_update_request.clone();
+
+            // The menu waits for the user, so don't block the bar while it is open.
+            thread::Builder::new()
+                .name("autorandr".into())
+                .spawn(move || {
+                    let _ = Command::new("sh").args(&["-c", &command]).status();
+                    let _ = tx_update_request.send(Task {
+                        id,
+                        update_time: Instant::now(),
+                    });
+                })
+                .unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -0,0 +1,146 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct AwsProfile {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    profile: String,
+    expiration: Option<DateTime<Utc>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct AwsProfileConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "AwsProfileConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "AwsProfileConfig::default_format")]
+    pub format: String,
+}
+
+impl AwsProfileConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_format() -> String {
+        "{profile}".to_owned()
+    }
+}
+
+impl ConfigBlock for AwsProfile {
+    type Config = AwsProfileConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        // aws-vault sets AWS_VAULT in the shells it starts, the AWS CLI uses AWS_PROFILE.
+        let profile = env::var("AWS_VAULT")
+            .or_else(|_| env::var("AWS_PROFILE"))
+            .unwrap_or_else(|_| "default".to_owned());
+
+        Ok(AwsProfile {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("aws_profile", "Invalid format specified")?,
+            profile,
+            expiration: None,
+        })
+    }
+}
+
+fn parse_expiration(expiration: &str) -> Option<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(expiration)
+        .ok()
+        .map(|expiration| expiration.with_timezone(&Utc))
+}
+
+/// Finds the expiration of the current session, either from the environment set up by
+/// aws-vault, or from the latest credentials cached by the AWS CLI.
+fn find_expiration() -> Option<DateTime<Utc>> {
+    if let Some(expiration) = env::var("AWS_SESSION_EXPIRATION")
+        .or_else(|_| env::var("AWS_CREDENTIAL_EXPIRATION"))
+        .ok()
+        .and_then(|expiration| parse_expiration(&expiration))
+    {
+        return Some(expiration);
+    }
+
+    let cache = PathBuf::from(env::var("HOME").ok()?).join(".aws/cli/cache");
+    fs::read_dir(cache)
+        .ok()?
+        .filter_map(|entry| fs::read(entry.ok()?.path()).ok())
+        .filter_map(|contents| {
+            let json: serde_json::value::Value = serde_json::from_slice(&contents).ok()?;
+            parse_expiration(json.pointer("/Credentials/Expiration")?.as_str()?)
+        })
+        .max()
+}
+
+fn format_expires_in(seconds: i64) -> String {
+    if seconds <= 0 {
+        "expired".to_owned()
+    } else if seconds >= 3600 {
+        format!("{}h{:02}m", seconds / 3600, seconds % 3600 / 60)
+    } else {
+        format!("{}m", seconds / 60)
+    }
+}
+
+impl Block for AwsProfile {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let now = Utc::now();
+        // Only look for a new session when there is no valid one cached.
+        if self.expiration.map_or(true, |expiration| expiration <= now) {
+            self.expiration = find_expiration();
+        }
+
+        let expires_in = self
+            .expiration
+            .map(|expiration| (expiration - now).num_seconds());
+        self.text.set_state(match expires_in {
+            Some(seconds) if seconds <= 0 => State::Critical,
+            Some(seconds) if seconds < 15 * 60 => State::Warning,
+            _ => State::Idle,
+        });
+
+        let values = map!(
+            "{profile}" => self.profile.clone(),
+            "{expires_in}" => expires_in.map(format_expires_in).unwrap_or_default()
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
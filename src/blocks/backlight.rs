@@ -20,10 +20,11 @@ use uuid::Uuid;
 
 use crate::blocks::Update;
 use crate::blocks::{Block, ConfigBlock};
-use crate::config::{Config, LogicalDirection, Scrolling};
+use crate::config::{Config, Scrolling};
 use crate::errors::*;
 use crate::input::I3BarEvent;
 use crate::scheduler::Task;
+use crate::util::handle_scroll;
 use crate::widget::I3BarWidget;
 use crate::widgets::button::ButtonWidget;
 
@@ -285,20 +286,15 @@ impl Block for Backlight {
         if let Some(ref name) = event.name {
             if name.as_str() == self.id {
                 let brightness = self.device.brightness()?;
-                use LogicalDirection::*;
-                match self.scrolling.to_logical_direction(event.button) {
-                    Some(Up) => {
-                        if brightness < 100 {
-                            self.device.set_brightness(brightness + self.step_width)?;
-                        }
+                let device = &self.device;
+                handle_scroll(self.scrolling, event, self.step_width as i64, |step| {
+                    let new_brightness = brightness as i64 + step;
+                    if (step > 0 && brightness < 100) || (step < 0 && new_brightness > 0) {
+                        device.set_brightness(new_brightness as u64)
+                    } else {
+                        Ok(())
                     }
-                    Some(Down) => {
-                        if brightness > self.step_width {
-                            self.device.set_brightness(brightness - self.step_width)?;
-                        }
-                    }
-                    None => {}
-                }
+                })?;
             }
         }
 
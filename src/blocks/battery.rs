@@ -4,6 +4,7 @@
 //! display the status, capacity, and time remaining for (dis)charge for an
 //! internal power supply.
 
+use std::fs::read_dir;
 use std::path::{Path, PathBuf};
 use std::thread;
 use std::time::{Duration, Instant};
@@ -54,6 +55,15 @@ pub trait BatteryDevice {
     fn power_consumption(&self) -> Result<u64>;
 }
 
+/// Whether sysfs knows any battery, i.e. a power supply named `BAT*`.
+fn has_battery() -> bool {
+    read_dir("/sys/class/power_supply").map_or(false, |entries| {
+        entries
+            .filter_map(|entry| entry.ok())
+            .any(|entry| entry.file_name().to_string_lossy().starts_with("BAT"))
+    })
+}
+
 /// Represents a physical power supply device, as known to sysfs.
 pub struct PowerSupplyDevice {
     device_path: PathBuf,
@@ -69,6 +79,14 @@ impl PowerSupplyDevice {
     pub fn from_device(device: &str, allow_missing: bool) -> Result<Self> {
         let device_path = Path::new("/sys/class/power_supply").join(device);
 
+        // Without any battery, e.g. on a desktop, leave the block out of the bar.
+        if !allow_missing && !device_path.exists() && !has_battery() {
+            return Err(Unavailable(
+                "battery".into(),
+                "no battery found in /sys/class/power_supply".into(),
+            ));
+        }
+
         let device = PowerSupplyDevice {
             device_path,
             allow_missing,
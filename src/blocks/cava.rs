@@ -0,0 +1,164 @@
+use std::env;
+use std::fs;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+const BARS: &[char] = &['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+pub struct Cava {
+    id: String,
+    text: TextWidget,
+    /// The latest frame, or `None` if cava isn't running
+    frame: Arc<Mutex<Option<String>>>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CavaConfig {
+    /// Number of bars
+    #[serde(default = "CavaConfig::default_bars")]
+    pub bars: usize,
+
+    /// Frames per second
+    #[serde(default = "CavaConfig::default_framerate")]
+    pub framerate: u32,
+}
+
+impl CavaConfig {
+    fn default_bars() -> usize {
+        8
+    }
+
+    fn default_framerate() -> u32 {
+        8
+    }
+}
+
+/// Converts a line of cava's raw ASCII output, e.g. "0;3;7;2;", to bars.
+fn parse_frame(line: &str) -> String {
+    line.split(';')
+        .filter_map(|value| value.trim().parse::<usize>().ok())
+        .map(|value| BARS[value.min(BARS.len() - 1)])
+        .collect()
+}
+
+impl ConfigBlock for Cava {
+    type Config = CavaConfig;
+
+    fn new(
+        block_config: Self::Config,
+        config: Config,
+        tx_update_request: Sender<Task>,
+    ) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        // Values range from 0 to the index of the highest bar character.
+        let cava_config = format!(
+            "[general]\n\
+             bars = {}\n\
+             framerate = {}\n\
+             [output]\n\
+             method = raw\n\
+             raw_target = /dev/stdout\n\
+             data_format = ascii\n\
+             ascii_max_range = {}\n\
+             bar_delimiter = 59\n\
+             frame_delimiter = 10\n",
+            block_config.bars,
+            block_config.framerate,
+            BARS.len() - 1
+        );
+        let config_path = env::temp_dir().join(format!("i3status-rust-cava-{}", id));
+        fs::write(&config_path, cava_config)
+            .block_error("cava", "failed to write the cava configuration")?;
+
+        let frame = Arc::new(Mutex::new(None));
+        let frame_copy = frame.clone();
+        let id_copy = id.clone();
+        thread::Builder::new()
+            .name("cava".into())
+            .spawn(move || {
+                // If cava is not installed, the block just stays hidden.
+                let output = Command::new("cava")
+                    .arg("-p")
+                    .arg(&config_path)
+                    .stdout(Stdio::piped())
+                    .stderr(Stdio::null())
+                    .spawn()
+                    .ok()
+                    .and_then(|child| child.stdout);
+
+                if let Some(output) = output {
+                    for line in BufReader::new(output).lines() {
+                        match line {
+                            Ok(line) => {
+                                *frame_copy.lock().unwrap() = Some(parse_frame(&line));
+                                tx_update_request
+                                    .send(Task {
+                                        id: id_copy.clone(),
+                                        update_time: Instant::now(),
+                                    })
+                                    .unwrap();
+                            }
+                            Err(_) => break,
+                        }
+                    }
+                }
+
+                // cava exited, hide the block.
+                *frame_copy.lock().unwrap() = None;
+                tx_update_request
+                    .send(Task {
+                        id: id_copy,
+                        update_time: Instant::now(),
+                    })
+                    .unwrap();
+                let _ = fs::remove_file(&config_path);
+            })
+            .unwrap();
+
+        Ok(Cava {
+            id,
+            text: TextWidget::new(config),
+            frame,
+        })
+    }
+}
+
+impl Block for Cava {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let frame = self
+            .frame
+            .lock()
+            .block_error("cava", "mutex poisoned")?
+            .clone();
+        self.text.set_text(frame.unwrap_or_default());
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.frame.lock().map_or(false, |frame| frame.is_some()) {
+            vec![&self.text]
+        } else {
+            vec![]
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
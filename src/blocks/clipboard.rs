@@ -0,0 +1,232 @@
+use std::env;
+use std::io::{BufRead, BufReader};
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::{escape_pango_text, FormatTemplate};
+use crate::widget::I3BarWidget;
+use crate::widgets::button::ButtonWidget;
+
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Selection {
+    Clipboard,
+    Primary,
+}
+
+impl Default for Selection {
+    fn default() -> Self {
+        Selection::Clipboard
+    }
+}
+
+pub struct Clipboard {
+    id: String,
+    text: ButtonWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    max_chars: usize,
+    selection: Selection,
+    wayland: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ClipboardConfig {
+    /// Update interval in seconds. Only used on X11, where the clipboard is polled.
+    #[serde(
+        default = "ClipboardConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Maximum number of characters of the clipboard content to show
+    #[serde(default = "ClipboardConfig::default_max_chars")]
+    pub max_chars: usize,
+
+    /// Format override
+    #[serde(default = "ClipboardConfig::default_format")]
+    pub format: String,
+
+    /// Which selection to display: "clipboard" or "primary"
+    #[serde(default)]
+    pub selection: Selection,
+}
+
+impl ClipboardConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_max_chars() -> usize {
+        20
+    }
+
+    fn default_format() -> String {
+        "{content}".to_owned()
+    }
+}
+
+impl ConfigBlock for Clipboard {
+    type Config = ClipboardConfig;
+
+    fn new(
+        block_config: Self::Config,
+        config: Config,
+        tx_update_request: Sender<Task>,
+    ) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let wayland = env::var("WAYLAND_DISPLAY").is_ok();
+
+        // wl-paste can tell us when the selection changes, so there is no need to poll.
+        if wayland {
+            let id_copy = id.clone();
+            let mut args = vec!["--watch", "echo"];
+            if block_config.selection == Selection::Primary {
+                args.insert(0, "--primary");
+            }
+            let mut watcher = Command::new("wl-paste")
+                .args(&args)
+                .stdout(Stdio::piped())
+                .spawn()
+                .block_error("clipboard", "failed to start wl-paste --watch")?;
+            let stdout = watcher
+                .stdout
+                .take()
+                .block_error("clipboard", "failed to pipe wl-paste output")?;
+
+            thread::Builder::new()
+                .name("clipboard".into())
+                .spawn(move || {
+                    for _ in BufReader::new(stdout).lines() {
+                        tx_update_request
+                            .send(Task {
+                                id: id_copy.clone(),
+                                update_time: Instant::now(),
+                            })
+                            .unwrap();
+                    }
+                    let _ = watcher.wait();
+                })
+                .unwrap();
+        }
+
+        Ok(Clipboard {
+            text: ButtonWidget::new(config, &id),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("clipboard", "Invalid format specified")?,
+            max_chars: block_config.max_chars,
+            selection: block_config.selection,
+            wayland,
+        })
+    }
+}
+
+impl Clipboard {
+    fn read(&self) -> Result<String> {
+        let mut command = if self.wayland {
+            let mut c = Command::new("wl-paste");
+            c.arg("--no-newline");
+            if self.selection == Selection::Primary {
+                c.arg("--primary");
+            }
+            c
+        } else {
+            let mut c = Command::new("xclip");
+            c.args(&["-o", "-selection"]);
+            c.arg(match self.selection {
+                Selection::Clipboard => "clipboard",
+                Selection::Primary => "primary",
+            });
+            c
+        };
+
+        // Both tools exit with an error when the selection is empty, which isn't a block error.
+        let output = command
+            .stderr(Stdio::null())
+            .output()
+            .block_error("clipboard", "failed to read the clipboard")?;
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+
+    fn clear(&self) -> Result<()> {
+        let mut command = if self.wayland {
+            let mut c = Command::new("wl-copy");
+            c.arg("--clear");
+            if self.selection == Selection::Primary {
+                c.arg("--primary");
+            }
+            c
+        } else {
+            let mut c = Command::new("xclip");
+            c.args(&["-i", "/dev/null", "-selection"]);
+            c.arg(match self.selection {
+                Selection::Clipboard => "clipboard",
+                Selection::Primary => "primary",
+            });
+            c
+        };
+
+        command
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .status()
+            .block_error("clipboard", "failed to clear the clipboard")?;
+        Ok(())
+    }
+
+    fn preview(&self, content: &str) -> String {
+        let flattened = content.split_whitespace().collect::<Vec<&str>>().join(" ");
+        if flattened.chars().count() > self.max_chars {
+            let mut truncated: String = flattened.chars().take(self.max_chars).collect();
+            truncated.push('…');
+            truncated
+        } else {
+            flattened
+        }
+    }
+}
+
+impl Block for Clipboard {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let content = self.read()?;
+        let values = map!("{content}" => escape_pango_text(self.preview(&content)));
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        if self.wayland {
+            Ok(None)
+        } else {
+            Ok(Some(self.update_interval.into()))
+        }
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if e.matches_name(self.id()) && e.button == MouseButton::Left {
+            self.clear()?;
+            self.update()?;
+        }
+
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
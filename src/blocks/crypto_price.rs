@@ -0,0 +1,149 @@
+use std::cmp::max;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::http;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+// CoinGecko's public API is rate limited, so don't poll more often than this.
+const MIN_INTERVAL: Duration = Duration::from_secs(60);
+
+pub struct CryptoPrice {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    coin: String,
+    currency: String,
+    critical_change: f64,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CryptoPriceConfig {
+    /// Update interval in seconds (at least 60)
+    #[serde(
+        default = "CryptoPriceConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// CoinGecko coin id, e.g. "bitcoin"
+    pub coin: String,
+
+    /// Currency to show the price in, e.g. "usd"
+    #[serde(default = "CryptoPriceConfig::default_currency")]
+    pub currency: String,
+
+    /// 24h change in percent below which the state is set to critical
+    #[serde(default = "CryptoPriceConfig::default_critical_change")]
+    pub critical_change: f64,
+
+    /// Format override
+    #[serde(default = "CryptoPriceConfig::default_format")]
+    pub format: String,
+}
+
+impl CryptoPriceConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(300)
+    }
+
+    fn default_currency() -> String {
+        "usd".to_owned()
+    }
+
+    fn default_critical_change() -> f64 {
+        5.0
+    }
+
+    fn default_format() -> String {
+        "{price}".to_owned()
+    }
+}
+
+impl ConfigBlock for CryptoPrice {
+    type Config = CryptoPriceConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        Ok(CryptoPrice {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: max(block_config.interval, MIN_INTERVAL),
+            text: TextWidget::new(config).with_text("×"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("crypto_price", "Invalid format specified")?,
+            coin: block_config.coin.to_lowercase(),
+            currency: block_config.currency.to_lowercase(),
+            critical_change: block_config.critical_change.abs(),
+        })
+    }
+}
+
+impl CryptoPrice {
+    /// Returns the current price and the change over the last 24 hours in percent.
+    fn fetch_price(&self) -> Option<(f64, f64)> {
+        let url = format!(
+            "https://api.coingecko.com/api/v3/simple/price?ids={}&vs_currencies={}&include_24hr_change=true",
+            self.coin, self.currency
+        );
+        let body = http::get(&url, Duration::from_secs(5)).ok()?;
+
+        let json: serde_json::value::Value = serde_json::from_str(&body).ok()?;
+        let price = json
+            .pointer(&format!("/{}/{}", self.coin, self.currency))
+            .and_then(|v| v.as_f64())?;
+        let change = json
+            .pointer(&format!("/{}/{}_24h_change", self.coin, self.currency))
+            .and_then(|v| v.as_f64())
+            .unwrap_or(0.0);
+        Some((price, change))
+    }
+}
+
+impl Block for CryptoPrice {
+    fn update(&mut self) -> Result<Option<Update>> {
+        match self.fetch_price() {
+            Some((price, change)) => {
+                let values = map!(
+                    "{price}" => format!("{:.2}", price),
+                    "{change_24h}" => format!("{:+.2}", change),
+                    "{coin}" => self.coin.clone(),
+                    "{currency}" => self.currency.to_uppercase()
+                );
+                self.text.set_text(self.format.render_static_str(&values)?);
+                self.text.set_state(if change > 0.0 {
+                    State::Good
+                } else if change < -self.critical_change {
+                    State::Critical
+                } else {
+                    State::Idle
+                });
+            }
+            None => {
+                // Most likely no connectivity or rate limited, try again later.
+                self.text.set_text("×".to_owned());
+                self.text.set_state(State::Idle);
+            }
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
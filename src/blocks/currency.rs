@@ -0,0 +1,177 @@
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+#[cfg(feature = "async_runtime")]
+use futures::future::BoxFuture;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+#[cfg(not(feature = "async_runtime"))]
+use crate::blocks::Block;
+use crate::blocks::{ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::http;
+#[cfg(feature = "async_runtime")]
+use crate::runtime::AsyncBlock;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct Currency {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    from: String,
+    to: String,
+    amount: f64,
+    decimal_places: usize,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct CurrencyConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "CurrencyConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Currency to convert from, e.g. "EUR"
+    pub from: String,
+
+    /// Currency to convert to, e.g. "USD"
+    pub to: String,
+
+    /// Amount of `from` currency to convert
+    #[serde(default = "CurrencyConfig::default_amount")]
+    pub amount: f64,
+
+    /// Number of decimal places to display
+    #[serde(default = "CurrencyConfig::default_decimal_places")]
+    pub decimal_places: usize,
+
+    /// Format override
+    #[serde(default = "CurrencyConfig::default_format")]
+    pub format: String,
+}
+
+impl CurrencyConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(3600)
+    }
+
+    fn default_amount() -> f64 {
+        1.0
+    }
+
+    fn default_decimal_places() -> usize {
+        2
+    }
+
+    fn default_format() -> String {
+        "{converted}".to_owned()
+    }
+}
+
+impl ConfigBlock for Currency {
+    type Config = CurrencyConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        Ok(Currency {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_text("×"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("currency", "Invalid format specified")?,
+            from: block_config.from.to_uppercase(),
+            to: block_config.to.to_uppercase(),
+            amount: block_config.amount,
+            decimal_places: block_config.decimal_places,
+        })
+    }
+}
+
+/// How long to wait for the exchange rate
+const FETCH_TIMEOUT: Duration = Duration::from_secs(5);
+
+impl Currency {
+    fn url(&self) -> String {
+        format!(
+            "https://api.exchangerate.host/latest?base={}&symbols={}",
+            self.from, self.to
+        )
+    }
+
+    fn parse_rate(&self, body: &str) -> Option<f64> {
+        let json: serde_json::value::Value = serde_json::from_str(body).ok()?;
+        json.pointer(&format!("/rates/{}", self.to))
+            .and_then(|v| v.as_f64())
+    }
+
+    fn show_rate(&mut self, rate: Option<f64>) -> Result<Option<Update>> {
+        // Fetch failures are likely due to connectivity, so don't error out.
+        match rate {
+            Some(rate) => {
+                let values = map!(
+                    "{rate}" => format!("{:.*}", self.decimal_places, rate),
+                    "{converted}" => format!("{:.*}", self.decimal_places, rate * self.amount),
+                    "{amount}" => format!("{}", self.amount),
+                    "{from}" => self.from.clone(),
+                    "{to}" => self.to.clone()
+                );
+                self.text.set_text(self.format.render_static_str(&values)?);
+                self.text.set_state(State::Idle);
+            }
+            None => {
+                self.text.set_text("×".to_owned());
+                self.text.set_state(State::Critical);
+            }
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+}
+
+#[cfg(not(feature = "async_runtime"))]
+impl Block for Currency {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let rate = http::get(&self.url(), FETCH_TIMEOUT)
+            .ok()
+            .and_then(|body| self.parse_rate(&body));
+        self.show_rate(rate)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(feature = "async_runtime")]
+impl AsyncBlock for Currency {
+    fn update(&mut self) -> BoxFuture<'_, Result<Option<Update>>> {
+        Box::pin(async move {
+            let rate = http::get_async(&self.url(), FETCH_TIMEOUT)
+                .await
+                .ok()
+                .and_then(|body| self.parse_rate(&body));
+            self.show_rate(rate)
+        })
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -0,0 +1,183 @@
+use crossbeam_channel::Sender;
+use dbus::arg::{ArgType, RefArg};
+use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
+use dbus::ffidisp::{BusType, Connection};
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::dbus::DbusSignalStream;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Bus {
+    Session,
+    System,
+}
+
+impl Default for Bus {
+    fn default() -> Self {
+        Bus::Session
+    }
+}
+
+impl From<Bus> for BusType {
+    fn from(bus: Bus) -> Self {
+        match bus {
+            Bus::Session => BusType::Session,
+            Bus::System => BusType::System,
+        }
+    }
+}
+
+pub struct DbusProperty {
+    id: String,
+    text: TextWidget,
+    format: FormatTemplate,
+    con: Connection,
+    service: String,
+    path: String,
+    interface: String,
+    property: String,
+    available: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DbusPropertyConfig {
+    /// Which bus to connect to: "session" or "system"
+    #[serde(default)]
+    pub bus: Bus,
+
+    /// Well-known name of the service owning the object, e.g. "org.freedesktop.UPower"
+    pub service: String,
+
+    /// Object path, e.g. "/org/freedesktop/UPower"
+    pub path: String,
+
+    /// Interface the property belongs to
+    pub interface: String,
+
+    /// Name of the property to display
+    pub property: String,
+
+    /// Format override
+    #[serde(default = "DbusPropertyConfig::default_format")]
+    pub format: String,
+}
+
+impl DbusPropertyConfig {
+    fn default_format() -> String {
+        "{value}".to_owned()
+    }
+}
+
+/// Renders a D-Bus value of any type as a string. Containers are flattened into a
+/// comma separated list.
+fn refarg_to_string(arg: &dyn RefArg) -> String {
+    match arg.arg_type() {
+        ArgType::Boolean => match arg.as_i64() {
+            Some(0) => "false".to_owned(),
+            _ => "true".to_owned(),
+        },
+        ArgType::Double => format!("{}", arg.as_f64().unwrap_or_default()),
+        ArgType::UInt64 => format!("{}", arg.as_u64().unwrap_or_default()),
+        ArgType::Byte
+        | ArgType::Int16
+        | ArgType::UInt16
+        | ArgType::Int32
+        | ArgType::UInt32
+        | ArgType::Int64 => format!("{}", arg.as_i64().unwrap_or_default()),
+        _ => {
+            if let Some(s) = arg.as_str() {
+                s.to_owned()
+            } else if let Some(iter) = arg.as_iter() {
+                iter.map(refarg_to_string)
+                    .collect::<Vec<String>>()
+                    .join(", ")
+            } else {
+                String::new()
+            }
+        }
+    }
+}
+
+impl ConfigBlock for DbusProperty {
+    type Config = DbusPropertyConfig;
+
+    fn new(
+        block_config: Self::Config,
+        config: Config,
+        tx_update_request: Sender<Task>,
+    ) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let bus = block_config.bus;
+        let con = Connection::get_private(bus.into())
+            .block_error("dbus_property", "Failed to establish D-Bus connection.")?;
+
+        let signal_bus = match bus {
+            Bus::Session => dbus::channel::BusType::Session,
+            Bus::System => dbus::channel::BusType::System,
+        };
+        // The first argument of PropertiesChanged is the interface of the changed properties.
+        let interface = block_config.interface.clone();
+        DbusSignalStream::new("dbus_property", signal_bus, id.clone(), tx_update_request)?
+            .on_signal(
+                Some(&block_config.path),
+                "org.freedesktop.DBus.Properties",
+                "PropertiesChanged",
+                move |(changed,): (String,)| changed == interface,
+            )?
+            .start();
+
+        Ok(DbusProperty {
+            id,
+            text: TextWidget::new(config),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("dbus_property", "Invalid format specified")?,
+            con,
+            service: block_config.service,
+            path: block_config.path,
+            interface: block_config.interface,
+            property: block_config.property,
+            available: false,
+        })
+    }
+}
+
+impl Block for DbusProperty {
+    fn update(&mut self) -> Result<Option<Update>> {
+        // The service may come and go, so a missing property just hides the block.
+        let value: Option<Box<dyn RefArg>> = self
+            .con
+            .with_path(self.service.as_str(), self.path.as_str(), 1000)
+            .get(&self.interface, &self.property)
+            .ok();
+
+        self.available = value.is_some();
+        if let Some(value) = value {
+            let values = map!("{value}" => refarg_to_string(&*value));
+            self.text.set_text(self.format.render_static_str(&values)?);
+        }
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.available {
+            vec![&self.text]
+        } else {
+            vec![]
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
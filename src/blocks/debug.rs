@@ -0,0 +1,88 @@
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::{update_stats, Task};
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::text::TextWidget;
+
+pub struct Debug {
+    id: String,
+    text: TextWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DebugConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "DebugConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "DebugConfig::default_format")]
+    pub format: String,
+}
+
+impl DebugConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "{avg}ms avg {max}ms max {queued} queued".to_owned()
+    }
+}
+
+/// Formats `duration` in milliseconds, e.g. "0.4" or "12.0".
+fn format_millis(duration: Duration) -> String {
+    format!("{:.1}", duration.as_secs_f64() * 1000.0)
+}
+
+impl ConfigBlock for Debug {
+    type Config = DebugConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        Ok(Debug {
+            id: Uuid::new_v4().to_simple().to_string(),
+            text: TextWidget::new(config),
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("debug", "Invalid format specified")?,
+        })
+    }
+}
+
+impl Block for Debug {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let stats = update_stats();
+        let values = map!(
+            "{avg}" => format_millis(stats.average()),
+            "{max}" => format_millis(stats.max),
+            "{updates}" => stats.updates.to_string(),
+            "{queued}" => stats.queued.to_string()
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -0,0 +1,139 @@
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+pub struct DockerContext {
+    id: String,
+    text: ButtonWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    menu_command: String,
+    tx_update_request: Sender<Task>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct DockerContextConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "DockerContextConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "DockerContextConfig::default_format")]
+    pub format: String,
+
+    /// Command reading context names on stdin and printing the selected one
+    #[serde(default = "DockerContextConfig::default_menu_command")]
+    pub menu_command: String,
+}
+
+impl DockerContextConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_format() -> String {
+        "{context}".to_owned()
+    }
+
+    fn default_menu_command() -> String {
+        "rofi -dmenu -p context".to_owned()
+    }
+}
+
+impl ConfigBlock for DockerContext {
+    type Config = DockerContextConfig;
+
+    fn new(
+        block_config: Self::Config,
+        config: Config,
+        tx_update_request: Sender<Task>,
+    ) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        Ok(DockerContext {
+            text: ButtonWidget::new(config, &id).with_icon("docker"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("docker_context", "Invalid format specified")?,
+            menu_command: block_config.menu_command,
+            tx_update_request,
+        })
+    }
+}
+
+impl Block for DockerContext {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let output = Command::new("docker")
+            .args(&["context", "show"])
+            .output()
+            .block_error("docker_context", "failed to run docker")?;
+        let context = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+
+        if output.status.success() && !context.is_empty() {
+            self.text.set_state(if context == "default" {
+                State::Idle
+            } else {
+                State::Warning
+            });
+            let values = map!("{context}" => context);
+            self.text.set_text(self.format.render_static_str(&values)?);
+        } else {
+            self.text.set_text("×".to_owned());
+            self.text.set_state(State::Warning);
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if e.matches_name(self.id()) && e.button == MouseButton::Left {
+            let command = format!(
+                "context=$(docker context ls --format '{{{{.Name}}}}' | {}) && docker context use \"$context\"",
+                self.menu_command
+            );
+            let id = self.id.clone();
+            let tx_update_request = self.tx_update_request.clone();
+
+            // The menu waits for the user, so don't block the bar while it is open.
+            thread::Builder::new()
+                .name("docker_context".into())
+                .spawn(move || {
+                    let _ = Command::new("sh").args(&["-c", &command]).status();
+                    let _ = tx_update_request.send(Task {
+                        id,
+                        update_time: Instant::now(),
+                    });
+                })
+                .unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
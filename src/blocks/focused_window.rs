@@ -1,17 +1,16 @@
 use std::sync::{Arc, Mutex};
-use std::thread;
-use std::time::Instant;
 
 use crossbeam_channel::Sender;
 use serde_derive::Deserialize;
 use swayipc::reply::{Event, Node, WindowChange, WorkspaceChange};
-use swayipc::{Connection, EventType};
+use swayipc::EventType;
 use uuid::Uuid;
 
 use crate::blocks::{Block, ConfigBlock, Update};
 use crate::config::Config;
 use crate::errors::*;
 use crate::scheduler::Task;
+use crate::util::i3ipc;
 use crate::widget::I3BarWidget;
 use crate::widgets::text::TextWidget;
 
@@ -59,7 +58,6 @@ impl ConfigBlock for FocusedWindow {
 
     fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
         let id = Uuid::new_v4().to_simple().to_string();
-        let id_clone = id.clone();
 
         let title = Arc::new(Mutex::new(String::from("")));
         let marks = Arc::new(Mutex::new(String::from("")));
@@ -124,59 +122,41 @@ impl ConfigBlock for FocusedWindow {
             }
         };
 
-        let _test_conn =
-            Connection::new().block_error("focused_window", "failed to acquire connect to IPC")?;
-
-        thread::Builder::new()
-            .name("focused_window".into())
-            .spawn(move || {
-                let conn = Connection::new().expect("failed to open connection with swayipc");
-
-                let events = conn
-                    .subscribe(&[EventType::Window, EventType::Workspace])
-                    .expect("could not subscribe to window events");
-
-                for event in events {
-                    let updated = match event.expect("could not read event in `window` block") {
-                        Event::Window(e) => match (e.change, e.container) {
-                            (WindowChange::Mark, Node { marks, .. }) => update_marks(marks),
-                            (WindowChange::Focus, Node { name, marks, .. }) => {
-                                let updated_for_window = name.map(&update_window).unwrap_or(false);
-                                let updated_for_marks = update_marks(marks);
-                                updated_for_window || updated_for_marks
-                            }
-                            (
-                                WindowChange::Title,
-                                Node {
-                                    focused: true,
-                                    name: Some(name),
-                                    ..
-                                },
-                            ) => update_window(name),
-                            (
-                                WindowChange::Close,
-                                Node {
-                                    name: Some(name), ..
-                                },
-                            ) => close_window(name),
-                            _ => false,
-                        },
-                        Event::Workspace(e) if e.change == WorkspaceChange::Init => {
-                            update_window("".to_string())
-                        }
-                        _ => false,
-                    };
-
-                    if updated {
-                        tx.send(Task {
-                            id: id_clone.clone(),
-                            update_time: Instant::now(),
-                        })
-                        .expect("could not communicate with channel in `window` block");
+        i3ipc::subscribe(
+            "focused_window",
+            &[EventType::Window, EventType::Workspace],
+            id.clone(),
+            tx,
+            move |event| match event {
+                Event::Window(e) => match (e.change, e.container) {
+                    (WindowChange::Mark, Node { marks, .. }) => update_marks(marks),
+                    (WindowChange::Focus, Node { name, marks, .. }) => {
+                        let updated_for_window = name.map(&update_window).unwrap_or(false);
+                        let updated_for_marks = update_marks(marks);
+                        updated_for_window || updated_for_marks
                     }
+                    (
+                        WindowChange::Title,
+                        Node {
+                            focused: true,
+                            name: Some(name),
+                            ..
+                        },
+                    ) => update_window(name),
+                    (
+                        WindowChange::Close,
+                        Node {
+                            name: Some(name), ..
+                        },
+                    ) => close_window(name),
+                    _ => false,
+                },
+                Event::Workspace(e) if e.change == WorkspaceChange::Init => {
+                    update_window("".to_string())
                 }
-            })
-            .expect("failed to start watching thread for `window` block");
+                _ => false,
+            },
+        )?;
 
         Ok(FocusedWindow {
             id,
@@ -1,5 +1,4 @@
 use std::collections::HashMap;
-use std::process::Command;
 use std::time::Duration;
 
 use crossbeam_channel::Sender;
@@ -12,6 +11,7 @@ use crate::blocks::{Block, ConfigBlock, Update};
 use crate::config::Config;
 use crate::de::deserialize_duration;
 use crate::errors::*;
+use crate::http;
 use crate::input::I3BarEvent;
 use crate::scheduler::Task;
 use crate::util::FormatTemplate;
@@ -187,39 +187,19 @@ impl<'a> Notifications<'a> {
             return Ok(None);
         }
 
-        let result = Command::new("sh")
-            .args(&[
-                "-c",
-                &format!(
-                    "curl --silent --dump-header - --header \"Authorization: Bearer {token}\" -m 3 \"{next_page_url}\"",
-                    token = self.token,
-                    next_page_url = self.next_page_url,
-                ),
-            ])
-            .output()?;
-
-        // Catch all errors, if response status is not 200, then curl wont exit with status code 0.
-        if !result.status.success() {
+        let response = http::request(
+            &self.next_page_url,
+            &[&format!("Authorization: Bearer {}", self.token)],
+            Duration::from_secs(3),
+        )?;
+        if response.status != 200 {
             return Err(Box::new(BlockError(
                 "github".to_owned(),
-                "curl status code different than 0".to_owned(),
+                format!("unexpected response status {}", response.status),
             )));
         }
 
-        let output = String::from_utf8(result.stdout)?;
-
-        // Status / headers sections are separed by a blank line.
-        let split: Vec<&str> = output.split("\r\n\r\n").collect();
-        if split.len() != 2 {
-            return Err(Box::new(BlockError(
-                "github".to_owned(),
-                "unexpected curl output".to_owned(),
-            )));
-        }
-
-        let (meta, body) = (split[0], split[1]);
-
-        let next = match meta.lines().find(|&l| l.starts_with("Link:")) {
+        let next = match response.header("Link") {
             Some(v) => match parse_links_header(v).get("next") {
                 Some(next) => next,
                 None => "",
@@ -228,7 +208,7 @@ impl<'a> Notifications<'a> {
         };
         self.next_page_url = next.to_owned();
 
-        let notifications: Vec<Notification> = serde_json::from_str(body)?;
+        let notifications: Vec<Notification> = serde_json::from_str(&response.body)?;
         self.notifications = notifications.into_iter();
 
         Ok(self.notifications.next())
@@ -0,0 +1,165 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct GrafanaAlerts {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    url: String,
+    auth_command: String,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct GrafanaAlertsConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "GrafanaAlertsConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Base URL of the Grafana instance, e.g. "https://example.grafana.net"
+    pub url: String,
+
+    /// Command printing either an API key or "user:password" for basic auth
+    pub auth_command: String,
+
+    /// Format override
+    #[serde(default = "GrafanaAlertsConfig::default_format")]
+    pub format: String,
+}
+
+impl GrafanaAlertsConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_format() -> String {
+        "{alerting}".to_owned()
+    }
+}
+
+impl ConfigBlock for GrafanaAlerts {
+    type Config = GrafanaAlertsConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        Ok(GrafanaAlerts {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_text("×"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("grafana_alerts", "Invalid format specified")?,
+            url: format!("{}/api/alerts", block_config.url.trim_end_matches('/')),
+            auth_command: block_config.auth_command,
+        })
+    }
+}
+
+impl GrafanaAlerts {
+    /// Builds the curl arguments for authentication. The command is run for every
+    /// request, so that rotating tokens are picked up.
+    fn auth_args(&self) -> Result<Vec<String>> {
+        let output = Command::new("sh")
+            .args(&["-c", &self.auth_command])
+            .output()
+            .block_error("grafana_alerts", "failed to run auth_command")?;
+        if !output.status.success() {
+            return Err(BlockError(
+                "grafana_alerts".to_owned(),
+                "auth_command exited with an error".to_owned(),
+            ));
+        }
+
+        let secret = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+        Ok(if secret.contains(':') {
+            vec!["--user".to_owned(), secret]
+        } else {
+            vec![
+                "--header".to_owned(),
+                format!("Authorization: Bearer {}", secret),
+            ]
+        })
+    }
+
+    /// Returns the number of alerting, pending and ok alerts.
+    fn fetch_counts(&self) -> Result<Option<(usize, usize, usize)>> {
+        // Run curl directly rather than through a shell, to keep the secret out of
+        // the command line parsing.
+        let output = match Command::new("curl")
+            .args(&["--fail", "--silent", "-m", "5"])
+            .args(self.auth_args()?)
+            .arg(&self.url)
+            .output()
+        {
+            Ok(output) => output,
+            Err(_) => return Ok(None),
+        };
+
+        let json: serde_json::value::Value = match serde_json::from_slice(&output.stdout) {
+            Ok(json) => json,
+            Err(_) => return Ok(None),
+        };
+        let alerts = match json.as_array() {
+            Some(alerts) => alerts,
+            None => return Ok(None),
+        };
+
+        let count = |state: &str| {
+            alerts
+                .iter()
+                .filter(|alert| alert.pointer("/state").and_then(|s| s.as_str()) == Some(state))
+                .count()
+        };
+        Ok(Some((count("alerting"), count("pending"), count("ok"))))
+    }
+}
+
+impl Block for GrafanaAlerts {
+    fn update(&mut self) -> Result<Option<Update>> {
+        match self.fetch_counts()? {
+            Some((alerting, pending, ok)) => {
+                let values = map!(
+                    "{alerting}" => alerting,
+                    "{pending}" => pending,
+                    "{ok}" => ok
+                );
+                self.text.set_text(self.format.render_static_str(&values)?);
+                self.text.set_state(if alerting == 0 {
+                    State::Good
+                } else {
+                    State::Critical
+                });
+            }
+            None => {
+                // Most likely no connectivity, try again later.
+                self.text.set_text("×".to_owned());
+                self.text.set_state(State::Warning);
+            }
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
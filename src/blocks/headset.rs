@@ -0,0 +1,131 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::{has_command, FormatTemplate};
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct Headset {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    available: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HeadsetConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "HeadsetConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "HeadsetConfig::default_format")]
+    pub format: String,
+}
+
+impl HeadsetConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(30)
+    }
+
+    fn default_format() -> String {
+        "{battery}%".to_owned()
+    }
+}
+
+impl ConfigBlock for Headset {
+    type Config = HeadsetConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        Ok(Headset {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_icon("headphones"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("headset", "Invalid format specified")?,
+            available: has_command("headset", "headsetcontrol")?,
+        })
+    }
+}
+
+impl Block for Headset {
+    fn update(&mut self) -> Result<Option<Update>> {
+        if !self.available {
+            // Don't crash the bar, but make it obvious what is missing.
+            self.text.set_text("headsetcontrol not found".to_owned());
+            self.text.set_state(State::Critical);
+            return Ok(None);
+        }
+
+        let output = Command::new("headsetcontrol")
+            .args(&["-o", "json"])
+            .output()
+            .block_error("headset", "failed to run headsetcontrol")
+            .and_then(|raw_output| {
+                String::from_utf8(raw_output.stdout)
+                    .block_error("headset", "headsetcontrol produced non-UTF8 output")
+            })?;
+
+        let json: serde_json::value::Value = serde_json::from_str(&output)
+            .block_error("headset", "Failed to parse JSON from headsetcontrol.")?;
+
+        // headsetcontrol reports every supported device it finds; only the first one is shown.
+        let device = match json.pointer("/devices/0") {
+            Some(device) => device,
+            None => {
+                self.text.set_text("No headset".to_owned());
+                self.text.set_state(State::Idle);
+                return Ok(Some(self.update_interval.into()));
+            }
+        };
+
+        let name = device
+            .pointer("/device")
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_owned();
+        // A negative level means the battery is charging or its state is unknown.
+        let battery = device
+            .pointer("/battery/level")
+            .and_then(|v| v.as_i64())
+            .filter(|level| *level >= 0);
+        let chatmix = device.pointer("/chatmix").and_then(|v| v.as_i64());
+
+        let values = map!(
+            "{battery}" => battery.map_or_else(|| "-".to_owned(), |b| format!("{}", b)),
+            "{chatmix}" => chatmix.map_or_else(|| "-".to_owned(), |c| format!("{}", c)),
+            "{device}" => name
+        );
+
+        self.text.set_state(match battery {
+            Some(0..=9) => State::Critical,
+            Some(10..=29) => State::Warning,
+            _ => State::Idle,
+        });
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use regex::Regex;
+use serde_derive::Deserialize;
+use serde_json::json;
+use tungstenite::Message;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::http;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct HomeAssistant {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    url: String,
+    token_command: String,
+    /// The token printed by `token_command`, read again once it is rejected
+    token: Option<String>,
+    /// Attributes referenced by the format string, e.g. "temperature" for `{attr_temperature}`
+    attributes: Vec<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HomeAssistantConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "HomeAssistantConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Base URL of the Home Assistant instance, e.g. "http://homeassistant.local:8123"
+    pub url: String,
+
+    /// Command printing a long-lived access token
+    pub token_command: String,
+
+    /// Entity to display, e.g. "climate.living_room"
+    pub entity_id: String,
+
+    /// Format override
+    #[serde(default = "HomeAssistantConfig::default_format")]
+    pub format: String,
+}
+
+impl HomeAssistantConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(30)
+    }
+
+    fn default_format() -> String {
+        "{state}".to_owned()
+    }
+}
+
+impl ConfigBlock for HomeAssistant {
+    type Config = HomeAssistantConfig;
+
+    fn new(
+        block_config: Self::Config,
+        config: Config,
+        tx_update_request: Sender<Task>,
+    ) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let base_url = block_config.url.trim_end_matches('/');
+
+        // The WebSocket API is served on the same port, e.g. "ws://" for "http://".
+        let websocket_url = match base_url.strip_prefix("http") {
+            Some(rest) => format!("ws{}/api/websocket", rest),
+            None => {
+                return Err(BlockError(
+                    "home_assistant".to_owned(),
+                    "url must start with http:// or https://".to_owned(),
+                ))
+            }
+        };
+        listen_for_changes(
+            websocket_url,
+            block_config.token_command.clone(),
+            block_config.entity_id.clone(),
+            id.clone(),
+            block_config.interval,
+            tx_update_request,
+        );
+
+        let re = Regex::new(r"\{attr_([a-zA-Z0-9_]+)\}")
+            .internal_error("home_assistant", "invalid regex")?;
+        let attributes = re
+            .captures_iter(&block_config.format)
+            .map(|cap| cap[1].to_owned())
+            .collect();
+
+        Ok(HomeAssistant {
+            id,
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_text("×"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("home_assistant", "Invalid format specified")?,
+            url: format!("{}/api/states/{}", base_url, block_config.entity_id),
+            token_command: block_config.token_command,
+            token: None,
+            attributes,
+        })
+    }
+}
+
+fn run_token_command(token_command: &str) -> Result<String> {
+    let output = Command::new("sh")
+        .args(&["-c", token_command])
+        .output()
+        .block_error("home_assistant", "failed to run token_command")?;
+    if !output.status.success() {
+        return Err(BlockError(
+            "home_assistant".to_owned(),
+            "token_command exited with an error".to_owned(),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+}
+
+/// Listens for changes of `entity_id` on a thread, and requests an update of the block
+/// for each. The connection is retried every `retry_delay` if it fails.
+fn listen_for_changes(
+    url: String,
+    token_command: String,
+    entity_id: String,
+    id: String,
+    retry_delay: Duration,
+    tx_update_request: Sender<Task>,
+) {
+    thread::Builder::new()
+        .name("home_assistant".into())
+        .spawn(move || loop {
+            match subscribe(&url, &token_command, &entity_id, &id, &tx_update_request) {
+                // The bar is shutting down.
+                Ok(()) => break,
+                Err(error) => {
+                    log::warn!(target: "home_assistant", "{}", error);
+                    thread::sleep(retry_delay);
+                }
+            }
+        })
+        .unwrap();
+}
+
+/// Subscribes to the `state_changed` events over the WebSocket API. Returns once the bar
+/// no longer receives update requests, or with an error once the connection fails.
+fn subscribe(
+    url: &str,
+    token_command: &str,
+    entity_id: &str,
+    id: &str,
+    tx_update_request: &Sender<Task>,
+) -> Result<()> {
+    let (mut socket, _) = tungstenite::connect(url)
+        .block_error_context("home_assistant", "failed to connect to the WebSocket API")?;
+
+    loop {
+        let text = match socket
+            .read_message()
+            .block_error_context("home_assistant", "lost the WebSocket connection")?
+        {
+            Message::Text(text) => text,
+            Message::Close(_) => {
+                return Err(BlockError(
+                    "home_assistant".to_owned(),
+                    "the WebSocket connection was closed".to_owned(),
+                ))
+            }
+            _ => continue,
+        };
+        let message: serde_json::value::Value = serde_json::from_str(&text)
+            .block_error("home_assistant", "invalid WebSocket message")?;
+
+        let reply = match message.pointer("/type").and_then(|t| t.as_str()) {
+            Some("auth_required") => json!({
+                "type": "auth",
+                "access_token": run_token_command(token_command)?,
+            }),
+            Some("auth_ok") => json!({
+                "id": 1,
+                "type": "subscribe_events",
+                "event_type": "state_changed",
+            }),
+            Some("auth_invalid") => {
+                return Err(BlockError(
+                    "home_assistant".to_owned(),
+                    "the token was rejected by the WebSocket API".to_owned(),
+                ))
+            }
+            Some("event")
+                if message
+                    .pointer("/event/data/entity_id")
+                    .and_then(|e| e.as_str())
+                    == Some(entity_id) =>
+            {
+                let task = Task {
+                    id: id.to_owned(),
+                    update_time: Instant::now(),
+                };
+                if tx_update_request.send(task).is_err() {
+                    return Ok(());
+                }
+                continue;
+            }
+            _ => continue,
+        };
+        socket
+            .write_message(Message::Text(reply.to_string()))
+            .block_error_context("home_assistant", "lost the WebSocket connection")?;
+    }
+}
+
+impl HomeAssistant {
+    /// Returns the token, running `token_command` if it isn't known yet.
+    fn token(&mut self) -> Result<String> {
+        match self.token {
+            Some(ref token) => Ok(token.clone()),
+            None => {
+                let token = run_token_command(&self.token_command)?;
+                self.token = Some(token.clone());
+                Ok(token)
+            }
+        }
+    }
+
+    /// Fetches the state of the entity, or `None` if Home Assistant can't be reached. A
+    /// rejected token is read again, in case it was rotated, and the request retried.
+    fn fetch_state(&mut self) -> Result<Option<serde_json::value::Value>> {
+        let mut retried = false;
+        loop {
+            let fresh = self.token.is_none();
+            let authorization = format!("Authorization: Bearer {}", self.token()?);
+            let response = match http::request(&self.url, &[&authorization], Duration::from_secs(5))
+            {
+                Ok(response) => response,
+                Err(_) => return Ok(None),
+            };
+
+            match response.status {
+                401 if !fresh && !retried => {
+                    self.token = None;
+                    retried = true;
+                }
+                401 => {
+                    return Err(BlockError(
+                        "home_assistant".to_owned(),
+                        "the token was rejected".to_owned(),
+                    ))
+                }
+                200 => return Ok(serde_json::from_str(&response.body).ok()),
+                _ => return Ok(None),
+            }
+        }
+    }
+}
+
+/// Attribute values may be of any JSON type; strings are shown without quotes.
+fn json_to_string(value: &serde_json::value::Value) -> String {
+    match value.as_str() {
+        Some(s) => s.to_owned(),
+        None => value.to_string(),
+    }
+}
+
+impl Block for HomeAssistant {
+    fn update(&mut self) -> Result<Option<Update>> {
+        // Fetch failures are likely due to connectivity, so don't error out.
+        match self.fetch_state()? {
+            Some(json) => {
+                let placeholders: Vec<String> = self
+                    .attributes
+                    .iter()
+                    .map(|name| format!("{{attr_{}}}", name))
+                    .collect();
+
+                let mut values: HashMap<&str, String> = map!(
+                    "{state}" => json.pointer("/state").map(json_to_string).unwrap_or_default(),
+                    "{name}" => json
+                        .pointer("/attributes/friendly_name")
+                        .map(json_to_string)
+                        .unwrap_or_default()
+                );
+                // Attributes which the entity doesn't currently have are shown empty.
+                for (name, placeholder) in self.attributes.iter().zip(placeholders.iter()) {
+                    values.insert(
+                        placeholder.as_str(),
+                        json.pointer(&format!("/attributes/{}", name))
+                            .map(json_to_string)
+                            .unwrap_or_default(),
+                    );
+                }
+
+                self.text.set_text(self.format.render_static_str(&values)?);
+                self.text.set_state(State::Idle);
+            }
+            None => {
+                self.text.set_text("×".to_owned());
+                self.text.set_state(State::Critical);
+            }
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
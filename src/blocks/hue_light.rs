@@ -0,0 +1,182 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::http;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+// The bridge reports brightness in the range 1-254.
+const MAX_BRI: f64 = 254.0;
+
+struct LightState {
+    name: String,
+    on: bool,
+    bri: u64,
+}
+
+pub struct HueLight {
+    id: String,
+    text: ButtonWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    base_url: String,
+    brightness_step: u32,
+    on: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct HueLightConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "HueLightConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// IP address (or host name) of the Hue bridge
+    pub bridge_ip: String,
+
+    /// Username/token registered with the bridge
+    pub token: String,
+
+    /// Id of the light to display
+    pub light_id: u32,
+
+    /// Brightness increase on right click, in percent
+    #[serde(default = "HueLightConfig::default_brightness_step")]
+    pub brightness_step: u32,
+
+    /// Format override
+    #[serde(default = "HueLightConfig::default_format")]
+    pub format: String,
+}
+
+impl HueLightConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_brightness_step() -> u32 {
+        10
+    }
+
+    fn default_format() -> String {
+        "{name} {bri}%".to_owned()
+    }
+}
+
+impl ConfigBlock for HueLight {
+    type Config = HueLightConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        Ok(HueLight {
+            text: ButtonWidget::new(config, &id).with_text("×"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("hue_light", "Invalid format specified")?,
+            base_url: format!(
+                "http://{}/api/{}/lights/{}",
+                block_config.bridge_ip, block_config.token, block_config.light_id
+            ),
+            brightness_step: block_config.brightness_step,
+            on: false,
+        })
+    }
+}
+
+impl HueLight {
+    fn fetch_state(&self) -> Option<LightState> {
+        let body = http::get(&self.base_url, Duration::from_secs(3)).ok()?;
+
+        // The bridge answers errors (e.g. an unknown light) with a JSON array instead of an object.
+        let json: serde_json::value::Value = serde_json::from_str(&body).ok()?;
+        Some(LightState {
+            name: json.pointer("/name")?.as_str()?.to_owned(),
+            on: json.pointer("/state/on")?.as_bool()?,
+            bri: json
+                .pointer("/state/bri")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(MAX_BRI as u64),
+        })
+    }
+
+    fn set_state(&self, body: &str) {
+        // Failures show up as an unreachable bridge on the next update.
+        let _ = Command::new("sh")
+            .args(&[
+                "-c",
+                &format!(
+                    "curl --fail --silent -m 3 -X PUT -d '{}' '{}/state'",
+                    body, self.base_url
+                ),
+            ])
+            .output();
+    }
+}
+
+impl Block for HueLight {
+    fn update(&mut self) -> Result<Option<Update>> {
+        match self.fetch_state() {
+            Some(light) => {
+                let values = map!(
+                    "{name}" => light.name,
+                    "{on}" => if light.on { "on" } else { "off" }.to_owned(),
+                    "{bri}" => format!("{:.0}", light.bri as f64 / MAX_BRI * 100.0)
+                );
+                self.on = light.on;
+                self.text.set_text(self.format.render_static_str(&values)?);
+                self.text.set_icon(if light.on {
+                    "backlight_full"
+                } else {
+                    "backlight_empty"
+                });
+                self.text
+                    .set_state(if light.on { State::Info } else { State::Idle });
+            }
+            None => {
+                self.text.set_text("×".to_owned());
+                self.text.set_state(State::Warning);
+            }
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if e.matches_name(self.id()) {
+            match e.button {
+                MouseButton::Left => self.set_state(&format!("{{\"on\": {}}}", !self.on)),
+                MouseButton::Right => {
+                    let step = (self.brightness_step as f64 / 100.0 * MAX_BRI).round() as u32;
+                    self.set_state(&format!("{{\"on\": true, \"bri_inc\": {}}}", step));
+                }
+                _ => return Ok(()),
+            }
+            self.update()?;
+        }
+
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -6,12 +6,12 @@ use serde_derive::Deserialize;
 use uuid::Uuid;
 
 use crate::blocks::{Block, ConfigBlock, Update};
-use crate::config::{Config, LogicalDirection};
+use crate::config::Config;
 use crate::de::deserialize_duration;
 use crate::errors::*;
 use crate::input::{I3BarEvent, MouseButton};
 use crate::scheduler::Task;
-use crate::util::has_command;
+use crate::util::{handle_scroll, has_command};
 use crate::widget::I3BarWidget;
 use crate::widgets::button::ButtonWidget;
 
@@ -177,26 +177,25 @@ impl Block for Hueshift {
                             update_hue(&self.hue_shifter, self.current_temp);
                         }
                     }
-                    mb => {
-                        use LogicalDirection::*;
-                        let new_temp: u16;
-                        match self.config.scrolling.to_logical_direction(mb) {
-                            Some(Up) => {
-                                new_temp = self.current_temp + self.step;
-                                if new_temp <= self.max_temp {
-                                    update_hue(&self.hue_shifter, new_temp);
-                                    self.current_temp = new_temp;
+                    _ => {
+                        let hue_shifter = &self.hue_shifter;
+                        let current_temp = &mut self.current_temp;
+                        let (min_temp, max_temp) = (self.min_temp, self.max_temp);
+                        handle_scroll(
+                            self.config.scrolling,
+                            event,
+                            i64::from(self.step),
+                            |step| {
+                                let new_temp = i64::from(*current_temp) + step;
+                                if new_temp >= i64::from(min_temp)
+                                    && new_temp <= i64::from(max_temp)
+                                {
+                                    update_hue(hue_shifter, new_temp as u16);
+                                    *current_temp = new_temp as u16;
                                 }
-                            }
-                            Some(Down) => {
-                                new_temp = self.current_temp - self.step;
-                                if new_temp >= self.min_temp {
-                                    update_hue(&self.hue_shifter, new_temp);
-                                    self.current_temp = new_temp;
-                                }
-                            }
-                            None => {}
-                        }
+                                Ok(())
+                            },
+                        )?;
                     }
                 }
             }
@@ -11,6 +11,7 @@ use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
 use dbus::{
     arg,
     ffidisp::{Connection, ConnectionItem},
+    Message,
 };
 use regex::Regex;
 use serde_derive::Deserialize;
@@ -20,18 +21,20 @@ use crate::blocks::Update;
 use crate::blocks::{Block, ConfigBlock};
 use crate::config::Config;
 use crate::errors::*;
-use crate::input::I3BarEvent;
+use crate::input::{I3BarEvent, MouseButton};
 use crate::scheduler::Task;
 use crate::util::{xdg_config_home, FormatTemplate};
 use crate::widget::I3BarWidget;
-use crate::widgets::text::TextWidget;
+use crate::widgets::button::ButtonWidget;
 
 pub struct IBus {
     id: String,
-    text: TextWidget,
+    text: ButtonWidget,
     engine: Arc<Mutex<String>>,
     mappings: Option<BTreeMap<String, String>>,
+    engines: Option<Vec<String>>,
     format: FormatTemplate,
+    con: Connection,
 }
 
 #[derive(Deserialize, Debug, Default, Clone)]
@@ -40,6 +43,10 @@ pub struct IBusConfig {
     #[serde(default = "IBusConfig::default_mappings")]
     pub mappings: Option<BTreeMap<String, String>>,
 
+    /// Engines to cycle through on click. Defaults to all active engines.
+    #[serde(default = "IBusConfig::default_engines")]
+    pub engines: Option<Vec<String>>,
+
     #[serde(default = "IBusConfig::default_format")]
     pub format: String,
 }
@@ -49,6 +56,10 @@ impl IBusConfig {
         None
     }
 
+    fn default_engines() -> Option<Vec<String>> {
+        None
+    }
+
     fn default_format() -> String {
         "{engine}".into()
     }
@@ -113,11 +124,13 @@ impl ConfigBlock for IBus {
             .unwrap();
 
         Ok(IBus {
+            text: ButtonWidget::new(config, &id_copy).with_text("IBus"),
             id: id_copy,
-            text: TextWidget::new(config).with_text("IBus"),
             engine,
             mappings: block_config.mappings,
+            engines: block_config.engines,
             format: FormatTemplate::from_string(&block_config.format)?,
+            con: c,
         })
     }
 }
@@ -156,10 +169,69 @@ impl Block for IBus {
         vec![&self.text]
     }
 
-    // This function is called on every block for every click.
-    // TODO: Filter events by using the event.name property,
-    // and use to switch between input engines?
-    fn click(&mut self, _: &I3BarEvent) -> Result<()> {
+    // Left click switches to the next engine. The engine-changed signal takes care of
+    // updating the block.
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if e.matches_name(self.id()) && e.button == MouseButton::Left {
+            let engines = match self.engines {
+                Some(ref engines) => engines.clone(),
+                None => self.active_engines()?,
+            };
+            if engines.is_empty() {
+                return Ok(());
+            }
+
+            let current = self
+                .engine
+                .lock()
+                .block_error("ibus", "failed to acquire lock")?
+                .clone();
+            let next = match engines.iter().position(|engine| *engine == current) {
+                Some(i) => &engines[(i + 1) % engines.len()],
+                None => &engines[0],
+            };
+            self.set_global_engine(next)?;
+        }
+        Ok(())
+    }
+}
+
+impl IBus {
+    fn active_engines(&self) -> Result<Vec<String>> {
+        let msg = Message::new_method_call(
+            "org.freedesktop.IBus",
+            "/org/freedesktop/IBus",
+            "org.freedesktop.IBus",
+            "ListActiveEngines",
+        )
+        .block_error("ibus", "Failed to create D-Bus message")?;
+        let reply = self
+            .con
+            .send_with_reply_and_block(msg, 5000)
+            .block_error("ibus", "Failed to list active engines")?;
+        let descs: Vec<arg::Variant<Box<dyn arg::RefArg>>> = reply
+            .get1()
+            .block_error("ibus", "Failed to parse D-Bus message")?;
+
+        // Each element is an IBusEngineDesc, see the comment in `new` for its layout.
+        Ok(descs
+            .iter()
+            .filter_map(|desc| desc.0.as_iter()?.nth(2)?.as_str().map(String::from))
+            .collect())
+    }
+
+    fn set_global_engine(&self, engine: &str) -> Result<()> {
+        let msg = Message::new_method_call(
+            "org.freedesktop.IBus",
+            "/org/freedesktop/IBus",
+            "org.freedesktop.IBus",
+            "SetGlobalEngine",
+        )
+        .block_error("ibus", "Failed to create D-Bus message")?
+        .append1(engine);
+        self.con
+            .send_with_reply_and_block(msg, 5000)
+            .block_error("ibus", &format!("Failed to switch to engine {}", engine))?;
         Ok(())
     }
 }
@@ -0,0 +1,229 @@
+use std::process::Command;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::subprocess::spawn_child_async;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+struct Build {
+    result: String,
+    building: bool,
+    duration: Duration,
+    url: String,
+}
+
+pub struct Jenkins {
+    id: String,
+    text: ButtonWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    api_url: String,
+    auth_command: Option<String>,
+    build_url: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct JenkinsConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "JenkinsConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Base URL of the Jenkins instance
+    pub url: String,
+
+    /// Name of the job. Jobs in folders are given as "folder/job".
+    pub job: String,
+
+    /// Command printing "user:api_token" for authentication
+    #[serde(default = "JenkinsConfig::default_auth_command")]
+    pub auth_command: Option<String>,
+
+    /// Format override
+    #[serde(default = "JenkinsConfig::default_format")]
+    pub format: String,
+}
+
+impl JenkinsConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_auth_command() -> Option<String> {
+        None
+    }
+
+    fn default_format() -> String {
+        "{result}".to_owned()
+    }
+}
+
+impl ConfigBlock for Jenkins {
+    type Config = JenkinsConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let job_path: String = block_config
+            .job
+            .split('/')
+            .map(|name| format!("/job/{}", name))
+            .collect();
+
+        Ok(Jenkins {
+            text: ButtonWidget::new(config, &id).with_text("×"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("jenkins", "Invalid format specified")?,
+            api_url: format!(
+                "{}{}/lastBuild/api/json",
+                block_config.url.trim_end_matches('/'),
+                job_path
+            ),
+            auth_command: block_config.auth_command,
+            build_url: None,
+        })
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    if secs >= 3600 {
+        format!("{}h{:02}m", secs / 3600, secs % 3600 / 60)
+    } else {
+        format!("{}m{:02}s", secs / 60, secs % 60)
+    }
+}
+
+impl Jenkins {
+    fn fetch_build(&self) -> Result<Option<Build>> {
+        let mut command = Command::new("curl");
+        command.args(&["--fail", "--silent", "-m", "5"]);
+        if let Some(ref auth_command) = self.auth_command {
+            let output = Command::new("sh")
+                .args(&["-c", auth_command.as_str()])
+                .output()
+                .block_error("jenkins", "failed to run auth_command")?;
+            command
+                .arg("--user")
+                .arg(String::from_utf8_lossy(&output.stdout).trim());
+        }
+
+        let output = match command.arg(&self.api_url).output() {
+            Ok(output) => output,
+            Err(_) => return Ok(None),
+        };
+        let json: serde_json::value::Value = match serde_json::from_slice(&output.stdout) {
+            Ok(json) => json,
+            Err(_) => return Ok(None),
+        };
+
+        let building = json
+            .pointer("/building")
+            .and_then(|v| v.as_bool())
+            .unwrap_or(false);
+        // While building, Jenkins reports a duration of 0, so compute the elapsed time.
+        let duration = if building {
+            let started = json
+                .pointer("/timestamp")
+                .and_then(|v| v.as_u64())
+                .unwrap_or(0);
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_millis() as u64)
+                .unwrap_or(started);
+            Duration::from_millis(now.saturating_sub(started))
+        } else {
+            Duration::from_millis(
+                json.pointer("/duration")
+                    .and_then(|v| v.as_u64())
+                    .unwrap_or(0),
+            )
+        };
+
+        Ok(Some(Build {
+            result: json
+                .pointer("/result")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_owned(),
+            building,
+            duration,
+            url: json
+                .pointer("/url")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_owned(),
+        }))
+    }
+}
+
+impl Block for Jenkins {
+    fn update(&mut self) -> Result<Option<Update>> {
+        match self.fetch_build()? {
+            Some(build) => {
+                let values = map!(
+                    "{result}" => if build.building { "BUILDING" } else { build.result.as_str() }.to_owned(),
+                    "{building}" => if build.building { "building" } else { "" }.to_owned(),
+                    "{duration}" => format_duration(build.duration)
+                );
+                self.text.set_text(self.format.render_static_str(&values)?);
+                self.text.set_state(if build.building {
+                    State::Info
+                } else {
+                    match build.result.as_str() {
+                        "SUCCESS" => State::Good,
+                        "UNSTABLE" => State::Warning,
+                        "FAILURE" => State::Critical,
+                        _ => State::Idle,
+                    }
+                });
+                self.build_url = if build.url.is_empty() {
+                    None
+                } else {
+                    Some(build.url)
+                };
+            }
+            None => {
+                // Most likely no connectivity, try again later.
+                self.text.set_text("×".to_owned());
+                self.text.set_state(State::Warning);
+            }
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if e.matches_name(self.id()) && e.button == MouseButton::Left {
+            if let Some(ref url) = self.build_url {
+                spawn_child_async("xdg-open", &[url.as_str()])
+                    .block_error("jenkins", "could not spawn xdg-open")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
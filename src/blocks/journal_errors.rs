@@ -0,0 +1,174 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::subprocess::spawn_child_async;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+pub struct JournalErrors {
+    id: String,
+    text: ButtonWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    priority: String,
+    since: String,
+    warning: usize,
+    critical: usize,
+    terminal: String,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct JournalErrorsConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "JournalErrorsConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Lowest priority of the counted messages, as accepted by `journalctl -p`
+    #[serde(default = "JournalErrorsConfig::default_priority")]
+    pub priority: String,
+
+    /// Start of the period in which messages are counted, as accepted by `journalctl --since`
+    #[serde(default = "JournalErrorsConfig::default_since")]
+    pub since: String,
+
+    /// Number of messages from which the state is set to warning
+    #[serde(default = "JournalErrorsConfig::default_warning")]
+    pub warning: usize,
+
+    /// Number of messages from which the state is set to critical
+    #[serde(default = "JournalErrorsConfig::default_critical")]
+    pub critical: usize,
+
+    /// Terminal in which `journalctl` is opened on click
+    #[serde(default = "JournalErrorsConfig::default_terminal")]
+    pub terminal: String,
+
+    /// Format override
+    #[serde(default = "JournalErrorsConfig::default_format")]
+    pub format: String,
+}
+
+impl JournalErrorsConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_priority() -> String {
+        "err".to_owned()
+    }
+
+    fn default_since() -> String {
+        "1 hour ago".to_owned()
+    }
+
+    fn default_warning() -> usize {
+        1
+    }
+
+    fn default_critical() -> usize {
+        10
+    }
+
+    fn default_terminal() -> String {
+        "i3-sensible-terminal".to_owned()
+    }
+
+    fn default_format() -> String {
+        "{count} errors".to_owned()
+    }
+}
+
+impl ConfigBlock for JournalErrors {
+    type Config = JournalErrorsConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        Ok(JournalErrors {
+            text: ButtonWidget::new(config, &id),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("journal_errors", "Invalid format specified")?,
+            priority: block_config.priority,
+            since: block_config.since,
+            warning: block_config.warning,
+            critical: block_config.critical,
+            terminal: block_config.terminal,
+        })
+    }
+}
+
+impl Block for JournalErrors {
+    fn update(&mut self) -> Result<Option<Update>> {
+        // Each message is printed on a single line in JSON, even if it spans several lines.
+        let output = Command::new("journalctl")
+            .args(&["-p", &self.priority, "--since", &self.since])
+            .args(&["--no-pager", "-q", "-o", "json", "--output-fields=PRIORITY"])
+            .output()
+            .block_error("journal_errors", "failed to run journalctl")?;
+        if !output.status.success() {
+            return Err(BlockError(
+                "journal_errors".to_owned(),
+                format!(
+                    "journalctl failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            ));
+        }
+        let count = String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .count();
+
+        let values = map!("{count}" => count);
+        self.text.set_text(self.format.render_static_str(&values)?);
+        self.text.set_state(match count {
+            0 => State::Good,
+            c if c >= self.critical => State::Critical,
+            c if c >= self.warning => State::Warning,
+            _ => State::Idle,
+        });
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if e.matches_name(self.id()) && e.button == MouseButton::Left {
+            let mut terminal = self.terminal.split_whitespace();
+            let program = terminal
+                .next()
+                .block_error("journal_errors", "terminal is empty")?;
+            let mut args: Vec<&str> = terminal.collect();
+            args.extend_from_slice(&["-e", "journalctl", "-p", &self.priority]);
+            args.extend_from_slice(&["--since", &self.since]);
+            spawn_child_async(program, &args)
+                .block_error("journal_errors", "could not spawn terminal")?;
+        }
+
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -462,9 +462,11 @@ impl Block for KDEConnect {
         let values = map!(
             "{bat_icon}" => bat_icon.unwrap().trim().to_string(),
             "{bat_charge}" => charge.to_string(),
+            "{battery}" => charge.to_string(),
             "{bat_state}" => charging.to_string(),
             "{notif_icon}" => self.config.icons.get("notification").unwrap().trim().to_string(),
             "{notif_count}" => notif_count.to_string(),
+            "{notifications}" => notif_count.to_string(),
             // TODO
             //"{notif_text}" => notif_text,
             "{name}" => name,
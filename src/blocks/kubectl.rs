@@ -0,0 +1,187 @@
+use std::process::Command;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use regex::Regex;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+pub struct Kubectl {
+    id: String,
+    text: ButtonWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    production_regex: Option<Regex>,
+    menu_command: String,
+    tx_update_request: Sender<Task>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct KubectlConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "KubectlConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "KubectlConfig::default_format")]
+    pub format: String,
+
+    /// Contexts matching this regex are shown as a warning
+    #[serde(default = "KubectlConfig::default_production_regex")]
+    pub production_regex: Option<String>,
+
+    /// Command reading context names on stdin and printing the selected one
+    #[serde(default = "KubectlConfig::default_menu_command")]
+    pub menu_command: String,
+}
+
+impl KubectlConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_format() -> String {
+        "{context}:{namespace}".to_owned()
+    }
+
+    fn default_production_regex() -> Option<String> {
+        None
+    }
+
+    fn default_menu_command() -> String {
+        "rofi -dmenu -p context".to_owned()
+    }
+}
+
+impl ConfigBlock for Kubectl {
+    type Config = KubectlConfig;
+
+    fn new(
+        block_config: Self::Config,
+        config: Config,
+        tx_update_request: Sender<Task>,
+    ) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let production_regex = match block_config.production_regex {
+            Some(ref regex) => {
+                Some(Regex::new(regex).block_error("kubectl", "invalid production_regex")?)
+            }
+            None => None,
+        };
+
+        Ok(Kubectl {
+            text: ButtonWidget::new(config, &id).with_text("×"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("kubectl", "Invalid format specified")?,
+            production_regex,
+            menu_command: block_config.menu_command,
+            tx_update_request,
+        })
+    }
+}
+
+fn kubectl(args: &[&str]) -> Result<Option<String>> {
+    let output = Command::new("kubectl")
+        .args(args)
+        .output()
+        .block_error("kubectl", "failed to run kubectl")?;
+
+    if output.status.success() {
+        Ok(Some(
+            String::from_utf8_lossy(&output.stdout).trim().to_owned(),
+        ))
+    } else {
+        Ok(None)
+    }
+}
+
+impl Block for Kubectl {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let context = match kubectl(&["config", "current-context"])? {
+            Some(context) => context,
+            None => {
+                // No context set, or no kubeconfig at all.
+                self.text.set_text("×".to_owned());
+                self.text.set_state(State::Idle);
+                return Ok(Some(self.update_interval.into()));
+            }
+        };
+        let namespace = kubectl(&[
+            "config",
+            "view",
+            "--minify",
+            "-o",
+            "jsonpath={.contexts[0].context.namespace}",
+        ])?
+        .filter(|namespace| !namespace.is_empty())
+        .unwrap_or_else(|| "default".to_owned());
+
+        let production = self
+            .production_regex
+            .as_ref()
+            .map_or(false, |regex| regex.is_match(&context));
+        self.text.set_state(if production {
+            State::Warning
+        } else {
+            State::Idle
+        });
+
+        let values = map!(
+            "{context}" => context,
+            "{namespace}" => namespace
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if e.matches_name(self.id()) && e.button == MouseButton::Left {
+            let command = format!(
+                "context=$(kubectl config get-contexts -o name | {}) && kubectl config use-context \"$context\"",
+                self.menu_command
+            );
+            let id = self.id.clone();
+            let tx_update_request = self.tx_update_request.clone();
+
+            // The menu waits for the user, so don't block the bar while it is open.
+            thread::Builder::new()
+                .name("kubectl".into())
+                .spawn(move || {
+                    let _ = Command::new("sh").args(&["-c", &command]).status();
+                    let _ = tx_update_request.send(Task {
+                        id,
+                        update_time: Instant::now(),
+                    });
+                })
+                .unwrap();
+        }
+
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
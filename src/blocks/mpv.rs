@@ -0,0 +1,233 @@
+use std::env;
+use std::io::{BufRead, BufReader, Write};
+use std::os::unix::net::UnixStream;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use serde_json::value::Value;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+pub struct Mpv {
+    id: String,
+    text: ButtonWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    socket: String,
+    running: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct MpvConfig {
+    /// Update interval in seconds, for the playback position
+    #[serde(
+        default = "MpvConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Path of mpv's IPC socket, as set with `input-ipc-server`
+    #[serde(default = "MpvConfig::default_socket")]
+    pub socket: String,
+
+    /// Format override
+    #[serde(default = "MpvConfig::default_format")]
+    pub format: String,
+}
+
+impl MpvConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_socket() -> String {
+        "~/.config/mpv/socket".to_owned()
+    }
+
+    fn default_format() -> String {
+        "{title}".to_owned()
+    }
+}
+
+/// Opens mpv's socket, with timeouts so a hanging mpv doesn't block the bar.
+fn connect(socket: &str) -> Option<UnixStream> {
+    let stream = UnixStream::connect(socket).ok()?;
+    stream.set_read_timeout(Some(Duration::from_secs(1))).ok()?;
+    stream
+        .set_write_timeout(Some(Duration::from_secs(1)))
+        .ok()?;
+    Some(stream)
+}
+
+/// Sends `commands` to mpv and returns the data of each reply, or `None` if mpv
+/// can't be reached.
+fn request(socket: &str, commands: &[Value]) -> Option<Vec<Value>> {
+    let mut stream = connect(socket)?;
+    for (request_id, command) in commands.iter().enumerate() {
+        let message = json!({ "command": command, "request_id": request_id });
+        writeln!(stream, "{}", message).ok()?;
+    }
+
+    let mut replies = vec![Value::Null; commands.len()];
+    let mut remaining = commands.len();
+    let mut lines = BufReader::new(stream).lines();
+    while remaining > 0 {
+        let reply: Value = serde_json::from_str(&lines.next()?.ok()?).ok()?;
+        // Events are sent on the same socket, skip them.
+        if let Some(request_id) = reply.pointer("/request_id").and_then(Value::as_u64) {
+            if let Some(slot) = replies.get_mut(request_id as usize) {
+                *slot = reply.pointer("/data").cloned().unwrap_or(Value::Null);
+                remaining -= 1;
+            }
+        }
+    }
+
+    Some(replies)
+}
+
+fn format_time(seconds: f64) -> String {
+    let seconds = seconds.max(0.0) as u64;
+    if seconds >= 3600 {
+        format!(
+            "{}:{:02}:{:02}",
+            seconds / 3600,
+            seconds % 3600 / 60,
+            seconds % 60
+        )
+    } else {
+        format!("{}:{:02}", seconds / 60, seconds % 60)
+    }
+}
+
+impl ConfigBlock for Mpv {
+    type Config = MpvConfig;
+
+    fn new(
+        block_config: Self::Config,
+        config: Config,
+        tx_update_request: Sender<Task>,
+    ) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let socket = if block_config.socket.starts_with("~/") {
+            format!(
+                "{}{}",
+                env::var("HOME").unwrap_or_default(),
+                &block_config.socket[1..]
+            )
+        } else {
+            block_config.socket
+        };
+
+        // Watch for changes of the title and pause state, so they show up immediately.
+        let id_copy = id.clone();
+        let socket_copy = socket.clone();
+        thread::Builder::new()
+            .name("mpv".into())
+            .spawn(move || loop {
+                if let Ok(mut stream) = UnixStream::connect(&socket_copy) {
+                    let observe = "{\"command\": [\"observe_property\", 1, \"pause\"]}\n\
+                                   {\"command\": [\"observe_property\", 2, \"media-title\"]}\n";
+                    if stream.write_all(observe.as_bytes()).is_ok() {
+                        for line in BufReader::new(stream).lines() {
+                            match line {
+                                Ok(ref line) if line.contains("\"event\"") => {
+                                    tx_update_request
+                                        .send(Task {
+                                            id: id_copy.clone(),
+                                            update_time: Instant::now(),
+                                        })
+                                        .unwrap();
+                                }
+                                Ok(_) => {}
+                                Err(_) => break,
+                            }
+                        }
+                    }
+                }
+                // mpv isn't running (anymore), check again later.
+                thread::sleep(Duration::from_secs(5));
+            })
+            .unwrap();
+
+        Ok(Mpv {
+            text: ButtonWidget::new(config, &id).with_icon("music"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("mpv", "Invalid format specified")?,
+            socket,
+            running: false,
+        })
+    }
+}
+
+impl Block for Mpv {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let replies = request(
+            &self.socket,
+            &[
+                json!(["get_property", "media-title"]),
+                json!(["get_property", "pause"]),
+                json!(["get_property", "time-pos"]),
+                json!(["get_property", "duration"]),
+            ],
+        );
+
+        match replies {
+            Some(replies) => {
+                self.running = true;
+                let paused = replies[1].as_bool().unwrap_or(false);
+                let values = map!(
+                    "{title}" => replies[0].as_str().unwrap_or("").to_owned(),
+                    "{paused}" => if paused { "paused" } else { "" }.to_owned(),
+                    "{position}" => replies[2].as_f64().map(format_time).unwrap_or_default(),
+                    "{duration}" => replies[3].as_f64().map(format_time).unwrap_or_default()
+                );
+                self.text.set_text(self.format.render_static_str(&values)?);
+                self.text
+                    .set_state(if paused { State::Idle } else { State::Info });
+            }
+            None => self.running = false,
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.running {
+            vec![&self.text]
+        } else {
+            vec![]
+        }
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if e.matches_name(self.id()) {
+            let command = match e.button {
+                MouseButton::Left => json!(["cycle", "pause"]),
+                MouseButton::Right => json!(["stop"]),
+                _ => return Ok(()),
+            };
+            request(&self.socket, &[command]);
+            self.update()?;
+        }
+
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -17,12 +17,13 @@ use uuid::Uuid;
 
 use crate::blocks::Update;
 use crate::blocks::{Block, ConfigBlock};
-use crate::config::{Config, LogicalDirection};
+use crate::config::Config;
 use crate::de::deserialize_duration;
 use crate::errors::*;
 use crate::input::{I3BarEvent, MouseButton};
 use crate::scheduler::Task;
 use crate::subprocess::spawn_child_async;
+use crate::util::{handle_horizontal_scroll, handle_scroll};
 use crate::widget::{I3BarWidget, State};
 use crate::widgets::button::ButtonWidget;
 use crate::widgets::rotatingtext::RotatingTextWidget;
@@ -459,28 +460,26 @@ impl Block for Music {
                     }
                 }
                 _ => {
-                    let m = Message::new_method_call(
-                        self.player.as_ref().unwrap(),
-                        "/org/mpris/MediaPlayer2",
-                        "org.mpris.MediaPlayer2.Player",
-                        "Seek",
-                    )
-                    .block_error("music", "failed to create D-Bus method call")?;
-
-                    use LogicalDirection::*;
-                    match self.config.scrolling.to_logical_direction(event.button) {
-                        Some(Up) => {
-                            self.dbus_conn
-                                .send(m.append1(self.seek_step * 1000))
-                                .block_error("music", "failed to call method via D-Bus")?;
-                        }
-                        Some(Down) => {
-                            self.dbus_conn
-                                .send(m.append1(self.seek_step * -1000))
-                                .block_error("music", "failed to call method via D-Bus")?;
-                        }
-                        None => {}
-                    }
+                    let player = self.player.as_ref().unwrap();
+                    let dbus_conn = &self.dbus_conn;
+                    let seek = |offset: i64| {
+                        let m = Message::new_method_call(
+                            player,
+                            "/org/mpris/MediaPlayer2",
+                            "org.mpris.MediaPlayer2.Player",
+                            "Seek",
+                        )
+                        .block_error("music", "failed to create D-Bus method call")?;
+                        dbus_conn
+                            .send(m.append1(offset))
+                            .block_error("music", "failed to call method via D-Bus")?;
+                        Ok(())
+                    };
+
+                    // Both axes seek, scrolling right or up moves forward.
+                    let step = self.seek_step * 1000;
+                    handle_scroll(self.config.scrolling, event, step, &seek)?;
+                    handle_horizontal_scroll(self.config.scrolling, event, step, &seek)?;
                 }
             }
         }
@@ -244,6 +244,43 @@ impl ConnectionManager {
             .map(|x| NmConnection { path: x })
             .collect())
     }
+
+    pub fn saved_connections(&self, c: &Connection) -> Result<Vec<Path<'static>>> {
+        let m = Message::new_method_call(
+            "org.freedesktop.NetworkManager",
+            "/org/freedesktop/NetworkManager/Settings",
+            "org.freedesktop.NetworkManager.Settings",
+            "ListConnections",
+        )
+        .block_error("networkmanager", "Failed to create message")?;
+
+        let r = c
+            .send_with_reply_and_block(m, 1000)
+            .block_error("networkmanager", "Failed to retrieve saved connections")?;
+
+        let saved_connections: Array<Path, Iter> = r
+            .get1()
+            .block_error("networkmanager", "Failed to read saved connections")?;
+
+        Ok(saved_connections.map(|x| x.into_static()).collect())
+    }
+
+    pub fn activate_connection(&self, c: &Connection, connection: Path) -> Result<()> {
+        // Passing "/" for the device and specific object lets NetworkManager pick them itself
+        let m = Message::new_method_call(
+            "org.freedesktop.NetworkManager",
+            "/org/freedesktop/NetworkManager",
+            "org.freedesktop.NetworkManager",
+            "ActivateConnection",
+        )
+        .block_error("networkmanager", "Failed to create message")?
+        .append3(connection, Path::from("/"), Path::from("/"));
+
+        c.send_with_reply_and_block(m, 1000)
+            .block_error("networkmanager", "Failed to activate connection")?;
+
+        Ok(())
+    }
 }
 
 #[derive(Clone)]
@@ -282,6 +319,36 @@ impl<'a> NmConnection<'a> {
         Ok(id.0)
     }
 
+    fn connection_type(&self, c: &Connection) -> Result<String> {
+        let m = ConnectionManager::get(
+            c,
+            self.path.clone(),
+            "org.freedesktop.NetworkManager.Connection.Active",
+            "Type",
+        )
+        .block_error("networkmanager", "Failed to retrieve connection type")?;
+
+        let connection_type: Variant<String> = m
+            .get1()
+            .block_error("networkmanager", "Failed to read connection type")?;
+        Ok(connection_type.0)
+    }
+
+    fn settings_path(&self, c: &Connection) -> Result<Path<'static>> {
+        let m = ConnectionManager::get(
+            c,
+            self.path.clone(),
+            "org.freedesktop.NetworkManager.Connection.Active",
+            "Connection",
+        )
+        .block_error("networkmanager", "Failed to retrieve connection settings")?;
+
+        let settings: Variant<Path> = m
+            .get1()
+            .block_error("networkmanager", "Failed to read connection settings")?;
+        Ok(settings.0.into_static())
+    }
+
     fn devices(&self, c: &Connection) -> Result<Vec<NmDevice>> {
         let m = ConnectionManager::get(
             c,
@@ -453,7 +520,9 @@ pub struct NetworkManager {
     manager: ConnectionManager,
     config: Config,
     on_click: Option<String>,
+    cycle_connections: bool,
     primary_only: bool,
+    show_vpn: bool,
     max_ssid_width: usize,
     ap_format: FormatTemplate,
     device_format: FormatTemplate,
@@ -468,10 +537,18 @@ pub struct NetworkManagerConfig {
     #[serde(default = "NetworkManagerConfig::default_on_click")]
     pub on_click: Option<String>,
 
+    /// Whether a left click cycles through the saved connections, if `on_click` is not set.
+    #[serde(default = "NetworkManagerConfig::default_cycle_connections")]
+    pub cycle_connections: bool,
+
     /// Whether to only show the primary connection, or all active connections.
     #[serde(default = "NetworkManagerConfig::default_primary_only")]
     pub primary_only: bool,
 
+    /// Whether to indicate active VPN connections.
+    #[serde(default = "NetworkManagerConfig::default_show_vpn")]
+    pub show_vpn: bool,
+
     /// Max SSID width, in characters.
     #[serde(default = "NetworkManagerConfig::default_max_ssid_width")]
     pub max_ssid_width: usize,
@@ -502,10 +579,18 @@ impl NetworkManagerConfig {
         None
     }
 
+    fn default_cycle_connections() -> bool {
+        false
+    }
+
     fn default_primary_only() -> bool {
         false
     }
 
+    fn default_show_vpn() -> bool {
+        false
+    }
+
     fn default_max_ssid_width() -> usize {
         21
     }
@@ -582,7 +667,9 @@ impl ConfigBlock for NetworkManager {
             dbus_conn,
             manager,
             on_click: block_config.on_click,
+            cycle_connections: block_config.cycle_connections,
             primary_only: block_config.primary_only,
+            show_vpn: block_config.show_vpn,
             max_ssid_width: block_config.max_ssid_width,
             ap_format: FormatTemplate::from_string(&block_config.ap_format)?,
             device_format: FormatTemplate::from_string(&block_config.device_format)?,
@@ -595,6 +682,41 @@ impl ConfigBlock for NetworkManager {
     }
 }
 
+impl NetworkManager {
+    fn activate_next_connection(&self) -> Result<()> {
+        let saved = self.manager.saved_connections(&self.dbus_conn)?;
+        if saved.is_empty() {
+            return Ok(());
+        }
+
+        // Start from the beginning of the list if nothing (we know of) is active
+        let primary = self.manager.primary_connection(&self.dbus_conn).ok();
+        let current = primary
+            .as_ref()
+            .and_then(|conn| conn.settings_path(&self.dbus_conn).ok());
+        let next = match current.and_then(|cur| saved.iter().position(|x| *x == cur)) {
+            Some(i) => (i + 1) % saved.len(),
+            None => 0,
+        };
+
+        self.manager
+            .activate_connection(&self.dbus_conn, saved[next].clone())
+    }
+
+    fn active_vpn_names(&self) -> Vec<String> {
+        self.manager
+            .active_connections(&self.dbus_conn)
+            .unwrap_or_else(|_| Vec::new())
+            .into_iter()
+            .filter(|conn| match conn.connection_type(&self.dbus_conn) {
+                Ok(t) => t == "vpn" || t == "wireguard",
+                Err(_) => false,
+            })
+            .filter_map(|conn| conn.id(&self.dbus_conn).ok())
+            .collect()
+    }
+}
+
 impl Block for NetworkManager {
     fn id(&self) -> &str {
         &self.id
@@ -631,6 +753,12 @@ impl Block for NetworkManager {
                     _ => State::Idle,
                 };
 
+                let vpns = if self.show_vpn {
+                    self.active_vpn_names()
+                } else {
+                    vec![]
+                };
+
                 let connections = if self.primary_only {
                     match self.manager.primary_connection(&self.dbus_conn) {
                         Ok(conn) => vec![conn],
@@ -719,36 +847,36 @@ impl Block for NetworkManager {
                                     ("".to_string(), "".to_string())
                                 };
 
-                                let ap = if let Ok(ap) = device.active_access_point(&self.dbus_conn)
-                                {
-                                    let ssid = match ap.ssid(&self.dbus_conn) {
-                                        Ok(ssid) => {
-                                            let mut truncated = ssid.to_string();
-                                            truncated.truncate(self.max_ssid_width);
-                                            truncated
-                                        }
-                                        Err(_) => "".to_string(),
-                                    };
-                                    let strength = match ap.strength(&self.dbus_conn) {
-                                        Ok(v) => format!("{}", v).to_string(),
-                                        Err(_) => "0".to_string(),
-                                    };
-                                    let freq = match ap.frequency(&self.dbus_conn) {
-                                        Ok(v) => format!("{}", v).to_string(),
-                                        Err(_) => "0".to_string(),
-                                    };
-
-                                    let values = map!("{ssid}" => ssid,
-                                                      "{strength}" => strength,
+                                let (ap, strength) =
+                                    if let Ok(ap) = device.active_access_point(&self.dbus_conn) {
+                                        let ssid = match ap.ssid(&self.dbus_conn) {
+                                            Ok(ssid) => {
+                                                let mut truncated = ssid.to_string();
+                                                truncated.truncate(self.max_ssid_width);
+                                                truncated
+                                            }
+                                            Err(_) => "".to_string(),
+                                        };
+                                        let strength = match ap.strength(&self.dbus_conn) {
+                                            Ok(v) => format!("{}", v).to_string(),
+                                            Err(_) => "0".to_string(),
+                                        };
+                                        let freq = match ap.frequency(&self.dbus_conn) {
+                                            Ok(v) => format!("{}", v).to_string(),
+                                            Err(_) => "0".to_string(),
+                                        };
+
+                                        let values = map!("{ssid}" => ssid,
+                                                      "{strength}" => strength.clone(),
                                                       "{freq}" => freq);
-                                    if let Ok(s) = self.ap_format.render_static_str(&values) {
-                                        s
+                                        if let Ok(s) = self.ap_format.render_static_str(&values) {
+                                            (s, strength)
+                                        } else {
+                                            ("[invalid device format string]".to_string(), strength)
+                                        }
                                     } else {
-                                        "[invalid device format string]".to_string()
-                                    }
-                                } else {
-                                    "".to_string()
-                                };
+                                        ("".to_string(), "".to_string())
+                                    };
 
                                 let mut ips = "×".to_string();
                                 if let Ok(ip4config) = device.ip4config(&self.dbus_conn) {
@@ -766,6 +894,7 @@ impl Block for NetworkManager {
                                 let values = map!("{icon}" => icon,
                                                   "{typename}" => type_name,
                                                   "{ap}" => ap,
+                                                  "{strength}" => strength,
                                                   "{name}" => name.to_string(), 
                                                   "{ips}" => ips);
 
@@ -783,7 +912,12 @@ impl Block for NetworkManager {
                         };
 
                         let values = map!("{devices}" => devicevec.join(" "),
-                                          "{id}" => id);
+                                          "{id}" => id,
+                                          "{vpn}" => vpns.join(" "));
+
+                        if !vpns.is_empty() {
+                            widget.set_icon("net_vpn");
+                        }
 
                         if let Ok(s) = self.connection_format.render_static_str(&values) {
                             widget.set_text(s);
@@ -822,6 +956,8 @@ impl Block for NetworkManager {
                         let mut _cmd = Command::new(OsStr::new(&itr.next().unwrap()))
                             .args(itr)
                             .spawn();
+                    } else if self.cycle_connections {
+                        self.activate_next_connection()?;
                     }
                 }
             }
@@ -0,0 +1,92 @@
+use std::env;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct NixShell {
+    text: TextWidget,
+    id: String,
+    format: FormatTemplate,
+    hide_when_inactive: bool,
+    active: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NixShellConfig {
+    /// Format override
+    #[serde(default = "NixShellConfig::default_format")]
+    pub format: String,
+
+    /// Hide the block when not running inside a nix-shell
+    #[serde(default = "NixShellConfig::default_hide_when_inactive")]
+    pub hide_when_inactive: bool,
+}
+
+impl NixShellConfig {
+    fn default_format() -> String {
+        "{name}".to_owned()
+    }
+
+    fn default_hide_when_inactive() -> bool {
+        true
+    }
+}
+
+impl ConfigBlock for NixShell {
+    type Config = NixShellConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        Ok(NixShell {
+            id: Uuid::new_v4().to_simple().to_string(),
+            text: TextWidget::new(config),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("nix_shell", "Invalid format specified")?,
+            hide_when_inactive: block_config.hide_when_inactive,
+            active: false,
+        })
+    }
+}
+
+impl Block for NixShell {
+    fn update(&mut self) -> Result<Option<Update>> {
+        // The environment is inherited when the bar is started, so there is no need to
+        // check it more than once.
+        self.active = env::var_os("IN_NIX_SHELL").is_some();
+
+        if self.active {
+            let name = env::var("name")
+                .or_else(|_| env::var("NIX_SHELL_PACKAGES"))
+                .unwrap_or_else(|_| "nix-shell".to_owned());
+            let values = map!("{name}" => name);
+            self.text.set_text(self.format.render_static_str(&values)?);
+            self.text.set_state(State::Good);
+        } else {
+            self.text.set_text("".to_owned());
+            self.text.set_state(State::Idle);
+        }
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.active || !self.hide_when_inactive {
+            vec![&self.text]
+        } else {
+            vec![]
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
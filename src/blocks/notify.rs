@@ -1,6 +1,7 @@
+use std::process::Command;
 use std::sync::{Arc, Mutex};
 use std::thread;
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use crossbeam_channel::Sender;
 use dbus::ffidisp::stdintf::org_freedesktop_dbus::{Properties, PropertiesPropertiesChanged};
@@ -11,21 +12,39 @@ use uuid::Uuid;
 
 use crate::blocks::{Block, ConfigBlock, Update};
 use crate::config::Config;
+use crate::de::deserialize_duration;
 use crate::errors::*;
 use crate::input::{I3BarEvent, MouseButton};
 use crate::scheduler::Task;
 use crate::util::FormatTemplate;
-use crate::widget::I3BarWidget;
+use crate::widget::{I3BarWidget, State};
 use crate::widgets::button::ButtonWidget;
 
-// TODO
-// Add driver option so can choose between dunst, mako, etc.
+// Mako has no mode for "paused" as such, this is the conventional one.
+const MAKO_DND_MODE: &str = "do-not-disturb";
+
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NotifyDriver {
+    Dunst,
+    Mako,
+}
+
+impl Default for NotifyDriver {
+    fn default() -> Self {
+        NotifyDriver::Dunst
+    }
+}
 
 pub struct Notify {
     id: String,
     paused: Arc<Mutex<i64>>,
     format: FormatTemplate,
     output: ButtonWidget,
+    driver: NotifyDriver,
+    update_interval: Duration,
+    /// Connection to dunst, kept for the updates and clicks
+    dbus_conn: Option<Connection>,
 }
 
 #[derive(Deserialize, Debug, Default, Clone)]
@@ -34,6 +53,18 @@ pub struct NotifyConfig {
     /// Format string for displaying phone information.
     #[serde(default = "NotifyConfig::default_format")]
     pub format: String,
+
+    /// Which notification daemon is running
+    #[serde(default)]
+    pub driver: NotifyDriver,
+
+    /// Update interval in seconds. Mako can't notify us of changes, and dunst doesn't for
+    /// the number of waiting notifications.
+    #[serde(
+        default = "NotifyConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
 }
 
 impl NotifyConfig {
@@ -41,6 +72,43 @@ impl NotifyConfig {
         // display just the bell icon
         "".into()
     }
+
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+}
+
+fn dunst_count(c: &Connection) -> Result<u32> {
+    c.with_path(
+        "org.freedesktop.Notifications",
+        "/org/freedesktop/Notifications",
+        5000,
+    )
+    .get("org.dunstproject.cmd0", "waitingLength")
+    .block_error("notify", &"Failed to query D-Bus".to_string())
+}
+
+fn makoctl(args: &[&str]) -> Result<String> {
+    let output = Command::new("makoctl")
+        .args(args)
+        .output()
+        .block_error("notify", "Failed to run makoctl. Is mako installed?")?;
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+fn mako_paused() -> Result<bool> {
+    Ok(makoctl(&["mode"])?
+        .lines()
+        .any(|mode| mode.trim() == MAKO_DND_MODE))
+}
+
+fn mako_count() -> Result<u32> {
+    let json: serde_json::value::Value = serde_json::from_str(&makoctl(&["list"])?)
+        .block_error("notify", "Failed to parse makoctl output")?;
+    Ok(json
+        .pointer("/data/0")
+        .and_then(|v| v.as_array())
+        .map_or(0, |notifications| notifications.len() as u32))
 }
 
 impl ConfigBlock for Notify {
@@ -48,6 +116,24 @@ impl ConfigBlock for Notify {
 
     fn new(block_config: Self::Config, config: Config, send: Sender<Task>) -> Result<Self> {
         let id: String = Uuid::new_v4().to_simple().to_string();
+
+        if block_config.driver == NotifyDriver::Mako {
+            let paused = mako_paused()?;
+            return Ok(Notify {
+                id,
+                paused: Arc::new(Mutex::new(paused as i64)),
+                format: FormatTemplate::from_string(&block_config.format)?,
+                output: ButtonWidget::new(config, "notify").with_icon(if paused {
+                    "bell-slash"
+                } else {
+                    "bell"
+                }),
+                driver: block_config.driver,
+                update_interval: block_config.interval,
+                dbus_conn: None,
+            });
+        }
+
         let id1 = id.clone();
 
         let c = Connection::get_private(BusType::Session).block_error(
@@ -86,10 +172,14 @@ impl ConfigBlock for Notify {
                 loop {
                     for msg in c.incoming(1000) {
                         if let Some(signal) = PropertiesPropertiesChanged::from_message(&msg) {
-                            let value = signal.changed_properties.get("paused").unwrap();
-                            let status = &value.0.as_i64().unwrap();
-                            let mut paused = state_copy.lock().unwrap();
-                            *paused = status.clone();
+                            // Other properties may change too, e.g. with newer versions of dunst.
+                            if let Some(status) = signal
+                                .changed_properties
+                                .get("paused")
+                                .and_then(|value| value.0.as_i64())
+                            {
+                                *state_copy.lock().unwrap() = status;
+                            }
 
                             // Tell block to update now.
                             send.send(Task {
@@ -108,6 +198,9 @@ impl ConfigBlock for Notify {
             paused: state,
             format: FormatTemplate::from_string(&block_config.format)?,
             output: ButtonWidget::new(config, "notify").with_icon(icon),
+            driver: block_config.driver,
+            update_interval: block_config.interval,
+            dbus_conn: Some(c),
         })
     }
 }
@@ -118,13 +211,31 @@ impl Block for Notify {
     }
 
     fn update(&mut self) -> Result<Option<Update>> {
-        let paused = *self
-            .paused
-            .lock()
-            .block_error("notify", "failed to acquire lock for `state`")?;
+        let (paused, count) = match self.driver {
+            NotifyDriver::Dunst => {
+                let paused = *self
+                    .paused
+                    .lock()
+                    .block_error("notify", "failed to acquire lock for `state`")?;
+                let c = self
+                    .dbus_conn
+                    .as_ref()
+                    .block_error("notify", "no D-Bus connection")?;
+                (paused, dunst_count(c)?)
+            }
+            NotifyDriver::Mako => {
+                let paused = mako_paused()? as i64;
+                *self
+                    .paused
+                    .lock()
+                    .block_error("notify", "failed to acquire lock for `state`")? = paused;
+                (paused, mako_count()?)
+            }
+        };
 
         let values = map!(
-            "{state}" => paused.to_string()
+            "{state}" => paused.to_string(),
+            "{count}" => count.to_string()
         );
 
         self.output
@@ -132,8 +243,13 @@ impl Block for Notify {
 
         let icon = if paused == 1 { "bell-slash" } else { "bell" };
         self.output.set_icon(icon);
+        self.output.set_state(if paused == 1 {
+            State::Info
+        } else {
+            State::Idle
+        });
 
-        Ok(None)
+        Ok(Some(self.update_interval.into()))
     }
 
     // Returns the view of the block, comprised of widgets.
@@ -144,16 +260,21 @@ impl Block for Notify {
     fn click(&mut self, e: &I3BarEvent) -> Result<()> {
         if e.name.as_ref().map(|s| s == "notify").unwrap_or(false) && e.button == MouseButton::Left
         {
-            let c = Connection::get_private(BusType::Session).block_error(
-                "notify",
-                &"Failed to establish D-Bus connection".to_string(),
-            )?;
-
-            let p = c.with_path(
-                "org.freedesktop.Notifications",
-                "/org/freedesktop/Notifications",
-                5000,
-            );
+            if self.driver == NotifyDriver::Mako {
+                makoctl(&["mode", "-t", MAKO_DND_MODE])?;
+                self.update()?;
+                return Ok(());
+            }
+
+            let p = self
+                .dbus_conn
+                .as_ref()
+                .block_error("notify", "no D-Bus connection")?
+                .with_path(
+                    "org.freedesktop.Notifications",
+                    "/org/freedesktop/Notifications",
+                    5000,
+                );
 
             let paused = (*self
                 .paused
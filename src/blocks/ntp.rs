@@ -0,0 +1,250 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum NtpDriver {
+    Auto,
+    Chrony,
+    Systemd,
+}
+
+impl Default for NtpDriver {
+    fn default() -> Self {
+        NtpDriver::Auto
+    }
+}
+
+/// The synchronization status reported by the NTP client
+#[derive(Debug, Default)]
+struct NtpStatus {
+    synced: bool,
+    /// Offset of the system clock in milliseconds, if known
+    offset_ms: Option<f64>,
+    stratum: Option<u32>,
+}
+
+pub struct Ntp {
+    id: String,
+    text: TextWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    driver: NtpDriver,
+    offset_warning_ms: f64,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct NtpConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "NtpConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Which NTP client to query
+    #[serde(default)]
+    pub driver: NtpDriver,
+
+    /// Offset in milliseconds from which the state is set to warning
+    #[serde(default = "NtpConfig::default_offset_warning_ms")]
+    pub offset_warning_ms: f64,
+
+    /// Format override
+    #[serde(default = "NtpConfig::default_format")]
+    pub format: String,
+}
+
+impl NtpConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_offset_warning_ms() -> f64 {
+        100.0
+    }
+
+    fn default_format() -> String {
+        "{offset_ms}ms".to_owned()
+    }
+}
+
+/// Parses the output of `chronyc tracking`, e.g.
+///
+/// ```text
+/// Stratum         : 3
+/// System time     : 0.000012345 seconds fast of NTP time
+/// Leap status     : Normal
+/// ```
+fn parse_chrony(output: &str) -> NtpStatus {
+    let mut status = NtpStatus::default();
+    for line in output.lines() {
+        let (key, value) = match line.find(':') {
+            Some(pos) => (line[..pos].trim(), line[pos + 1..].trim()),
+            None => continue,
+        };
+        match key {
+            "Stratum" => status.stratum = value.parse().ok(),
+            "System time" => {
+                let mut words = value.split_whitespace();
+                let seconds = words.next().and_then(|s| s.parse::<f64>().ok());
+                // The system clock is behind the NTP time when it is "slow".
+                let sign = if words.nth(1) == Some("slow") {
+                    -1.0
+                } else {
+                    1.0
+                };
+                status.offset_ms = seconds.map(|s| sign * s * 1000.0);
+            }
+            "Leap status" => status.synced = value != "Not synchronised",
+            _ => {}
+        }
+    }
+    status
+}
+
+/// Parses the output of `timedatectl timesync-status`, e.g. "Offset: -1.263ms" and
+/// "Stratum: 2".
+fn parse_timesync_status(output: &str, status: &mut NtpStatus) {
+    for line in output.lines() {
+        let (key, value) = match line.find(':') {
+            Some(pos) => (line[..pos].trim(), line[pos + 1..].trim()),
+            None => continue,
+        };
+        match key {
+            "Offset" => {
+                let split = value
+                    .find(|c: char| c.is_alphabetic())
+                    .unwrap_or(value.len());
+                let scale = match &value[split..] {
+                    "s" => 1000.0,
+                    "ms" => 1.0,
+                    "us" | "µs" => 0.001,
+                    _ => continue,
+                };
+                status.offset_ms = value[..split].parse::<f64>().ok().map(|v| v * scale);
+            }
+            "Stratum" => status.stratum = value.parse().ok(),
+            _ => {}
+        }
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .block_error("ntp", &format!("failed to run {}", program))?;
+    if !output.status.success() {
+        return Err(BlockError(
+            "ntp".to_owned(),
+            format!(
+                "{} failed: {}",
+                program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            ),
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+impl ConfigBlock for Ntp {
+    type Config = NtpConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        // chronyc only succeeds if chronyd is running.
+        let driver = match block_config.driver {
+            NtpDriver::Auto if run("chronyc", &["tracking"]).is_ok() => NtpDriver::Chrony,
+            NtpDriver::Auto => NtpDriver::Systemd,
+            driver => driver,
+        };
+
+        Ok(Ntp {
+            id: Uuid::new_v4().to_simple().to_string(),
+            text: TextWidget::new(config).with_icon("time"),
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("ntp", "Invalid format specified")?,
+            driver,
+            offset_warning_ms: block_config.offset_warning_ms,
+        })
+    }
+}
+
+impl Ntp {
+    fn status(&self) -> Result<NtpStatus> {
+        match self.driver {
+            NtpDriver::Chrony | NtpDriver::Auto => {
+                Ok(parse_chrony(&run("chronyc", &["tracking"])?))
+            }
+            NtpDriver::Systemd => {
+                let synced = run(
+                    "timedatectl",
+                    &["show", "--property=NTPSynchronized", "--value"],
+                )?;
+                let mut status = NtpStatus {
+                    synced: synced.trim() == "yes",
+                    ..NtpStatus::default()
+                };
+                // Only systemd-timesyncd reports the offset and stratum.
+                if let Ok(output) = run("timedatectl", &["timesync-status"]) {
+                    parse_timesync_status(&output, &mut status);
+                }
+                Ok(status)
+            }
+        }
+    }
+}
+
+impl Block for Ntp {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let status = self.status()?;
+
+        let values = map!(
+            "{synced}" => if status.synced { "yes" } else { "no" }.to_owned(),
+            "{offset_ms}" => status
+                .offset_ms
+                .map(|offset| format!("{:+.1}", offset))
+                .unwrap_or_else(|| "?".to_owned()),
+            "{stratum}" => status
+                .stratum
+                .map(|stratum| stratum.to_string())
+                .unwrap_or_else(|| "?".to_owned())
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+        self.text.set_state(if !status.synced {
+            State::Critical
+        } else if status
+            .offset_ms
+            .map_or(false, |offset| offset.abs() >= self.offset_warning_ms)
+        {
+            State::Warning
+        } else {
+            State::Good
+        });
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
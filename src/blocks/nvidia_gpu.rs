@@ -7,11 +7,12 @@ use uuid::Uuid;
 
 use crate::blocks::Update;
 use crate::blocks::{Block, ConfigBlock};
-use crate::config::{Config, LogicalDirection, Scrolling};
+use crate::config::{Config, Scrolling};
 use crate::de::deserialize_duration;
 use crate::errors::*;
 use crate::input::{I3BarEvent, MouseButton};
 use crate::scheduler::Task;
+use crate::util::handle_scroll;
 use crate::widget::{I3BarWidget, Spacing, State};
 use crate::widgets::button::ButtonWidget;
 use crate::widgets::text::TextWidget;
@@ -423,20 +424,15 @@ impl Block for NvidiaGpu {
                         controlled_changed = true;
                     }
                     _ => {
-                        use LogicalDirection::*;
-                        match self.scrolling.to_logical_direction(e.button) {
-                            Some(Up) => {
-                                if self.fan_speed < 100 && self.fan_speed_controlled {
-                                    new_fan_speed += 1;
-                                }
+                        let (fan_speed, controlled) = (self.fan_speed, self.fan_speed_controlled);
+                        handle_scroll(self.scrolling, e, 1, |step| {
+                            if controlled
+                                && ((step > 0 && fan_speed < 100) || (step < 0 && fan_speed > 0))
+                            {
+                                new_fan_speed = (fan_speed as i64 + step) as u64;
                             }
-                            Some(Down) => {
-                                if self.fan_speed > 0 && self.fan_speed_controlled {
-                                    new_fan_speed -= 1;
-                                }
-                            }
-                            None => {}
-                        }
+                            Ok(())
+                        })?;
                     }
                 };
 
@@ -0,0 +1,276 @@
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::http;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct Prometheus {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    url: String,
+    metric: String,
+    labels: HashMap<String, String>,
+    include_histograms: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct PrometheusConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "PrometheusConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// URL of the metrics endpoint, e.g. "http://localhost:9100/metrics"
+    pub url: String,
+
+    /// Name of the metric to display
+    pub metric: String,
+
+    /// Only consider samples with these label values
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+
+    /// Whether samples of histograms and summaries may be displayed
+    #[serde(default)]
+    pub include_histograms: bool,
+
+    /// Format override
+    #[serde(default = "PrometheusConfig::default_format")]
+    pub format: String,
+}
+
+impl PrometheusConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_format() -> String {
+        "{value}".to_owned()
+    }
+}
+
+impl ConfigBlock for Prometheus {
+    type Config = PrometheusConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        Ok(Prometheus {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_text("×"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("prometheus", "Invalid format specified")?,
+            url: block_config.url,
+            metric: block_config.metric,
+            labels: block_config.labels,
+            include_histograms: block_config.include_histograms,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Sample {
+    name: String,
+    labels: HashMap<String, String>,
+    value: f64,
+}
+
+/// Parses the label set of a sample, e.g. `{cpu="0",mode="idle"}`, returning the
+/// labels and the remainder of the line.
+fn parse_labels(input: &str) -> Option<(HashMap<String, String>, &str)> {
+    if !input.starts_with('{') {
+        return None;
+    }
+    let mut labels = HashMap::new();
+    let mut rest = &input[1..];
+
+    loop {
+        rest = rest.trim_start_matches(&[',', ' '][..]);
+        if let Some(rest) = rest.strip_prefix('}') {
+            return Some((labels, rest));
+        }
+
+        let eq = rest.find('=')?;
+        let name = rest[..eq].trim().to_owned();
+        rest = rest[eq + 1..].trim_start();
+        if !rest.starts_with('"') {
+            return None;
+        }
+        rest = &rest[1..];
+
+        let mut value = String::new();
+        let mut chars = rest.char_indices();
+        let end = loop {
+            match chars.next()? {
+                (i, '"') => break i,
+                (_, '\\') => match chars.next()?.1 {
+                    'n' => value.push('\n'),
+                    c => value.push(c),
+                },
+                (_, c) => value.push(c),
+            }
+        };
+        labels.insert(name, value);
+        rest = &rest[end + 1..];
+    }
+}
+
+/// Parses the samples of a text exposition format document. Samples of histograms and
+/// summaries are skipped unless `include_histograms` is set.
+fn parse_samples(input: &str, include_histograms: bool) -> Vec<Sample> {
+    let mut types: HashMap<&str, &str> = HashMap::new();
+    let mut samples = Vec::new();
+
+    for line in input.lines().map(str::trim) {
+        if let Some(comment) = line.strip_prefix('#') {
+            let mut words = comment.split_whitespace();
+            if let (Some("TYPE"), Some(name), Some(typ)) =
+                (words.next(), words.next(), words.next())
+            {
+                types.insert(name, typ);
+            }
+            continue;
+        }
+        if line.is_empty() {
+            continue;
+        }
+
+        let name_end = line
+            .find(|c: char| c == '{' || c.is_whitespace())
+            .unwrap_or(line.len());
+        let name = &line[..name_end];
+        let (labels, rest) = if line[name_end..].starts_with('{') {
+            match parse_labels(&line[name_end..]) {
+                Some(parsed) => parsed,
+                None => continue,
+            }
+        } else {
+            (HashMap::new(), &line[name_end..])
+        };
+        // The value may be followed by a timestamp.
+        let value = match rest.split_whitespace().next().map(str::parse::<f64>) {
+            Some(Ok(value)) => value,
+            _ => continue,
+        };
+
+        if !include_histograms {
+            let family = ["_bucket", "_sum", "_count"]
+                .iter()
+                .filter(|suffix| name.ends_with(*suffix))
+                .map(|suffix| &name[..name.len() - suffix.len()])
+                .next()
+                .filter(|family| types.contains_key(family))
+                .unwrap_or(name);
+            match types.get(family) {
+                Some(&"histogram") | Some(&"summary") => continue,
+                _ => (),
+            }
+        }
+
+        samples.push(Sample {
+            name: name.to_owned(),
+            labels,
+            value,
+        });
+    }
+
+    samples
+}
+
+impl Prometheus {
+    fn fetch_value(&self) -> Option<f64> {
+        let body = http::get(&self.url, Duration::from_secs(5)).ok()?;
+
+        parse_samples(&body, self.include_histograms)
+            .into_iter()
+            .find(|sample| {
+                sample.name == self.metric
+                    && self
+                        .labels
+                        .iter()
+                        .all(|(k, v)| sample.labels.get(k) == Some(v))
+            })
+            .map(|sample| sample.value)
+    }
+}
+
+impl Block for Prometheus {
+    fn update(&mut self) -> Result<Option<Update>> {
+        match self.fetch_value() {
+            Some(value) => {
+                let values = map!("{value}" => value);
+                self.text.set_text(self.format.render_static_str(&values)?);
+                self.text.set_state(State::Idle);
+            }
+            None => {
+                // Either the endpoint is unreachable or the metric is missing.
+                self.text.set_text("×".to_owned());
+                self.text.set_state(State::Warning);
+            }
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocks::prometheus::parse_samples;
+
+    const METRICS: &str = r#"# HELP node_load1 1m load average.
+# TYPE node_load1 gauge
+node_load1 0.42
+# TYPE node_cpu_seconds_total counter
+node_cpu_seconds_total{cpu="0",mode="idle"} 1234.5
+node_cpu_seconds_total{cpu="0",mode="user"} 67.25 1600000000000
+# TYPE http_request_duration_seconds histogram
+http_request_duration_seconds_bucket{le="0.1"} 3
+http_request_duration_seconds_sum 0.9
+http_request_duration_seconds_count 7
+escaped{path="C:\\dir",quote="\"q\""} 1
+"#;
+
+    #[test]
+    fn test_parse_samples() {
+        let samples = parse_samples(METRICS, false);
+        assert_eq!(samples.len(), 4);
+        assert_eq!(samples[0].name, "node_load1");
+        assert_eq!(samples[0].value, 0.42);
+        assert!(samples[0].labels.is_empty());
+        assert_eq!(samples[2].labels.get("mode").unwrap(), "user");
+        assert_eq!(samples[2].value, 67.25);
+        assert_eq!(samples[3].labels.get("path").unwrap(), "C:\\dir");
+        assert_eq!(samples[3].labels.get("quote").unwrap(), "\"q\"");
+    }
+
+    #[test]
+    fn test_parse_samples_histograms() {
+        let samples = parse_samples(METRICS, true);
+        assert_eq!(samples.len(), 7);
+        assert_eq!(samples[5].name, "http_request_duration_seconds_count");
+        assert_eq!(samples[5].value, 7.0);
+    }
+}
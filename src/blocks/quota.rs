@@ -0,0 +1,217 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+/// The block usage of a file system with a quota, in KiB
+#[derive(Debug)]
+struct QuotaUsage {
+    filesystem: String,
+    used: u64,
+    limit: u64,
+}
+
+impl QuotaUsage {
+    fn percentage(&self) -> f64 {
+        self.used as f64 / self.limit as f64 * 100.0
+    }
+}
+
+pub struct Quota {
+    id: String,
+    text: TextWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    user: Option<String>,
+    warning: f64,
+    critical: f64,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct QuotaConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "QuotaConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// User whose quota is shown, instead of the current user
+    #[serde(default = "QuotaConfig::default_user")]
+    pub user: Option<String>,
+
+    /// Percentage of the quota from which the state is set to warning
+    #[serde(default = "QuotaConfig::default_warning")]
+    pub warning: f64,
+
+    /// Percentage of the quota from which the state is set to critical
+    #[serde(default = "QuotaConfig::default_critical")]
+    pub critical: f64,
+
+    /// Format override
+    #[serde(default = "QuotaConfig::default_format")]
+    pub format: String,
+}
+
+impl QuotaConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(300)
+    }
+
+    fn default_user() -> Option<String> {
+        None
+    }
+
+    fn default_warning() -> f64 {
+        80.0
+    }
+
+    fn default_critical() -> f64 {
+        95.0
+    }
+
+    fn default_format() -> String {
+        "{used}/{limit} ({percentage}%)".to_owned()
+    }
+}
+
+/// Parses the output of `quota -u -w`, e.g.
+///
+/// ```text
+/// Disk quotas for user joe (uid 1000):
+///      Filesystem  blocks   quota   limit   grace   files   quota   limit   grace
+///       /dev/sda1  12345*   10000   20000   6days     100       0       0
+/// ```
+///
+/// The hard limit is used, or the soft limit if there is none. File systems without
+/// either are skipped.
+fn parse_quota(output: &str) -> Vec<QuotaUsage> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.split_whitespace().collect();
+            if fields.len() < 4 {
+                return None;
+            }
+            // Usage beyond the soft limit is marked with a "*".
+            let used = fields[1].trim_end_matches('*').parse().ok()?;
+            let soft: u64 = fields[2].parse().ok()?;
+            let hard: u64 = fields[3].parse().ok()?;
+            let limit = if hard > 0 { hard } else { soft };
+            if limit == 0 {
+                return None;
+            }
+            Some(QuotaUsage {
+                filesystem: fields[0].to_owned(),
+                used,
+                limit,
+            })
+        })
+        .collect()
+}
+
+/// Formats `kib` with a binary prefix, e.g. "1.5G" or "340M".
+fn format_size(kib: u64) -> String {
+    let mut size = kib as f64;
+    let mut prefixes = ["K", "M", "G", "T"].iter().peekable();
+    let mut prefix = "K";
+    while let Some(p) = prefixes.next() {
+        prefix = p;
+        if size < 1024.0 || prefixes.peek().is_none() {
+            break;
+        }
+        size /= 1024.0;
+    }
+    if size < 10.0 {
+        format!("{:.1}{}", size, prefix)
+    } else {
+        format!("{:.0}{}", size, prefix)
+    }
+}
+
+impl ConfigBlock for Quota {
+    type Config = QuotaConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        Ok(Quota {
+            id: Uuid::new_v4().to_simple().to_string(),
+            text: TextWidget::new(config).with_icon("disk_drive"),
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("quota", "Invalid format specified")?,
+            user: block_config.user,
+            warning: block_config.warning,
+            critical: block_config.critical,
+        })
+    }
+}
+
+impl Block for Quota {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let mut command = Command::new("quota");
+        command.args(&["-u", "-w"]);
+        if let Some(ref user) = self.user {
+            command.arg(user);
+        }
+        // quota exits with a failure status when a quota is exceeded, so only the
+        // output is checked.
+        let output = command
+            .output()
+            .block_error("quota", "failed to run quota")?;
+
+        // Show the file system closest to its limit.
+        let usage = parse_quota(&String::from_utf8_lossy(&output.stdout))
+            .into_iter()
+            .max_by(|a, b| {
+                a.percentage()
+                    .partial_cmp(&b.percentage())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+
+        match usage {
+            Some(usage) => {
+                let percentage = usage.percentage();
+                let values = map!(
+                    "{used}" => format_size(usage.used),
+                    "{limit}" => format_size(usage.limit),
+                    "{percentage}" => format!("{:.0}", percentage),
+                    "{filesystem}" => usage.filesystem
+                );
+                self.text.set_text(self.format.render_static_str(&values)?);
+                self.text.set_state(if percentage >= self.critical {
+                    State::Critical
+                } else if percentage >= self.warning {
+                    State::Warning
+                } else {
+                    State::Idle
+                });
+            }
+            None => {
+                self.text.set_text("no quota".to_owned());
+                self.text.set_state(State::Idle);
+            }
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -0,0 +1,314 @@
+use std::env;
+use std::process::Command;
+use std::time::Duration;
+
+use chrono::offset::Local;
+use chrono::{NaiveDate, NaiveDateTime, NaiveTime};
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+#[derive(Deserialize, Copy, Clone, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum RemindDriver {
+    Remind,
+    Calcurse,
+}
+
+impl Default for RemindDriver {
+    fn default() -> Self {
+        RemindDriver::Remind
+    }
+}
+
+pub struct Remind {
+    id: String,
+    text: TextWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    driver: RemindDriver,
+    file: String,
+    lookahead: chrono::Duration,
+    visible: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct RemindConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "RemindConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Which program to get the events from
+    #[serde(default)]
+    pub driver: RemindDriver,
+
+    /// Reminders file, only used with remind
+    #[serde(default = "RemindConfig::default_file")]
+    pub file: String,
+
+    /// How far ahead to look for events, in hours
+    #[serde(default = "RemindConfig::default_lookahead_hours")]
+    pub lookahead_hours: u32,
+
+    /// Format override
+    #[serde(default = "RemindConfig::default_format")]
+    pub format: String,
+}
+
+impl RemindConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(60)
+    }
+
+    fn default_file() -> String {
+        "~/.reminders".to_owned()
+    }
+
+    fn default_lookahead_hours() -> u32 {
+        24
+    }
+
+    fn default_format() -> String {
+        "{next_event} in {time_until}".to_owned()
+    }
+}
+
+/// Parses the simple calendar output of `remind -s -b2`, e.g.
+/// "2020/10/16 * * 60 600 Meeting". The fifth field is the time in minutes after
+/// midnight, or "*" for events without a time, which are skipped.
+fn parse_remind(output: &str) -> Vec<(NaiveDateTime, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let fields: Vec<&str> = line.splitn(6, ' ').collect();
+            if fields.len() < 6 {
+                return None;
+            }
+            let date = NaiveDate::parse_from_str(fields[0], "%Y/%m/%d").ok()?;
+            let minutes: u32 = fields[4].parse().ok()?;
+            let time = NaiveTime::from_hms_opt(minutes / 60, minutes % 60, 0)?;
+            Some((date.and_time(time), fields[5].trim().to_owned()))
+        })
+        .collect()
+}
+
+/// Parses the output of `calcurse` run with `--format-apt "%(start:%Y-%m-%d %H:%M) %m\n"`.
+/// Lines in other formats, such as the headers of the days, are skipped.
+fn parse_calcurse(output: &str) -> Vec<(NaiveDateTime, String)> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.len() < 17 || !line.is_char_boundary(16) {
+                return None;
+            }
+            let start = NaiveDateTime::parse_from_str(&line[..16], "%Y-%m-%d %H:%M").ok()?;
+            Some((start, line[16..].trim().to_owned()))
+        })
+        .collect()
+}
+
+fn format_time_until(minutes: i64) -> String {
+    if minutes >= 60 {
+        format!("{}h{:02}m", minutes / 60, minutes % 60)
+    } else {
+        format!("{}m", minutes)
+    }
+}
+
+impl ConfigBlock for Remind {
+    type Config = RemindConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        let file = if block_config.file.starts_with("~/") {
+            format!(
+                "{}{}",
+                env::var("HOME").unwrap_or_default(),
+                &block_config.file[1..]
+            )
+        } else {
+            block_config.file
+        };
+
+        Ok(Remind {
+            id: Uuid::new_v4().to_simple().to_string(),
+            text: TextWidget::new(config).with_icon("time"),
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("remind", "Invalid format specified")?,
+            driver: block_config.driver,
+            file,
+            lookahead: chrono::Duration::hours(i64::from(block_config.lookahead_hours)),
+            visible: false,
+        })
+    }
+}
+
+impl Remind {
+    fn events(&self) -> Result<Vec<(NaiveDateTime, String)>> {
+        let (program, output) = match self.driver {
+            RemindDriver::Remind => {
+                // The calendar starts at the beginning of the current week.
+                let weeks = self.lookahead.num_weeks() + 2;
+                let output = Command::new("remind")
+                    .arg(format!("-s+{}", weeks))
+                    .args(&["-b2", "-q", &self.file])
+                    .output();
+                ("remind", output)
+            }
+            RemindDriver::Calcurse => {
+                let days = self.lookahead.num_days() + 2;
+                let output = Command::new("calcurse")
+                    .arg(format!("-r{}", days))
+                    .args(&[
+                        "--format-apt",
+                        "%(start:%Y-%m-%d %H:%M) %m\\n",
+                        "--format-recur-apt",
+                        "%(start:%Y-%m-%d %H:%M) %m\\n",
+                    ])
+                    .output();
+                ("calcurse", output)
+            }
+        };
+        let output = output.block_error("remind", &format!("failed to run {}", program))?;
+        if !output.status.success() {
+            return Err(BlockError(
+                "remind".to_owned(),
+                format!(
+                    "{} failed: {}",
+                    program,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            ));
+        }
+
+        let output = String::from_utf8_lossy(&output.stdout);
+        Ok(match self.driver {
+            RemindDriver::Remind => parse_remind(&output),
+            RemindDriver::Calcurse => parse_calcurse(&output),
+        })
+    }
+}
+
+impl Block for Remind {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let now = Local::now().naive_local();
+        let next = self
+            .events()?
+            .into_iter()
+            .filter(|(start, _)| *start >= now && *start - now <= self.lookahead)
+            .min_by_key(|(start, _)| *start);
+
+        self.visible = next.is_some();
+        if let Some((start, event)) = next {
+            let minutes = (start - now).num_minutes();
+            let values = map!(
+                "{next_event}" => event,
+                "{time_until}" => format_time_until(minutes)
+            );
+            self.text.set_text(self.format.render_static_str(&values)?);
+            self.text.set_state(if minutes < 15 {
+                State::Critical
+            } else if minutes < 60 {
+                State::Warning
+            } else {
+                State::Idle
+            });
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.visible {
+            vec![&self.text]
+        } else {
+            vec![]
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::blocks::remind::{format_time_until, parse_calcurse, parse_remind};
+    use chrono::NaiveDate;
+
+    // From `remind -s+2 -b2 -q ~/.reminders`
+    const REMIND_OUTPUT: &str = "2020/10/16 * * * * Alice's birthday
+2020/10/16 * * 60 600 Team meeting
+2020/10/16 SHADE * * * 255 255 200
+2020/10/17 MOON * * * 2 -1 -1 Full moon
+2020/10/19 * * 30 1290 Call  the landlord
+";
+
+    // From `calcurse -r2 --format-apt "%(start:%Y-%m-%d %H:%M) %m\n"`
+    const CALCURSE_OUTPUT: &str = "10/16/20:
+ * Alice's birthday
+2020-10-16 10:00 Team meeting
+
+10/17/20:
+2020-10-17 21:30 Dinner
+";
+
+    #[test]
+    fn test_parse_remind() {
+        let events = parse_remind(REMIND_OUTPUT);
+        let date = |d| NaiveDate::from_ymd_opt(2020, 10, d).unwrap();
+        assert_eq!(
+            events,
+            vec![
+                (
+                    date(16).and_hms_opt(10, 0, 0).unwrap(),
+                    "Team meeting".to_owned()
+                ),
+                (
+                    date(19).and_hms_opt(21, 30, 0).unwrap(),
+                    "Call  the landlord".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_calcurse() {
+        let events = parse_calcurse(CALCURSE_OUTPUT);
+        let date = |d| NaiveDate::from_ymd_opt(2020, 10, d).unwrap();
+        assert_eq!(
+            events,
+            vec![
+                (
+                    date(16).and_hms_opt(10, 0, 0).unwrap(),
+                    "Team meeting".to_owned()
+                ),
+                (
+                    date(17).and_hms_opt(21, 30, 0).unwrap(),
+                    "Dinner".to_owned()
+                ),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_format_time_until() {
+        assert_eq!(format_time_until(5), "5m");
+        assert_eq!(format_time_until(60), "1h00m");
+        assert_eq!(format_time_until(125), "2h05m");
+    }
+}
@@ -0,0 +1,143 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use swayipc::reply::{Node, NodeType};
+use swayipc::{Connection, EventType};
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::i3ipc;
+use crate::util::FormatTemplate;
+use crate::widget::I3BarWidget;
+use crate::widgets::button::ButtonWidget;
+
+pub struct Scratchpad {
+    id: String,
+    text: ButtonWidget,
+    format: FormatTemplate,
+    hide_when_empty: bool,
+    count: Arc<AtomicUsize>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ScratchpadConfig {
+    /// Format override
+    #[serde(default = "ScratchpadConfig::default_format")]
+    pub format: String,
+
+    /// Hide the block when the scratchpad is empty
+    #[serde(default = "ScratchpadConfig::default_hide_when_empty")]
+    pub hide_when_empty: bool,
+}
+
+impl ScratchpadConfig {
+    fn default_format() -> String {
+        "{count}".to_owned()
+    }
+
+    fn default_hide_when_empty() -> bool {
+        false
+    }
+}
+
+/// Counts the windows in `node` and its descendants.
+fn count_windows(node: &Node) -> usize {
+    if node.nodes.is_empty()
+        && node.floating_nodes.is_empty()
+        && (node.node_type == NodeType::Con || node.node_type == NodeType::FloatingCon)
+    {
+        return 1;
+    }
+    node.nodes
+        .iter()
+        .chain(node.floating_nodes.iter())
+        .map(count_windows)
+        .sum()
+}
+
+/// Counts the windows on the hidden `__i3_scratch` workspace, which holds the scratchpad.
+fn scratchpad_count(conn: &mut Connection) -> Result<usize> {
+    let tree = conn
+        .get_tree()
+        .block_error("scratchpad", "failed to get the tree")?;
+
+    fn find_scratch(node: &Node) -> Option<&Node> {
+        if node.node_type == NodeType::Workspace && node.name.as_deref() == Some("__i3_scratch") {
+            return Some(node);
+        }
+        node.nodes.iter().find_map(find_scratch)
+    }
+
+    Ok(find_scratch(&tree).map_or(0, count_windows))
+}
+
+impl ConfigBlock for Scratchpad {
+    type Config = ScratchpadConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        let mut conn = i3ipc::connect("scratchpad")?;
+        let count = Arc::new(AtomicUsize::new(scratchpad_count(&mut conn)?));
+
+        // Moving a window to or from the scratchpad is a window event.
+        let count_clone = count.clone();
+        i3ipc::subscribe(
+            "scratchpad",
+            &[EventType::Window],
+            id.clone(),
+            tx,
+            move |_| match scratchpad_count(&mut conn) {
+                Ok(new_count) => count_clone.swap(new_count, Ordering::SeqCst) != new_count,
+                Err(_) => false,
+            },
+        )?;
+
+        Ok(Scratchpad {
+            text: ButtonWidget::new(config, &id),
+            id,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("scratchpad", "Invalid format specified")?,
+            hide_when_empty: block_config.hide_when_empty,
+            count,
+        })
+    }
+}
+
+impl Block for Scratchpad {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let values = map!("{count}" => self.count.load(Ordering::SeqCst));
+        self.text.set_text(self.format.render_static_str(&values)?);
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.hide_when_empty && self.count.load(Ordering::SeqCst) == 0 {
+            vec![]
+        } else {
+            vec![&self.text]
+        }
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if e.matches_name(self.id()) && e.button == MouseButton::Left {
+            i3ipc::connect("scratchpad")?
+                .run_command("scratchpad show")
+                .block_error("scratchpad", "failed to toggle the scratchpad")?;
+        }
+
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
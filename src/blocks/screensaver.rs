@@ -0,0 +1,174 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+pub struct Screensaver {
+    id: String,
+    text: ButtonWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    warning: u64,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ScreensaverConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "ScreensaverConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Format override
+    #[serde(default = "ScreensaverConfig::default_format")]
+    pub format: String,
+
+    /// Remaining time in seconds below which the state is warning
+    #[serde(default = "ScreensaverConfig::default_warning")]
+    pub warning: u64,
+}
+
+impl ScreensaverConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_format() -> String {
+        "{remaining}".to_owned()
+    }
+
+    fn default_warning() -> u64 {
+        60
+    }
+}
+
+impl ConfigBlock for Screensaver {
+    type Config = ScreensaverConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        Ok(Screensaver {
+            text: ButtonWidget::new(config, &id).with_icon("time"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("screensaver", "Invalid format specified")?,
+            warning: block_config.warning,
+        })
+    }
+}
+
+fn run(program: &str, args: &[&str]) -> Result<String> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .block_error("screensaver", &format!("failed to run {}", program))?;
+    if !output.status.success() {
+        return Err(BlockError(
+            "screensaver".to_owned(),
+            format!("{} exited with an error", program),
+        ));
+    }
+
+    String::from_utf8(output.stdout).block_error("screensaver", "non-UTF8 output")
+}
+
+/// Returns the seconds of inactivity after which the screen blanks, from the screen saver
+/// and DPMS settings in `xset q`. `None` if neither is enabled.
+fn parse_blank_timeout(xset: &str) -> Option<u64> {
+    // Example:
+    //   Screen Saver:
+    //     prefer blanking:  yes    allow exposures:  yes
+    //     timeout:  600    cycle:  600
+    //   ...
+    //   DPMS (Energy Star):
+    //     Standby: 900    Suspend: 900    Off: 900
+    //     DPMS is Enabled
+    let value_after = |key: &str| -> Option<u64> {
+        let mut words = xset.split_whitespace();
+        words.find(|word| *word == key)?;
+        words.next()?.parse().ok()
+    };
+
+    let screensaver = value_after("timeout:").filter(|&timeout| timeout > 0);
+    let dpms = if xset.contains("DPMS is Enabled") {
+        ["Standby:", "Suspend:", "Off:"]
+            .iter()
+            .filter_map(|key| value_after(key))
+            .filter(|&timeout| timeout > 0)
+            .min()
+    } else {
+        None
+    };
+
+    match (screensaver, dpms) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+fn format_remaining(seconds: u64) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+impl Block for Screensaver {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let timeout = parse_blank_timeout(&run("xset", &["q"])?);
+
+        let (remaining, state) = match timeout {
+            Some(timeout) => {
+                let idle_ms: u64 = run("xprintidle", &[])?
+                    .trim()
+                    .parse()
+                    .block_error("screensaver", "failed to parse xprintidle output")?;
+                let remaining = timeout.saturating_sub(idle_ms / 1000);
+                let state = if remaining < self.warning {
+                    State::Warning
+                } else {
+                    State::Good
+                };
+                (format_remaining(remaining), state)
+            }
+            None => ("off".to_owned(), State::Idle),
+        };
+
+        let values = map!("{remaining}" => remaining);
+        self.text.set_text(self.format.render_static_str(&values)?);
+        self.text.set_state(state);
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if e.matches_name(self.id()) && e.button == MouseButton::Left {
+            // Resets the idle time, which also postpones DPMS.
+            run("xset", &["s", "reset"])?;
+            self.update()?;
+        }
+
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
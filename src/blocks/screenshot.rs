@@ -0,0 +1,289 @@
+use std::env;
+use std::io::Write;
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::{Duration, Instant};
+
+use chrono::offset::Local;
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::SPINNER;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+enum UploadState {
+    Idle,
+    Uploading(usize),
+    Done(String),
+    Failed,
+}
+
+pub struct Screenshot {
+    id: String,
+    text: ButtonWidget,
+    capture_command: String,
+    upload_command: Option<String>,
+    save_path: PathBuf,
+    last_path: Option<String>,
+    upload: Arc<Mutex<UploadState>>,
+    tx_update_request: Sender<Task>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct ScreenshotConfig {
+    /// Command used to take the screenshot. `{path}` is replaced with the file to write.
+    #[serde(default = "ScreenshotConfig::default_capture_command")]
+    pub capture_command: String,
+
+    /// Command used to upload the last screenshot. `{path}` is replaced with the file to upload.
+    #[serde(default = "ScreenshotConfig::default_upload_command")]
+    pub upload_command: Option<String>,
+
+    /// Directory to save screenshots in
+    #[serde(default = "ScreenshotConfig::default_save_path")]
+    pub save_path: String,
+}
+
+impl ScreenshotConfig {
+    fn default_capture_command() -> String {
+        if env::var("WAYLAND_DISPLAY").is_ok() {
+            "grim {path}".to_owned()
+        } else {
+            "scrot {path}".to_owned()
+        }
+    }
+
+    fn default_upload_command() -> Option<String> {
+        None
+    }
+
+    fn default_save_path() -> String {
+        format!(
+            "{}/Pictures",
+            env::var("HOME").unwrap_or_else(|_| "".to_string())
+        )
+    }
+}
+
+impl ConfigBlock for Screenshot {
+    type Config = ScreenshotConfig;
+
+    fn new(
+        block_config: Self::Config,
+        config: Config,
+        tx_update_request: Sender<Task>,
+    ) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        Ok(Screenshot {
+            text: ButtonWidget::new(config, &id).with_icon("screenshot"),
+            id,
+            capture_command: block_config.capture_command,
+            upload_command: block_config.upload_command,
+            save_path: PathBuf::from(block_config.save_path),
+            last_path: None,
+            upload: Arc::new(Mutex::new(UploadState::Idle)),
+            tx_update_request,
+        })
+    }
+}
+
+impl Screenshot {
+    fn capture(&mut self) -> Result<()> {
+        let file_name = format!("screenshot-{}.png", Local::now().format("%Y%m%d-%H%M%S"));
+        let path = self
+            .save_path
+            .join(file_name)
+            .to_string_lossy()
+            .into_owned();
+
+        let status = Command::new("sh")
+            .args(&["-c", &self.capture_command.replace("{path}", &path)])
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .status()
+            .block_error("screenshot", "failed to run capture command")?;
+
+        if status.success() {
+            self.last_path = Some(path);
+            *self
+                .upload
+                .lock()
+                .block_error("screenshot", "mutex poisoned")? = UploadState::Idle;
+        }
+
+        Ok(())
+    }
+
+    fn copy_path(&self) -> Result<()> {
+        let path = match self.last_path {
+            Some(ref path) => path,
+            None => return Ok(()),
+        };
+
+        let mut command = if env::var("WAYLAND_DISPLAY").is_ok() {
+            Command::new("wl-copy")
+        } else {
+            let mut c = Command::new("xclip");
+            c.args(&["-selection", "clipboard"]);
+            c
+        };
+        let mut child = command
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .spawn()
+            .block_error("screenshot", "failed to copy path to the clipboard")?;
+        child
+            .stdin
+            .take()
+            .block_error("screenshot", "failed to pipe path to the clipboard")?
+            .write_all(path.as_bytes())
+            .block_error("screenshot", "failed to copy path to the clipboard")?;
+        child
+            .wait()
+            .block_error("screenshot", "failed to copy path to the clipboard")?;
+
+        Ok(())
+    }
+
+    fn start_upload(&mut self) -> Result<()> {
+        let cmd = match (&self.upload_command, &self.last_path) {
+            (Some(cmd), Some(path)) => cmd.replace("{path}", path),
+            _ => return Ok(()),
+        };
+
+        {
+            let mut upload = self
+                .upload
+                .lock()
+                .block_error("screenshot", "mutex poisoned")?;
+            if let UploadState::Uploading(_) = *upload {
+                return Ok(());
+            }
+            *upload = UploadState::Uploading(0);
+        }
+
+        let upload = self.upload.clone();
+        let id = self.id.clone();
+        let tx_update_request = self.tx_update_request.clone();
+
+        // The upload runs in the background, waking the block up regularly to animate the spinner.
+        thread::Builder::new()
+            .name("screenshot".into())
+            .spawn(move || {
+                let mut child = match Command::new("sh")
+                    .args(&["-c", &cmd])
+                    .stdin(Stdio::null())
+                    .stdout(Stdio::piped())
+                    .spawn()
+                {
+                    Ok(child) => child,
+                    Err(_) => {
+                        *upload.lock().unwrap() = UploadState::Failed;
+                        tx_update_request
+                            .send(Task {
+                                id,
+                                update_time: Instant::now(),
+                            })
+                            .unwrap();
+                        return;
+                    }
+                };
+
+                while let Ok(None) = child.try_wait() {
+                    if let UploadState::Uploading(ref mut frame) = *upload.lock().unwrap() {
+                        *frame = (*frame + 1) % SPINNER.len();
+                    }
+                    tx_update_request
+                        .send(Task {
+                            id: id.clone(),
+                            update_time: Instant::now(),
+                        })
+                        .unwrap();
+                    thread::sleep(Duration::from_millis(100));
+                }
+
+                *upload.lock().unwrap() = match child.wait_with_output() {
+                    Ok(ref output) if output.status.success() => UploadState::Done(
+                        String::from_utf8_lossy(&output.stdout)
+                            .lines()
+                            .next()
+                            .unwrap_or("")
+                            .trim()
+                            .to_owned(),
+                    ),
+                    _ => UploadState::Failed,
+                };
+                tx_update_request
+                    .send(Task {
+                        id,
+                        update_time: Instant::now(),
+                    })
+                    .unwrap();
+            })
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+impl Block for Screenshot {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let upload = self
+            .upload
+            .lock()
+            .block_error("screenshot", "mutex poisoned")?;
+
+        match *upload {
+            UploadState::Idle => {
+                self.text.set_text("");
+                self.text.set_state(State::Idle);
+            }
+            UploadState::Uploading(frame) => {
+                self.text.set_text(SPINNER[frame].to_string());
+                self.text.set_state(State::Info);
+            }
+            UploadState::Done(ref url) => {
+                self.text.set_text(url.clone());
+                self.text.set_state(State::Good);
+            }
+            UploadState::Failed => {
+                self.text.set_text("upload failed");
+                self.text.set_state(State::Critical);
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if e.matches_name(self.id()) {
+            match e.button {
+                MouseButton::Left => self.capture()?,
+                MouseButton::Right => self.copy_path()?,
+                MouseButton::Middle => self.start_upload()?,
+                _ => (),
+            }
+            self.update()?;
+        }
+
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -1,79 +1,265 @@
 #[cfg(feature = "pulseaudio")]
 use {
     crate::pulse::callbacks::ListResult,
+    crate::pulse::channelmap::Map,
     crate::pulse::context::{
-        flags, introspect::ServerInfo, introspect::SinkInfo, introspect::SourceInfo,
-        subscribe::subscription_masks, subscribe::Facility,
-        subscribe::Operation as SubscribeOperation, Context, State as PulseState,
+        flags, introspect::ServerInfo, introspect::SinkInfo, introspect::SinkInputInfo,
+        introspect::SourceInfo, introspect::SourceOutputInfo, subscribe::subscription_masks,
+        subscribe::Facility, subscribe::Operation as SubscribeOperation, Context,
+        State as PulseState,
     },
+    crate::pulse::def::BufferAttr,
     crate::pulse::mainloop::standard::IterateResult,
     crate::pulse::mainloop::standard::Mainloop,
     crate::pulse::proplist::{properties, Proplist},
+    crate::pulse::sample::{Format, Spec},
+    crate::pulse::stream::{flags as stream_flags, PeekResult, Stream},
     crate::pulse::volume::{ChannelVolumes, VOLUME_MAX, VOLUME_NORM},
-    crossbeam_channel::unbounded,
+    crossbeam_channel::{unbounded, SendError},
     lazy_static::lazy_static,
     std::cell::RefCell,
-    std::collections::HashMap,
     std::convert::{TryFrom, TryInto},
     std::ops::Deref,
     std::rc::Rc,
-    std::sync::Mutex,
+    std::sync::atomic::{AtomicBool, Ordering},
+    std::thread::JoinHandle,
 };
 
 use std::cmp::{max, min};
-use std::collections::BTreeMap;
-use std::io::Read;
-use std::process::{Command, Stdio};
+use std::collections::{BTreeMap, HashMap};
+use std::fs::read_to_string;
+use std::io::{BufRead, BufReader, Read};
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
 
 use crossbeam_channel::Sender;
+use dbus::ffidisp::stdintf::org_freedesktop_dbus::Properties;
+use dbus::ffidisp::{BusType, Connection};
+use dbus::Message;
 use serde_derive::Deserialize;
 use uuid::Uuid;
 
 use crate::blocks::Update;
 use crate::blocks::{Block, ConfigBlock};
-use crate::config::{Config, LogicalDirection};
+use crate::config::Config;
+use crate::dbus::DbusSignalStream;
+use crate::de::deserialize_duration;
 use crate::errors::*;
 use crate::input::{I3BarEvent, MouseButton};
 use crate::scheduler::Task;
-use crate::subprocess::spawn_child_async;
-use crate::util::{format_percent_bar, FormatTemplate};
-use crate::widget::{I3BarWidget, State};
+use crate::signals::convert_to_valid_signal;
+use crate::subprocess::spawn_child_async_with_env;
+use crate::util::{
+    format_percent_bar_colored, format_percent_bar_styled, handle_horizontal_scroll, handle_scroll,
+    BarStyle, FormatTemplate,
+};
+use crate::widget::{Align, I3BarWidget, State};
 use crate::widgets::button::ButtonWidget;
 
-trait SoundDevice {
+pub(crate) trait SoundDevice {
     fn volume(&self) -> u32;
     fn muted(&self) -> bool;
     fn output_name(&self) -> String;
+    /// Human readable description of the device, if the driver provides one.
+    fn output_description(&self) -> Option<String>;
+    /// Balance between the left (-100) and right (100) channels, if known.
+    fn balance(&self) -> Option<i32>;
 
     fn get_info(&mut self) -> Result<()>;
     fn set_volume(&mut self, step: i32, max_vol: Option<u32>) -> Result<()>;
+    fn set_balance(&mut self, balance: i32) -> Result<()>;
     fn toggle(&mut self) -> Result<()>;
     fn monitor(&mut self, id: String, tx_update_request: Sender<Task>) -> Result<()>;
+
+    /// Sets how the volumes of the channels are combined into one, for drivers reporting
+    /// the volume of each channel.
+    fn set_channel_aggregate(&mut self, _aggregate: ChannelAggregate) {}
+
+    /// Whether an application is recording from the device, if the driver knows it.
+    fn recording(&self) -> Option<bool> {
+        None
+    }
+
+    /// Index of the device, or of the controlled stream, for drivers numbering them.
+    fn index(&self) -> Option<u32> {
+        None
+    }
+
+    /// The peak level since the last update, from 0 to 1, while it is monitored.
+    fn peak_volume(&self) -> Option<f32> {
+        None
+    }
+
+    /// Starts monitoring the peak level, requesting an update when it changes.
+    fn monitor_peak(&mut self, _id: String, _tx_update_request: Sender<Task>) -> Result<()> {
+        Err(BlockError(
+            "sound".into(),
+            "showing the peak level requires the pulseaudio driver".into(),
+        ))
+    }
+
+    /// Makes the sink after the current default sink the default.
+    fn select_next_sink(&mut self) -> Result<()> {
+        Err(BlockError(
+            "sound".into(),
+            "switching sinks requires the pulseaudio driver".into(),
+        ))
+    }
+}
+
+/// How `step_width` is applied when scrolling
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum StepMode {
+    /// Change the volume by `step_width` percentage points
+    Absolute,
+    /// Change the volume by `step_width` percent of the current volume
+    Relative,
+}
+
+impl Default for StepMode {
+    fn default() -> Self {
+        StepMode::Absolute
+    }
+}
+
+/// How the volume is inserted into `{volume}`
+#[derive(Deserialize, Copy, Clone, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum VolumeScale {
+    /// An integer percentage, e.g. "65"
+    Percent,
+    /// A fraction of 1, e.g. "0.65"
+    Fraction,
+}
+
+impl Default for VolumeScale {
+    fn default() -> Self {
+        VolumeScale::Percent
+    }
+}
+
+/// How the volumes of several channels are combined into the displayed volume
+#[derive(Deserialize, Copy, Clone, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum ChannelAggregate {
+    Avg,
+    Max,
+}
+
+impl Default for ChannelAggregate {
+    fn default() -> Self {
+        ChannelAggregate::Avg
+    }
 }
 
 struct AlsaSoundDevice {
     name: String,
     device: String,
     natural_mapping: bool,
+    poll_interval: Duration,
+    monitor_debounce: Duration,
     volume: u32,
     muted: bool,
+    /// The `alsactl monitor` process, killed when the device is dropped
+    monitor: Arc<Mutex<Option<Child>>>,
 }
 
 impl AlsaSoundDevice {
-    fn new(name: String, device: String, natural_mapping: bool) -> Result<Self> {
+    fn new(
+        name: String,
+        device: String,
+        natural_mapping: bool,
+        poll_interval: Duration,
+        monitor_debounce: Duration,
+        lazy_init: bool,
+    ) -> Result<Self> {
+        let device = if device == "auto" {
+            find_alsa_card(&name)?
+        } else {
+            device
+        };
         let mut sd = AlsaSoundDevice {
             name,
             device,
             natural_mapping,
+            poll_interval,
+            monitor_debounce,
             volume: 0,
             muted: false,
+            monitor: Arc::new(Mutex::new(None)),
         };
-        sd.get_info()?;
+        // Otherwise the volume is first read by the block's first update.
+        if !lazy_init {
+            sd.get_info()?;
+        }
 
         Ok(sd)
     }
+
+    /// Builds the error for a control that doesn't exist on the device, listing the
+    /// controls that do.
+    fn missing_control_error(&self) -> Error {
+        let controls = Command::new("amixer")
+            .args(&["-D", &self.device, "scontrols"])
+            .output()
+            .map(|o| {
+                // Lines look like: Simple mixer control 'Master',0
+                String::from_utf8_lossy(&o.stdout)
+                    .lines()
+                    .filter_map(|line| line.split('\'').nth(1))
+                    .map(|control| format!("'{}'", control))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            })
+            .unwrap_or_default();
+
+        BlockError(
+            "sound".into(),
+            if controls.is_empty() {
+                format!(
+                    "ALSA control '{}' not found, and device '{}' has no controls",
+                    self.name, self.device
+                )
+            } else {
+                format!(
+                    "ALSA control '{}' not found on device '{}', available controls are: {}",
+                    self.name, self.device, controls
+                )
+            },
+        )
+    }
+}
+
+/// Finds the first sound card with the control `control`, in the order of
+/// `/proc/asound/cards`, and returns its device name, e.g. "hw:1".
+fn find_alsa_card(control: &str) -> Result<String> {
+    let cards = read_to_string("/proc/asound/cards")
+        .block_error_context("sound", "failed to list the sound cards")?;
+    // Each card takes two lines, the first starting with its number, e.g.
+    //  1 [Pebbles        ]: USB-Audio - JBL Pebbles
+    let numbers = cards
+        .lines()
+        .filter_map(|line| line.split_whitespace().next()?.parse::<u32>().ok());
+    for number in numbers {
+        let device = format!("hw:{}", number);
+        let found = Command::new("amixer")
+            .args(&["-D", &device, "sget", control])
+            .output()
+            .map(|output| output.status.success())
+            .unwrap_or(false);
+        if found {
+            log::info!(target: "sound", "using ALSA device {} for '{}'", device, control);
+            return Ok(device);
+        }
+    }
+
+    Err(BlockError(
+        "sound".into(),
+        format!("no sound card has the ALSA control '{}'", control),
+    ))
 }
 
 impl SoundDevice for AlsaSoundDevice {
@@ -86,6 +272,12 @@ impl SoundDevice for AlsaSoundDevice {
     fn output_name(&self) -> String {
         self.name.clone()
     }
+    fn output_description(&self) -> Option<String> {
+        None
+    }
+    fn balance(&self) -> Option<i32> {
+        None
+    }
 
     fn get_info(&mut self) -> Result<()> {
         let mut args = Vec::new();
@@ -97,8 +289,12 @@ impl SoundDevice for AlsaSoundDevice {
         let output = Command::new("amixer")
             .args(&args)
             .output()
-            .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_owned())
-            .block_error("sound", "could not run amixer to get sound info")?;
+            .block_error_context("sound", "could not run amixer to get sound info")?;
+        // amixer fails without output if the control doesn't exist
+        if !output.status.success() && output.stdout.is_empty() {
+            return Err(self.missing_control_error());
+        }
+        let output = String::from_utf8_lossy(&output.stdout).trim().to_owned();
 
         let last_line = &output
             .lines()
@@ -139,13 +335,18 @@ impl SoundDevice for AlsaSoundDevice {
         Command::new("amixer")
             .args(&args)
             .output()
-            .block_error("sound", "failed to set volume")?;
+            .block_error_context("sound", "failed to set volume")?;
 
         self.volume = capped_volume;
 
         Ok(())
     }
 
+    fn set_balance(&mut self, _: i32) -> Result<()> {
+        // Only supported with PulseAudio.
+        Ok(())
+    }
+
     fn toggle(&mut self) -> Result<()> {
         let mut args = Vec::new();
         if self.natural_mapping {
@@ -156,7 +357,7 @@ impl SoundDevice for AlsaSoundDevice {
         Command::new("amixer")
             .args(&args)
             .output()
-            .block_error("sound", "failed to toggle mute")?;
+            .block_error_context("sound", "failed to toggle mute")?;
 
         self.muted = !self.muted;
 
@@ -164,35 +365,70 @@ impl SoundDevice for AlsaSoundDevice {
     }
 
     fn monitor(&mut self, id: String, tx_update_request: Sender<Task>) -> Result<()> {
+        let poll_interval = self.poll_interval;
+        let monitor_debounce = self.monitor_debounce;
+
+        // Line-buffer to reduce noise.
+        let output = Command::new("stdbuf")
+            .args(&["-oL", "alsactl", "monitor"])
+            .stdout(Stdio::piped())
+            .spawn()
+            .ok()
+            .and_then(|mut child| {
+                let output = child.stdout.take();
+                *self.monitor.lock().unwrap() = Some(child);
+                output
+            });
+        let child = Arc::clone(&self.monitor);
+
         // Monitor volume changes in a separate thread.
         thread::Builder::new()
             .name("sound_alsa".into())
             .spawn(move || {
-                // Line-buffer to reduce noise.
-                let mut monitor = Command::new("stdbuf")
-                    .args(&["-oL", "alsactl", "monitor"])
-                    .stdout(Stdio::piped())
-                    .spawn()
-                    .expect("Failed to start alsactl monitor")
-                    .stdout
-                    .expect("Failed to pipe alsactl monitor output");
-
-                let mut buffer = [0; 1024]; // Should be more than enough.
-                loop {
-                    // Block until we get some output. Doesn't really matter what
-                    // the output actually is -- these are events -- we just update
-                    // the sound information if *something* happens.
-                    if monitor.read(&mut buffer).is_ok() {
-                        tx_update_request
-                            .send(Task {
-                                id: id.clone(),
-                                update_time: Instant::now(),
-                            })
-                            .unwrap();
+                // Fails once the block is gone.
+                let request_update = || {
+                    tx_update_request
+                        .send(Task {
+                            id: id.clone(),
+                            update_time: Instant::now(),
+                        })
+                        .is_ok()
+                };
+
+                if let Some(mut output) = output {
+                    let mut buffer = [0; 1024]; // Should be more than enough.
+                    loop {
+                        // Block until we get some output. Doesn't really matter what
+                        // the output actually is -- these are events -- we just update
+                        // the sound information if *something* happens.
+                        match output.read(&mut buffer) {
+                            // The monitor exited
+                            Ok(0) | Err(_) => break,
+                            Ok(_) => {
+                                if !request_update() {
+                                    return;
+                                }
+                            }
+                        }
+                        // Don't update too often. The default of 1/4 second is fast
+                        // enough for volume button mashing but slow enough to skip
+                        // event spam.
+                        thread::sleep(monitor_debounce)
+                    }
+
+                    // The monitor was killed because the device was dropped.
+                    if child.lock().unwrap().is_none() {
+                        return;
                     }
-                    // Don't update too often. Wait 1/4 second, fast enough for
-                    // volume button mashing but slow enough to skip event spam.
-                    thread::sleep(Duration::new(0, 250_000_000))
+                }
+
+                log::warn!(
+                    target: "sound",
+                    "`stdbuf -oL alsactl monitor` is not running, polling every {:?} instead",
+                    poll_interval
+                );
+                while request_update() {
+                    thread::sleep(poll_interval);
                 }
             })
             .unwrap();
@@ -201,6 +437,414 @@ impl SoundDevice for AlsaSoundDevice {
     }
 }
 
+impl Drop for AlsaSoundDevice {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.monitor.lock().unwrap().take() {
+            // It may have exited on its own already.
+            child.kill().ok();
+            child.wait().ok();
+        }
+    }
+}
+
+/// Controls PulseAudio (or PipeWire's PulseAudio server) through the `pactl` command,
+/// for setups built without the `pulseaudio` feature.
+struct PactlSoundDevice {
+    kind: &'static str,
+    name: String,
+    volume: u32,
+    muted: bool,
+    /// The `pactl subscribe` process, killed when the device is dropped
+    monitor: Option<Child>,
+}
+
+impl PactlSoundDevice {
+    fn new(device_kind: DeviceKind, name: Option<String>) -> Result<Self> {
+        let (kind, default_name) = match device_kind {
+            DeviceKind::Sink => ("sink", "@DEFAULT_SINK@"),
+            DeviceKind::Source => ("source", "@DEFAULT_SOURCE@"),
+        };
+        let mut sd = PactlSoundDevice {
+            kind,
+            name: name.unwrap_or_else(|| default_name.into()),
+            volume: 0,
+            muted: false,
+            monitor: None,
+        };
+        sd.get_info()?;
+
+        Ok(sd)
+    }
+
+    /// Runs `pactl get-<kind>-<property> <name>` and returns its output.
+    fn get(&self, property: &str) -> Result<String> {
+        let output = Command::new("pactl")
+            .args(&[&format!("get-{}-{}", self.kind, property), &self.name])
+            .output()
+            .block_error_context("sound", "could not run pactl")?;
+        if !output.status.success() {
+            return Err(BlockError(
+                "sound".into(),
+                format!(
+                    "pactl failed: {}",
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            ));
+        }
+
+        String::from_utf8(output.stdout).block_error("sound", "could not parse pactl output")
+    }
+}
+
+impl SoundDevice for PactlSoundDevice {
+    fn volume(&self) -> u32 {
+        self.volume
+    }
+    fn muted(&self) -> bool {
+        self.muted
+    }
+    fn output_name(&self) -> String {
+        self.name.clone()
+    }
+    fn output_description(&self) -> Option<String> {
+        None
+    }
+    fn balance(&self) -> Option<i32> {
+        None
+    }
+
+    fn get_info(&mut self) -> Result<()> {
+        // Example: "Volume: front-left: 32768 /  50% / -18.06 dB,   front-right: ..."
+        let output = self.get("volume")?;
+        let volumes: Vec<u32> = output
+            .split_whitespace()
+            .filter(|word| word.ends_with('%'))
+            .filter_map(|word| word.trim_end_matches('%').parse().ok())
+            .collect();
+        if volumes.is_empty() {
+            return Err(BlockError(
+                "sound".into(),
+                "could not parse volume from pactl".into(),
+            ));
+        }
+        self.volume = volumes.iter().sum::<u32>() / volumes.len() as u32;
+
+        // Example: "Mute: no"
+        self.muted = self.get("mute")?.trim() == "Mute: yes";
+
+        Ok(())
+    }
+
+    fn set_volume(&mut self, step: i32, max_vol: Option<u32>) -> Result<()> {
+        let new_vol = max(0, self.volume as i32 + step) as u32;
+        let capped_volume = if let Some(vol_cap) = max_vol {
+            min(new_vol, vol_cap)
+        } else {
+            new_vol
+        };
+
+        Command::new("pactl")
+            .args(&[
+                &format!("set-{}-volume", self.kind),
+                &self.name,
+                &format!("{}%", capped_volume),
+            ])
+            .output()
+            .block_error_context("sound", "failed to set volume")?;
+
+        self.volume = capped_volume;
+
+        Ok(())
+    }
+
+    fn set_balance(&mut self, _: i32) -> Result<()> {
+        // Only supported with PulseAudio.
+        Ok(())
+    }
+
+    fn toggle(&mut self) -> Result<()> {
+        Command::new("pactl")
+            .args(&[&format!("set-{}-mute", self.kind), &self.name, "toggle"])
+            .output()
+            .block_error_context("sound", "failed to toggle mute")?;
+
+        self.muted = !self.muted;
+
+        Ok(())
+    }
+
+    fn monitor(&mut self, id: String, tx_update_request: Sender<Task>) -> Result<()> {
+        let mut child = Command::new("pactl")
+            .arg("subscribe")
+            .stdout(Stdio::piped())
+            .spawn()
+            .block_error_context("sound", "failed to run `pactl subscribe`")?;
+        let monitor = child
+            .stdout
+            .take()
+            .block_error("sound", "failed to read `pactl subscribe` output")?;
+        self.monitor = Some(child);
+
+        thread::Builder::new()
+            .name("sound_pactl".into())
+            .spawn(move || {
+                // Example: "Event 'change' on sink #0"
+                for line in BufReader::new(monitor).lines() {
+                    match line {
+                        Ok(ref line)
+                            if line.contains(" sink")
+                                || line.contains(" source")
+                                || line.contains(" server") =>
+                        {
+                            // The block is gone.
+                            if tx_update_request
+                                .send(Task {
+                                    id: id.clone(),
+                                    update_time: Instant::now(),
+                                })
+                                .is_err()
+                            {
+                                return;
+                            }
+                        }
+                        Ok(_) => {}
+                        Err(_) => break,
+                    }
+                }
+                log::warn!(
+                    target: "sound",
+                    "`pactl subscribe` exited, volume changes won't be shown anymore"
+                );
+            })
+            .unwrap();
+
+        Ok(())
+    }
+}
+
+impl Drop for PactlSoundDevice {
+    fn drop(&mut self) {
+        if let Some(mut child) = self.monitor.take() {
+            child.kill().ok();
+            child.wait().ok();
+        }
+    }
+}
+
+const MPRIS_BUS_PREFIX: &str = "org.mpris.MediaPlayer2.";
+const MPRIS_PATH: &str = "/org/mpris/MediaPlayer2";
+const MPRIS_PLAYER_INTERFACE: &str = "org.mpris.MediaPlayer2.Player";
+
+/// The volume of an MPRIS2 media player, for players handling their own output. The
+/// player counts as muted while it isn't playing.
+struct MprisSoundDevice {
+    con: Connection,
+    /// Bus name of the player, e.g. "org.mpris.MediaPlayer2.spotify"
+    player: String,
+    volume: u32,
+    playing: bool,
+}
+
+impl MprisSoundDevice {
+    /// Uses the player `name`, e.g. "spotify", or the first player on the bus.
+    fn new(name: Option<String>) -> Result<Self> {
+        let con = Connection::get_private(BusType::Session)
+            .block_error("sound", "Failed to establish D-Bus connection.")?;
+        let player = match name {
+            Some(name) if name.starts_with(MPRIS_BUS_PREFIX) => name,
+            Some(name) => format!("{}{}", MPRIS_BUS_PREFIX, name),
+            None => Self::first_player(&con)?,
+        };
+
+        let mut sd = MprisSoundDevice {
+            con,
+            player,
+            volume: 0,
+            playing: false,
+        };
+        sd.get_info()?;
+
+        Ok(sd)
+    }
+
+    fn first_player(con: &Connection) -> Result<String> {
+        let m = Message::new_method_call(
+            "org.freedesktop.DBus",
+            "/",
+            "org.freedesktop.DBus",
+            "ListNames",
+        )
+        .block_error("sound", "failed to create D-Bus method call")?;
+        let names: Vec<String> = con
+            .send_with_reply_and_block(m, 2000)
+            .block_error("sound", "failed to list D-Bus names")?
+            .get1()
+            .block_error("sound", "unexpected reply when listing D-Bus names")?;
+        names
+            .into_iter()
+            .find(|name| name.starts_with(MPRIS_BUS_PREFIX))
+            .block_error("sound", "no MPRIS player found")
+    }
+}
+
+impl SoundDevice for MprisSoundDevice {
+    fn volume(&self) -> u32 {
+        self.volume
+    }
+
+    fn muted(&self) -> bool {
+        !self.playing
+    }
+
+    fn output_name(&self) -> String {
+        self.player.trim_start_matches(MPRIS_BUS_PREFIX).to_owned()
+    }
+
+    fn output_description(&self) -> Option<String> {
+        None
+    }
+
+    fn balance(&self) -> Option<i32> {
+        None
+    }
+
+    fn get_info(&mut self) -> Result<()> {
+        let player = self.con.with_path(self.player.as_str(), MPRIS_PATH, 1000);
+        let volume: f64 = player
+            .get(MPRIS_PLAYER_INTERFACE, "Volume")
+            .block_error("sound", "failed to read the volume of the player")?;
+        let status: String = player
+            .get(MPRIS_PLAYER_INTERFACE, "PlaybackStatus")
+            .block_error("sound", "failed to read the playback status of the player")?;
+
+        self.volume = (volume.max(0.0) * 100.0).round() as u32;
+        self.playing = status == "Playing";
+
+        Ok(())
+    }
+
+    fn set_volume(&mut self, step: i32, max_vol: Option<u32>) -> Result<()> {
+        let uncapped_volume = max(0, self.volume as i32 + step) as u32;
+        let volume = match max_vol {
+            Some(vol_cap) => min(uncapped_volume, vol_cap),
+            None => uncapped_volume,
+        };
+
+        self.con
+            .with_path(self.player.as_str(), MPRIS_PATH, 1000)
+            .set(MPRIS_PLAYER_INTERFACE, "Volume", volume as f64 / 100.0)
+            .block_error("sound", "failed to set the volume of the player")?;
+        self.volume = volume;
+
+        Ok(())
+    }
+
+    fn set_balance(&mut self, _: i32) -> Result<()> {
+        // MPRIS players have no balance.
+        Ok(())
+    }
+
+    fn toggle(&mut self) -> Result<()> {
+        let m = Message::new_method_call(
+            self.player.as_str(),
+            MPRIS_PATH,
+            MPRIS_PLAYER_INTERFACE,
+            "PlayPause",
+        )
+        .block_error("sound", "failed to create D-Bus method call")?;
+        self.con
+            .send(m)
+            .block_error("sound", "failed to call method via D-Bus")?;
+        self.playing = !self.playing;
+
+        Ok(())
+    }
+
+    fn monitor(&mut self, id: String, tx_update_request: Sender<Task>) -> Result<()> {
+        // The first argument of PropertiesChanged is the interface of the changed properties.
+        DbusSignalStream::new(
+            "sound",
+            dbus::channel::BusType::Session,
+            id,
+            tx_update_request,
+        )?
+        .on_signal(
+            Some(MPRIS_PATH),
+            "org.freedesktop.DBus.Properties",
+            "PropertiesChanged",
+            |(interface,): (String,)| interface == MPRIS_PLAYER_INTERFACE,
+        )?
+        .start();
+
+        Ok(())
+    }
+}
+
+/// A device without any hardware behind it, whose state is only kept in memory
+#[cfg(test)]
+struct MockSoundDevice {
+    volume: u32,
+    muted: bool,
+    balance: i32,
+}
+
+#[cfg(test)]
+impl MockSoundDevice {
+    fn new(volume: u32, muted: bool) -> Self {
+        MockSoundDevice {
+            volume,
+            muted,
+            balance: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+impl SoundDevice for MockSoundDevice {
+    fn volume(&self) -> u32 {
+        self.volume
+    }
+    fn muted(&self) -> bool {
+        self.muted
+    }
+    fn output_name(&self) -> String {
+        "mock".to_owned()
+    }
+    fn output_description(&self) -> Option<String> {
+        None
+    }
+    fn balance(&self) -> Option<i32> {
+        Some(self.balance)
+    }
+
+    fn get_info(&mut self) -> Result<()> {
+        Ok(())
+    }
+
+    fn set_volume(&mut self, step: i32, max_vol: Option<u32>) -> Result<()> {
+        let volume = max(0, self.volume as i32 + step) as u32;
+        self.volume = match max_vol {
+            Some(vol_cap) => min(volume, vol_cap),
+            None => volume,
+        };
+        Ok(())
+    }
+
+    fn set_balance(&mut self, balance: i32) -> Result<()> {
+        self.balance = balance.clamp(-100, 100);
+        Ok(())
+    }
+
+    fn toggle(&mut self) -> Result<()> {
+        self.muted = !self.muted;
+        Ok(())
+    }
+
+    fn monitor(&mut self, _id: String, _tx_update_request: Sender<Task>) -> Result<()> {
+        Ok(())
+    }
+}
+
 #[cfg(feature = "pulseaudio")]
 struct PulseAudioConnection {
     mainloop: Rc<RefCell<Mainloop>>,
@@ -210,23 +854,42 @@ struct PulseAudioConnection {
 #[cfg(feature = "pulseaudio")]
 struct PulseAudioClient {
     sender: Sender<PulseAudioClientRequest>,
+    /// The thread sending the requests, which exits once `sender` is dropped
+    request_thread: JoinHandle<()>,
+    /// The thread receiving the events, which exits once `stop` is set
+    subscribe_thread: JoinHandle<()>,
+    stop: Arc<AtomicBool>,
 }
 
 #[cfg(feature = "pulseaudio")]
 struct PulseAudioSoundDevice {
     name: Option<String>,
+    /// Application whose stream is controlled instead of the device
+    stream_name: Option<String>,
+    index: Option<u32>,
     device_kind: DeviceKind,
     volume: Option<ChannelVolumes>,
     volume_avg: u32,
+    channel_aggregate: ChannelAggregate,
+    channel_map: Option<Map>,
     muted: bool,
+    description: Option<String>,
+    peak_volume: Option<f32>,
+    /// The peak level, written by the thread reading it
+    peak: Option<Arc<Mutex<Option<f32>>>>,
+    /// Id of the block notified of changes, if it is monitoring the device
+    monitor_id: Option<String>,
 }
 
 #[cfg(feature = "pulseaudio")]
 #[derive(Debug)]
 struct PulseAudioVolInfo {
+    index: u32,
     volume: ChannelVolumes,
+    channel_map: Map,
     mute: bool,
     name: String,
+    description: Option<String>,
 }
 
 #[cfg(feature = "pulseaudio")]
@@ -237,9 +900,12 @@ impl TryFrom<&SourceInfo<'_>> for PulseAudioVolInfo {
         match source_info.name.as_ref() {
             None => Err(()),
             Some(name) => Ok(PulseAudioVolInfo {
+                index: source_info.index,
                 volume: source_info.volume,
+                channel_map: source_info.channel_map,
                 mute: source_info.mute,
                 name: name.to_string(),
+                description: source_info.description.as_ref().map(|d| d.to_string()),
             }),
         }
     }
@@ -253,9 +919,35 @@ impl TryFrom<&SinkInfo<'_>> for PulseAudioVolInfo {
         match sink_info.name.as_ref() {
             None => Err(()),
             Some(name) => Ok(PulseAudioVolInfo {
+                index: sink_info.index,
                 volume: sink_info.volume,
+                channel_map: sink_info.channel_map,
                 mute: sink_info.mute,
                 name: name.to_string(),
+                description: sink_info.description.as_ref().map(|d| d.to_string()),
+            }),
+        }
+    }
+}
+
+/// Sink inputs are named after the application playing them.
+#[cfg(feature = "pulseaudio")]
+impl TryFrom<&SinkInputInfo<'_>> for PulseAudioVolInfo {
+    type Error = ();
+
+    fn try_from(sink_input_info: &SinkInputInfo) -> std::result::Result<Self, Self::Error> {
+        match sink_input_info
+            .proplist
+            .get_str(properties::APPLICATION_NAME)
+        {
+            None => Err(()),
+            Some(name) => Ok(PulseAudioVolInfo {
+                index: sink_input_info.index,
+                volume: sink_input_info.volume,
+                channel_map: sink_input_info.channel_map,
+                mute: sink_input_info.mute,
+                name,
+                description: sink_input_info.name.as_ref().map(|d| d.to_string()),
             }),
         }
     }
@@ -267,13 +959,53 @@ enum PulseAudioClientRequest {
     GetDefaultDevice,
     GetInfoByIndex(DeviceKind, u32),
     GetInfoByName(DeviceKind, String),
+    GetSourceOutputList,
+    GetSinkList,
+    SetDefaultSink(String),
+    GetSinkInputByName(String),
+    SetSinkInputVolumeByIndex(u32, ChannelVolumes),
+    SetSinkInputMuteByIndex(u32, bool),
     SetVolumeByName(DeviceKind, String, ChannelVolumes),
     SetMuteByName(DeviceKind, String, bool),
 }
 
+/// The connection to PulseAudio, which is (re-)established on demand. After a failure,
+/// the next attempt is delayed, doubling the delay each time.
+#[cfg(feature = "pulseaudio")]
+struct PulseAudioClientState {
+    client: Option<PulseAudioClient>,
+    failed_attempts: u32,
+    next_attempt: Instant,
+    /// Set while a thread is connecting, without holding the lock
+    connecting: bool,
+    /// Set once the connection is lost, for the next `send` to reconnect
+    lost: bool,
+    /// Set once disconnected on shutdown, to not connect again
+    closed: bool,
+}
+
+/// How often to try connecting to PulseAudio before giving up
+#[cfg(feature = "pulseaudio")]
+const PULSEAUDIO_MAX_ATTEMPTS: u32 = 10;
+
+/// The longest delay between two attempts to connect to PulseAudio
+#[cfg(feature = "pulseaudio")]
+const PULSEAUDIO_MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// How long the subscribe thread waits for events before checking whether to stop, in ms
+#[cfg(feature = "pulseaudio")]
+const PULSEAUDIO_SUBSCRIBE_POLL_TIMEOUT: i32 = 1000;
+
 #[cfg(feature = "pulseaudio")]
 lazy_static! {
-    static ref PULSEAUDIO_CLIENT: Result<PulseAudioClient> = PulseAudioClient::new();
+    static ref PULSEAUDIO_CLIENT: Mutex<PulseAudioClientState> = Mutex::new(PulseAudioClientState {
+        client: None,
+        failed_attempts: 0,
+        next_attempt: Instant::now(),
+        connecting: false,
+        lost: false,
+        closed: false,
+    });
     static ref PULSEAUDIO_EVENT_LISTENER: Mutex<HashMap<String, Sender<Task>>> =
         Mutex::new(HashMap::new());
 
@@ -284,6 +1016,16 @@ lazy_static! {
     // State for each device
     static ref PULSEAUDIO_DEVICES: Mutex<HashMap<(DeviceKind, String), PulseAudioVolInfo>> =
         Mutex::new(HashMap::new());
+
+    // State of the streams of the applications with the given names, if they are playing
+    static ref PULSEAUDIO_SINK_INPUTS: Mutex<HashMap<String, Option<PulseAudioVolInfo>>> =
+        Mutex::new(HashMap::new());
+
+    // Names of all sinks, in the order PulseAudio lists them
+    static ref PULSEAUDIO_SINKS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+    // Indices of the sources applications are recording from
+    static ref PULSEAUDIO_RECORDED_SOURCES: Mutex<Vec<u32>> = Mutex::new(Vec::new());
 }
 
 #[cfg(feature = "pulseaudio")]
@@ -344,22 +1086,28 @@ impl PulseAudioConnection {
     }
 }
 
+#[cfg(feature = "pulseaudio")]
+impl Drop for PulseAudioConnection {
+    fn drop(&mut self) {
+        self.context.borrow_mut().disconnect();
+    }
+}
+
 #[cfg(feature = "pulseaudio")]
 impl PulseAudioClient {
     fn new() -> Result<PulseAudioClient> {
         let (send_req, recv_req) = unbounded();
         let (send_result, recv_result) = unbounded();
         let send_result2 = send_result.clone();
-        let new_connection = |sender: Sender<Result<()>>| -> PulseAudioConnection {
-            let conn = PulseAudioConnection::new();
-            match conn {
+        let new_connection = |sender: Sender<Result<()>>| -> Option<PulseAudioConnection> {
+            match PulseAudioConnection::new() {
                 Ok(conn) => {
-                    sender.send(Ok(())).unwrap();
-                    conn
+                    sender.send(Ok(())).ok();
+                    Some(conn)
                 }
                 Err(err) => {
-                    sender.send(Err(err)).unwrap();
-                    panic!("failed to create pulseaudio connection");
+                    sender.send(Err(err)).ok();
+                    None
                 }
             }
         };
@@ -374,19 +1122,24 @@ impl PulseAudioClient {
         };
 
         // requests
-        thread::Builder::new()
+        let request_thread = thread::Builder::new()
             .name("sound_pulseaudio_req".into())
             .spawn(move || {
-                let mut connection = new_connection(send_result);
+                let mut connection = match new_connection(send_result) {
+                    Some(connection) => connection,
+                    None => return,
+                };
 
                 loop {
                     // make sure mainloop dispatched everything
-                    for _ in 0..10 {
-                        connection.iterate(false).unwrap();
+                    if (0..10).try_for_each(|_| connection.iterate(false)).is_err() {
+                        log::warn!(target: "sound", "lost the pulseaudio request connection");
+                        break;
                     }
 
                     match recv_req.recv() {
-                        Err(_) => {}
+                        // The client was dropped to reconnect.
+                        Err(_) => break,
                         Ok(req) => {
                             use PulseAudioClientRequest::*;
                             let mut introspector = connection.context.borrow_mut().introspect();
@@ -420,6 +1173,73 @@ impl PulseAudioClient {
                                         PulseAudioClient::source_info_callback,
                                     );
                                 }
+                                GetSourceOutputList => {
+                                    // The list is only replaced once complete.
+                                    let mut sources = Vec::new();
+                                    introspector.get_source_output_info_list(
+                                        move |result: ListResult<&SourceOutputInfo>| match result {
+                                            ListResult::Item(info) => sources.push(info.source),
+                                            ListResult::End => {
+                                                *PULSEAUDIO_RECORDED_SOURCES.lock().unwrap() =
+                                                    std::mem::take(&mut sources);
+                                                PulseAudioClient::send_update_event();
+                                            }
+                                            ListResult::Error => {}
+                                        },
+                                    );
+                                }
+                                GetSinkList => {
+                                    let mut sinks = Vec::new();
+                                    introspector.get_sink_info_list(
+                                        move |result: ListResult<&SinkInfo>| match result {
+                                            ListResult::Item(info) => {
+                                                if let Some(name) = info.name.as_ref() {
+                                                    sinks.push(name.to_string());
+                                                }
+                                            }
+                                            ListResult::End => {
+                                                *PULSEAUDIO_SINKS.lock().unwrap() =
+                                                    std::mem::take(&mut sinks);
+                                            }
+                                            ListResult::Error => {}
+                                        },
+                                    );
+                                }
+                                SetDefaultSink(name) => {
+                                    connection
+                                        .context
+                                        .borrow_mut()
+                                        .set_default_sink(&name, |_| {});
+                                }
+                                GetSinkInputByName(name) => {
+                                    // Only the first stream of the application is used.
+                                    let mut stream = None;
+                                    introspector.get_sink_input_info_list(
+                                        move |result: ListResult<&SinkInputInfo>| match result {
+                                            ListResult::Item(info) => {
+                                                if stream.is_none() {
+                                                    stream = PulseAudioVolInfo::try_from(info)
+                                                        .ok()
+                                                        .filter(|info| info.name == name);
+                                                }
+                                            }
+                                            ListResult::End => {
+                                                PULSEAUDIO_SINK_INPUTS
+                                                    .lock()
+                                                    .unwrap()
+                                                    .insert(name.clone(), stream.take());
+                                                PulseAudioClient::send_update_event();
+                                            }
+                                            ListResult::Error => {}
+                                        },
+                                    );
+                                }
+                                SetSinkInputVolumeByIndex(index, volumes) => {
+                                    introspector.set_sink_input_volume(index, &volumes, None);
+                                }
+                                SetSinkInputMuteByIndex(index, mute) => {
+                                    introspector.set_sink_input_mute(index, mute, None);
+                                }
                                 SetVolumeByName(DeviceKind::Sink, name, volumes) => {
                                     introspector.set_sink_volume_by_name(&name, &volumes, None);
                                 }
@@ -435,8 +1255,17 @@ impl PulseAudioClient {
                             };
 
                             // send request and receive response
-                            connection.iterate(true).unwrap();
-                            connection.iterate(true).unwrap();
+                            if connection
+                                .iterate(true)
+                                .and_then(|_| connection.iterate(true))
+                                .is_err()
+                            {
+                                log::warn!(
+                                    target: "sound",
+                                    "lost the pulseaudio request connection"
+                                );
+                                break;
+                            }
                         }
                     }
                 }
@@ -445,10 +1274,15 @@ impl PulseAudioClient {
         thread_result()?;
 
         // subscribe
-        thread::Builder::new()
+        let stop = Arc::new(AtomicBool::new(false));
+        let stop_subscribe = stop.clone();
+        let subscribe_thread = thread::Builder::new()
             .name("sound_pulseaudio_sub".into())
             .spawn(move || {
-                let connection = new_connection(send_result2);
+                let connection = match new_connection(send_result2) {
+                    Some(connection) => connection,
+                    None => return,
+                };
 
                 // subcribe for events
                 connection
@@ -458,28 +1292,182 @@ impl PulseAudioClient {
                 connection.context.borrow_mut().subscribe(
                     subscription_masks::SERVER
                         | subscription_masks::SINK
-                        | subscription_masks::SOURCE,
+                        | subscription_masks::SOURCE
+                        | subscription_masks::SOURCE_OUTPUT
+                        | subscription_masks::SINK_INPUT,
                     |_| {},
                 );
 
-                connection.mainloop.borrow_mut().run().unwrap();
+                // Poll with a timeout rather than running the mainloop, so that the
+                // thread notices when it should stop.
+                while !stop_subscribe.load(Ordering::Relaxed) {
+                    let mut mainloop = connection.mainloop.borrow_mut();
+                    if mainloop
+                        .prepare(Some(PULSEAUDIO_SUBSCRIBE_POLL_TIMEOUT))
+                        .and_then(|_| mainloop.poll())
+                        .and_then(|_| mainloop.dispatch())
+                        .is_err()
+                    {
+                        log::warn!(target: "sound", "lost the pulseaudio subscribe connection");
+                        PULSEAUDIO_CLIENT.lock().unwrap().lost = true;
+                        break;
+                    }
+                }
             })
             .unwrap();
         thread_result()?;
 
-        Ok(PulseAudioClient { sender: send_req })
+        Ok(PulseAudioClient {
+            sender: send_req,
+            request_thread,
+            subscribe_thread,
+            stop,
+        })
     }
 
-    fn send(request: PulseAudioClientRequest) -> Result<()> {
-        match PULSEAUDIO_CLIENT.as_ref() {
-            Ok(client) => {
-                client.sender.send(request).unwrap();
-                Ok(())
+    /// Closes both connections and waits for their threads to exit. Called once at
+    /// shutdown, after which no new connection is made.
+    fn disconnect() {
+        let client = {
+            let mut state = PULSEAUDIO_CLIENT.lock().unwrap();
+            state.closed = true;
+            state.client.take()
+        };
+        if let Some(client) = client {
+            log::debug!(target: "sound", "disconnecting from pulseaudio");
+            client.close();
+        }
+    }
+
+    /// Stops both threads and waits for them. Must be called without holding the lock on
+    /// `PULSEAUDIO_CLIENT`, which the subscribe thread may need to finish, and never from
+    /// the subscribe thread itself.
+    fn close(self) {
+        drop(self.sender);
+        self.stop.store(true, Ordering::Relaxed);
+        if self.request_thread.join().is_err() {
+            log::warn!(target: "sound", "the pulseaudio request thread panicked");
+        }
+        if self.subscribe_thread.join().is_err() {
+            log::warn!(target: "sound", "the pulseaudio subscribe thread panicked");
+        }
+    }
+
+    /// Sends a request from `subscribe_callback`, which runs on the subscribe thread. That
+    /// thread can neither close the client, which joins it, nor connect, so a lost
+    /// connection is only marked for the next `send` to replace it.
+    fn send_from_callback(request: PulseAudioClientRequest) {
+        let mut state = PULSEAUDIO_CLIENT.lock().unwrap();
+        if let Some(ref client) = state.client {
+            if client.sender.send(request).is_err() {
+                state.lost = true;
+            }
+        }
+    }
+
+    /// Sends a request from a block, (re-)connecting first if needed.
+    fn send(mut request: PulseAudioClientRequest) -> Result<()> {
+        log::debug!(target: "sound", "PulseAudio request: {:?}", request);
+        let mut state = PULSEAUDIO_CLIENT.lock().unwrap();
+
+        if state.closed {
+            return Err(BlockError(
+                "sound".into(),
+                "the pulseaudio connection is closed".into(),
+            ));
+        }
+        if let Some(client) = state.client.take() {
+            if !state.lost {
+                match client.sender.send(request) {
+                    Ok(()) => {
+                        state.client = Some(client);
+                        return Ok(());
+                    }
+                    Err(SendError(unsent)) => request = unsent,
+                }
             }
-            Err(err) => Err(BlockError(
+            // The connection threads have died, e.g. because the daemon restarted.
+            log::warn!(target: "sound", "lost the pulseaudio connection, reconnecting");
+            state.lost = false;
+            drop(state);
+            client.close();
+            return PulseAudioClient::send(request);
+        }
+        if state.connecting {
+            return Err(BlockError(
                 "sound".into(),
-                format!("pulseaudio connection failed with error: {}", err),
-            )),
+                "connecting to pulseaudio, retrying later".into(),
+            ));
+        }
+        if state.failed_attempts >= PULSEAUDIO_MAX_ATTEMPTS {
+            return Err(BlockError(
+                "sound".into(),
+                "could not connect to pulseaudio, giving up".into(),
+            ));
+        }
+        if Instant::now() < state.next_attempt {
+            return Err(BlockError(
+                "sound".into(),
+                "not connected to pulseaudio, retrying later".into(),
+            ));
+        }
+
+        log::info!(
+            target: "sound",
+            "connecting to pulseaudio (attempt {} of {})",
+            state.failed_attempts + 1,
+            PULSEAUDIO_MAX_ATTEMPTS
+        );
+        state.connecting = true;
+        drop(state);
+
+        // Connecting takes a while, so the lock is released meanwhile to not block the
+        // other sound blocks and the subscribe thread.
+        let result = PulseAudioClient::new();
+
+        let mut state = PULSEAUDIO_CLIENT.lock().unwrap();
+        state.connecting = false;
+        match result {
+            Ok(client) => {
+                state.failed_attempts = 0;
+                if state.closed {
+                    // Shut down while connecting.
+                    drop(state);
+                    client.close();
+                    return Err(BlockError(
+                        "sound".into(),
+                        "the pulseaudio connection is closed".into(),
+                    ));
+                }
+                let sent = client.sender.send(request).is_ok();
+                state.client = Some(client);
+                if sent {
+                    Ok(())
+                } else {
+                    Err(BlockError(
+                        "sound".into(),
+                        "lost the pulseaudio connection".into(),
+                    ))
+                }
+            }
+            Err(err) => {
+                let delay = min(
+                    Duration::from_secs(1 << state.failed_attempts),
+                    PULSEAUDIO_MAX_RETRY_DELAY,
+                );
+                state.failed_attempts += 1;
+                state.next_attempt = Instant::now() + delay;
+                log::warn!(
+                    target: "sound",
+                    "pulseaudio connection failed, retrying in {:?}: {}",
+                    delay,
+                    err
+                );
+                Err(BlockError(
+                    "sound".into(),
+                    format!("pulseaudio connection failed with error: {}", err),
+                ))
+            }
         }
     }
 
@@ -500,7 +1488,11 @@ impl PulseAudioClient {
     ) -> Option<PulseAudioVolInfo> {
         match result {
             ListResult::End | ListResult::Error => None,
-            ListResult::Item(info) => info.try_into().ok(),
+            ListResult::Item(info) => {
+                let info: Option<PulseAudioVolInfo> = info.try_into().ok();
+                log::debug!(target: "sound", "PulseAudio device info: {:?}", info);
+                info
+            }
         }
     }
 
@@ -528,43 +1520,178 @@ impl PulseAudioClient {
 
     fn subscribe_callback(
         facility: Option<Facility>,
-        _operation: Option<SubscribeOperation>,
+        operation: Option<SubscribeOperation>,
         index: u32,
     ) {
         match facility {
             None => {}
             Some(facility) => match facility {
                 Facility::Server => {
-                    PulseAudioClient::send(PulseAudioClientRequest::GetDefaultDevice).ok();
+                    PulseAudioClient::send_from_callback(PulseAudioClientRequest::GetDefaultDevice);
                 }
                 Facility::Sink => {
-                    PulseAudioClient::send(PulseAudioClientRequest::GetInfoByIndex(
+                    match operation {
+                        Some(SubscribeOperation::New) | Some(SubscribeOperation::Removed) => {
+                            PulseAudioClient::send_from_callback(
+                                PulseAudioClientRequest::GetSinkList,
+                            );
+                        }
+                        _ => {}
+                    }
+                    PulseAudioClient::send_from_callback(PulseAudioClientRequest::GetInfoByIndex(
                         DeviceKind::Sink,
                         index,
-                    ))
-                    .ok();
+                    ));
                 }
                 Facility::Source => {
-                    PulseAudioClient::send(PulseAudioClientRequest::GetInfoByIndex(
+                    PulseAudioClient::send_from_callback(PulseAudioClientRequest::GetInfoByIndex(
                         DeviceKind::Source,
                         index,
-                    ))
-                    .ok();
+                    ));
+                }
+                Facility::SinkInput => {
+                    let streams: Vec<String> = PULSEAUDIO_SINK_INPUTS
+                        .lock()
+                        .unwrap()
+                        .keys()
+                        .cloned()
+                        .collect();
+                    for name in streams {
+                        PulseAudioClient::send_from_callback(
+                            PulseAudioClientRequest::GetSinkInputByName(name),
+                        );
+                    }
+                }
+                Facility::SourceOutput => {
+                    PulseAudioClient::send_from_callback(
+                        PulseAudioClientRequest::GetSourceOutputList,
+                    );
                 }
                 _ => {}
             },
         }
     }
 
-    fn send_update_event() {
-        for (id, tx_update_request) in &*PULSEAUDIO_EVENT_LISTENER.lock().unwrap() {
-            tx_update_request
-                .send(Task {
-                    id: id.clone(),
-                    update_time: Instant::now(),
-                })
-                .unwrap();
-        }
+    fn send_update_event() {
+        for (id, tx_update_request) in &*PULSEAUDIO_EVENT_LISTENER.lock().unwrap() {
+            tx_update_request
+                .send(Task {
+                    id: id.clone(),
+                    update_time: Instant::now(),
+                })
+                .unwrap();
+        }
+    }
+}
+
+/// How often the peak level is read per second
+#[cfg(feature = "pulseaudio")]
+const PEAK_RATE: u32 = 10;
+
+/// Records from `source` with peak detection in a new thread with its own connection,
+/// storing the level in `peak`. The block `id` is updated when the level changes by at
+/// least a percent.
+#[cfg(feature = "pulseaudio")]
+fn spawn_peak_monitor(
+    source: String,
+    peak: Arc<Mutex<Option<f32>>>,
+    id: String,
+    tx_update_request: Sender<Task>,
+) -> Result<()> {
+    let (send_result, recv_result) = unbounded();
+
+    thread::Builder::new()
+        .name("sound_pulseaudio_peak".into())
+        .spawn(move || {
+            let connect = || -> Result<(PulseAudioConnection, Rc<RefCell<Stream>>)> {
+                let connection = PulseAudioConnection::new()?;
+                // With peak detection, each sample is the peak since the previous one.
+                let spec = Spec {
+                    format: Format::F32le,
+                    channels: 1,
+                    rate: PEAK_RATE,
+                };
+                let stream = Stream::new(
+                    &mut connection.context.borrow_mut(),
+                    "i3status-rs peak",
+                    &spec,
+                    None,
+                )
+                .block_error("sound", "failed to create the peak stream")?;
+                let stream = Rc::new(RefCell::new(stream));
+                // Deliver every sample as soon as it is recorded.
+                let attr = BufferAttr {
+                    maxlength: u32::MAX,
+                    tlength: u32::MAX,
+                    prebuf: u32::MAX,
+                    minreq: u32::MAX,
+                    fragsize: std::mem::size_of::<f32>() as u32,
+                };
+                stream
+                    .borrow_mut()
+                    .connect_record(
+                        Some(&source),
+                        Some(&attr),
+                        stream_flags::PEAK_DETECT | stream_flags::ADJUST_LATENCY,
+                    )
+                    .block_error("sound", "failed to record the peak level")?;
+                Ok((connection, stream))
+            };
+
+            let (connection, stream) = match connect() {
+                Ok(connected) => {
+                    send_result.send(Ok(())).unwrap();
+                    connected
+                }
+                Err(err) => {
+                    send_result.send(Err(err)).unwrap();
+                    return;
+                }
+            };
+
+            let reader = Rc::clone(&stream);
+            stream
+                .borrow_mut()
+                .set_read_callback(Some(Box::new(move |_| {
+                    let mut stream = reader.borrow_mut();
+                    loop {
+                        let level = match stream.peek() {
+                            Ok(PeekResult::Data(data)) => data
+                                .chunks_exact(4)
+                                .last()
+                                .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]])),
+                            Ok(PeekResult::Hole(_)) => None,
+                            _ => break,
+                        };
+                        stream.discard().ok();
+
+                        if let Some(level) = level {
+                            let level = level.clamp(0.0, 1.0);
+                            let mut peak = peak.lock().unwrap();
+                            let changed = peak.map_or(true, |previous| {
+                                (previous * 100.0).round() != (level * 100.0).round()
+                            });
+                            *peak = Some(level);
+                            if changed {
+                                let _ = tx_update_request.send(Task {
+                                    id: id.clone(),
+                                    update_time: Instant::now(),
+                                });
+                            }
+                        }
+                    }
+                })));
+
+            connection.mainloop.borrow_mut().run().ok();
+        })
+        .unwrap();
+
+    match recv_result.recv() {
+        Err(_) => Err(BlockError(
+            "sound".into(),
+            "failed to receive from the peak thread channel".into(),
+        )),
+        Ok(result) => result,
     }
 }
 
@@ -575,16 +1702,30 @@ impl PulseAudioSoundDevice {
 
         let device = PulseAudioSoundDevice {
             name: None,
+            stream_name: None,
+            index: None,
             device_kind,
             volume: None,
             volume_avg: 0,
+            channel_aggregate: ChannelAggregate::Avg,
+            channel_map: None,
             muted: false,
+            description: None,
+            peak_volume: None,
+            peak: None,
+            monitor_id: None,
         };
 
         PulseAudioClient::send(PulseAudioClientRequest::GetInfoByName(
             device_kind,
             device.name(),
         ))?;
+        match device_kind {
+            DeviceKind::Sink => PulseAudioClient::send(PulseAudioClientRequest::GetSinkList)?,
+            DeviceKind::Source => {
+                PulseAudioClient::send(PulseAudioClientRequest::GetSourceOutputList)?
+            }
+        }
 
         Ok(device)
     }
@@ -594,6 +1735,35 @@ impl PulseAudioSoundDevice {
         self
     }
 
+    fn with_stream(mut self, stream_name: String) -> Result<Self> {
+        PULSEAUDIO_SINK_INPUTS
+            .lock()
+            .unwrap()
+            .entry(stream_name.clone())
+            .or_insert(None);
+        PulseAudioClient::send(PulseAudioClientRequest::GetSinkInputByName(
+            stream_name.clone(),
+        ))?;
+        self.stream_name = Some(stream_name);
+        Ok(self)
+    }
+
+    /// The request setting `volume`, for the stream if one is controlled, otherwise for
+    /// the device.
+    fn volume_request(&self, volume: ChannelVolumes) -> Result<PulseAudioClientRequest> {
+        match (&self.stream_name, self.index) {
+            (None, _) => Ok(PulseAudioClientRequest::SetVolumeByName(
+                self.device_kind,
+                self.name(),
+                volume,
+            )),
+            (Some(_), Some(index)) => Ok(PulseAudioClientRequest::SetSinkInputVolumeByIndex(
+                index, volume,
+            )),
+            (Some(_), None) => Err(BlockError("sound".into(), "stream not playing".into())),
+        }
+    }
+
     fn name(&self) -> String {
         self.name
             .clone()
@@ -602,7 +1772,11 @@ impl PulseAudioSoundDevice {
 
     fn volume(&mut self, volume: ChannelVolumes) {
         self.volume = Some(volume);
-        self.volume_avg = (volume.avg().0 as f32 / VOLUME_NORM.0 as f32 * 100.0).round() as u32;
+        let aggregate = match self.channel_aggregate {
+            ChannelAggregate::Avg => volume.avg(),
+            ChannelAggregate::Max => volume.max(),
+        };
+        self.volume_avg = (aggregate.0 as f32 / VOLUME_NORM.0 as f32 * 100.0).round() as u32;
     }
 }
 
@@ -617,15 +1791,113 @@ impl SoundDevice for PulseAudioSoundDevice {
     }
 
     fn output_name(&self) -> String {
-        self.name()
+        match self.stream_name {
+            Some(ref stream_name) => stream_name.clone(),
+            None => self.name(),
+        }
+    }
+
+    fn output_description(&self) -> Option<String> {
+        self.description.clone()
+    }
+
+    fn set_channel_aggregate(&mut self, aggregate: ChannelAggregate) {
+        self.channel_aggregate = aggregate;
+        if let Some(volume) = self.volume {
+            self.volume(volume);
+        }
+    }
+
+    fn index(&self) -> Option<u32> {
+        self.index
+    }
+
+    fn recording(&self) -> Option<bool> {
+        match (self.device_kind, self.index) {
+            (DeviceKind::Source, Some(index)) => {
+                Some(PULSEAUDIO_RECORDED_SOURCES.lock().unwrap().contains(&index))
+            }
+            _ => None,
+        }
+    }
+
+    fn peak_volume(&self) -> Option<f32> {
+        self.peak_volume
+    }
+
+    fn monitor_peak(&mut self, id: String, tx_update_request: Sender<Task>) -> Result<()> {
+        // Sinks are monitored through their monitor source.
+        let source = match (self.device_kind, &self.name) {
+            (DeviceKind::Sink, None) => "@DEFAULT_MONITOR@".to_owned(),
+            (DeviceKind::Sink, Some(name)) => format!("{}.monitor", name),
+            (DeviceKind::Source, _) => self.name(),
+        };
+        let peak = Arc::new(Mutex::new(None));
+        spawn_peak_monitor(source, Arc::clone(&peak), id, tx_update_request)?;
+        self.peak = Some(peak);
+        Ok(())
+    }
+
+    fn select_next_sink(&mut self) -> Result<()> {
+        let next = {
+            let sinks = PULSEAUDIO_SINKS.lock().unwrap();
+            let default = PULSEAUDIO_DEFAULT_SINK.lock().unwrap();
+            // Start from the first sink if the default one isn't listed.
+            let next = match sinks.iter().position(|sink| *sink == *default) {
+                Some(pos) => (pos + 1) % sinks.len(),
+                None => 0,
+            };
+            match sinks.get(next) {
+                Some(sink) => sink.clone(),
+                None => return Err(BlockError("sound".into(), "no sinks found".into())),
+            }
+        };
+
+        PulseAudioClient::send(PulseAudioClientRequest::SetDefaultSink(next))
+    }
+
+    fn balance(&self) -> Option<i32> {
+        match (self.volume, self.channel_map) {
+            (Some(volume), Some(map)) => Some((volume.get_balance(&map) * 100.0).round() as i32),
+            _ => None,
+        }
     }
 
     fn get_info(&mut self) -> Result<()> {
+        if let Some(ref peak) = self.peak {
+            self.peak_volume = *peak.lock().unwrap();
+        }
+
+        if let Some(ref stream_name) = self.stream_name {
+            match PULSEAUDIO_SINK_INPUTS.lock().unwrap().get(stream_name) {
+                Some(Some(info)) => {
+                    self.index = Some(info.index);
+                    self.volume(info.volume);
+                    self.channel_map = Some(info.channel_map);
+                    self.muted = info.mute;
+                    self.description = info.description.clone();
+                }
+                // The application isn't playing anything.
+                _ => {
+                    self.index = None;
+                    self.volume = None;
+                    self.volume_avg = 0;
+                    self.channel_map = None;
+                    self.muted = false;
+                    self.description = None;
+                }
+            }
+            return Ok(());
+        }
+
         let devices = PULSEAUDIO_DEVICES.lock().unwrap();
 
         if let Some(info) = devices.get(&(self.device_kind, self.name())) {
+            self.index = Some(info.index);
             self.volume(info.volume);
+            self.channel_map = Some(info.channel_map);
             self.muted = info.mute;
+            self.description = info.description.clone();
         }
 
         Ok(())
@@ -654,23 +1926,44 @@ impl SoundDevice for PulseAudioSoundDevice {
 
         // update volumes
         self.volume(volume);
-        PulseAudioClient::send(PulseAudioClientRequest::SetVolumeByName(
-            self.device_kind,
-            self.name(),
-            volume,
-        ))?;
+        PulseAudioClient::send(self.volume_request(volume)?)?;
+
+        Ok(())
+    }
+
+    fn set_balance(&mut self, balance: i32) -> Result<()> {
+        let (mut volume, map) = match (self.volume, self.channel_map) {
+            (Some(volume), Some(map)) => (volume, map),
+            _ => return Err(BlockError("sound".into(), "volume unknown".into())),
+        };
+
+        let balance = balance.clamp(-100, 100) as f32 / 100.0;
+        if volume.set_balance(&map, balance).is_none() {
+            return Err(BlockError(
+                "sound".into(),
+                "the balance of this device can't be changed".into(),
+            ));
+        }
+
+        self.volume(volume);
+        PulseAudioClient::send(self.volume_request(volume)?)?;
 
         Ok(())
     }
 
     fn toggle(&mut self) -> Result<()> {
+        let request = match (&self.stream_name, self.index) {
+            (None, _) => {
+                PulseAudioClientRequest::SetMuteByName(self.device_kind, self.name(), !self.muted)
+            }
+            (Some(_), Some(index)) => {
+                PulseAudioClientRequest::SetSinkInputMuteByIndex(index, !self.muted)
+            }
+            (Some(_), None) => return Err(BlockError("sound".into(), "stream not playing".into())),
+        };
         self.muted = !self.muted;
 
-        PulseAudioClient::send(PulseAudioClientRequest::SetMuteByName(
-            self.device_kind,
-            self.name(),
-            self.muted,
-        ))?;
+        PulseAudioClient::send(request)?;
 
         Ok(())
     }
@@ -679,27 +1972,65 @@ impl SoundDevice for PulseAudioSoundDevice {
         PULSEAUDIO_EVENT_LISTENER
             .lock()
             .unwrap()
-            .insert(id, tx_update_request);
+            .insert(id.clone(), tx_update_request);
+        self.monitor_id = Some(id);
         Ok(())
     }
 }
 
+#[cfg(feature = "pulseaudio")]
+impl Drop for PulseAudioSoundDevice {
+    fn drop(&mut self) {
+        // The connection is shared by all sound blocks and outlives them, it is only
+        // closed by `shutdown`.
+        if let Some(id) = self.monitor_id.take() {
+            PULSEAUDIO_EVENT_LISTENER.lock().unwrap().remove(&id);
+        }
+    }
+}
+
+/// Closes the connection to PulseAudio, if any, once the bar is shutting down.
+pub fn shutdown() {
+    #[cfg(feature = "pulseaudio")]
+    PulseAudioClient::disconnect();
+}
+
 // TODO: Use the alsa control bindings to implement push updates
 pub struct Sound {
     text: ButtonWidget,
     id: String,
     device: Box<dyn SoundDevice>,
     device_kind: DeviceKind,
+    linked_source: Option<Box<dyn SoundDevice>>,
     step_width: u32,
+    step_mode: StepMode,
     format: FormatTemplate,
     config: Config,
     on_click: Option<String>,
+    cycle_sinks_on_click: bool,
     show_volume_when_muted: bool,
     bar: bool,
+    show_peak_bar: bool,
+    bar_width: usize,
+    bar_style: BarStyle,
+    bar_color: bool,
     mappings: Option<BTreeMap<String, String>>,
     max_vol: Option<u32>,
+    auto_mute_at_zero: bool,
+    display_clamp: bool,
+    volume_scale: VolumeScale,
+    warning_volume: Option<u32>,
+    critical_volume: Option<u32>,
+    show_source_indicator: bool,
+    signal: Option<i32>,
+    tx_update_request: Sender<Task>,
+    /// Until when the block is highlighted after toggling mute
+    flash_until: Option<Instant>,
 }
 
+/// How long the block is highlighted after toggling mute
+const MUTE_FLASH_DURATION: Duration = Duration::from_millis(300);
+
 #[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq)]
 #[serde(rename_all = "lowercase")]
 pub enum DeviceKind {
@@ -743,22 +2074,53 @@ pub struct SoundConfig {
     #[serde(default)]
     pub device_kind: DeviceKind,
 
+    /// With PulseAudio, control the stream of the application with this name instead of a device
+    #[serde(default = "SoundConfig::default_stream_name")]
+    pub stream_name: Option<String>,
+
     /// Use the mapped volume for evaluating the percentage representation like alsamixer, to be more natural for human ear
     #[serde(default = "SoundConfig::default_natural_mapping")]
     pub natural_mapping: bool,
 
+    /// How often to poll the volume with ALSA if `alsactl monitor` can't be used
+    #[serde(
+        default = "SoundConfig::default_alsa_poll_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub alsa_poll_interval: Duration,
+
+    /// Minimum time between two updates caused by `alsactl monitor` events, in milliseconds
+    #[serde(default = "SoundConfig::default_monitor_debounce_ms")]
+    pub monitor_debounce_ms: u64,
+
+    /// With ALSA, read the volume only on the first update instead of when creating the block
+    #[serde(default = "SoundConfig::default_lazy_init")]
+    pub lazy_init: bool,
+
+    /// With PulseAudio, whether the average or the loudest of the channels is shown
+    #[serde(default)]
+    pub channel_aggregate: ChannelAggregate,
+
     /// The steps volume is in/decreased for the selected audio device (When greater than 50 it gets limited to 50)
     #[serde(default = "SoundConfig::default_step_width")]
     pub step_width: u32,
 
+    /// Whether `step_width` is in percentage points or relative to the current volume
+    #[serde(default)]
+    pub step_mode: StepMode,
+
     /// Format string for displaying sound information.
-    /// placeholders: {volume}
+    /// placeholders: {volume}, {output_name}, {device}, {balance}, {peak}
     #[serde(default = "SoundConfig::default_format")]
     pub format: String,
 
     #[serde(default = "SoundConfig::default_on_click")]
     pub on_click: Option<String>,
 
+    /// When no `on_click` command is set, make the next sink the default on left click
+    #[serde(default = "SoundConfig::default_cycle_sinks_on_click")]
+    pub cycle_sinks_on_click: bool,
+
     #[serde(default = "SoundConfig::default_show_volume_when_muted")]
     pub show_volume_when_muted: bool,
 
@@ -766,11 +2128,68 @@ pub struct SoundConfig {
     #[serde(default = "SoundConfig::default_bar")]
     pub bar: bool,
 
+    /// With PulseAudio, show the peak level as a bar after the volume
+    #[serde(default = "SoundConfig::default_show_peak_bar")]
+    pub show_peak_bar: bool,
+
+    /// Number of cells of the volume bar
+    #[serde(default = "SoundConfig::default_bar_width")]
+    pub bar_width: usize,
+
+    /// Characters used to draw the volume bar
+    #[serde(default)]
+    pub bar_style: BarStyle,
+
+    /// Color the volume bar from green to red
+    #[serde(default = "SoundConfig::default_bar_color")]
+    pub bar_color: bool,
+
     #[serde(default = "SoundConfig::default_mappings")]
     pub mappings: Option<BTreeMap<String, String>>,
 
     #[serde(default = "SoundConfig::default_max_vol")]
     pub max_vol: Option<u32>,
+
+    /// Mute when scrolling down to 0, and unmute when scrolling up while muted
+    #[serde(default = "SoundConfig::default_auto_mute_at_zero")]
+    pub auto_mute_at_zero: bool,
+
+    /// Show at most 100 in {volume}, even if the actual volume is higher
+    #[serde(default = "SoundConfig::default_display_clamp")]
+    pub display_clamp: bool,
+
+    /// Whether {volume} is a percentage or a fraction of 1
+    #[serde(default)]
+    pub volume_scale: VolumeScale,
+
+    /// Volume from which the state is set to warning
+    #[serde(default = "SoundConfig::default_warning_volume")]
+    pub warning_volume: Option<u32>,
+
+    /// Volume from which the state is set to critical
+    #[serde(default = "SoundConfig::default_critical_volume")]
+    pub critical_volume: Option<u32>,
+
+    /// Also show whether the default source is muted, for sinks
+    #[serde(default = "SoundConfig::default_link_source")]
+    pub link_source: bool,
+
+    /// For sources, show a different icon while an application is recording
+    #[serde(default = "SoundConfig::default_show_source_indicator")]
+    pub show_source_indicator: bool,
+
+    /// Signal to re-query the volume upon reception
+    #[serde(default = "SoundConfig::default_signal")]
+    pub signal: Option<i32>,
+
+    /// Text whose width is reserved for the block, so it doesn't change size with the volume.
+    /// An empty string disables this.
+    #[serde(default = "SoundConfig::default_min_width")]
+    pub min_width: String,
+
+    /// Alignment of the text within `min_width`
+    #[serde(default = "SoundConfig::default_align")]
+    pub align: Align,
 }
 
 #[derive(Deserialize, Copy, Clone, Debug)]
@@ -778,8 +2197,13 @@ pub struct SoundConfig {
 pub enum SoundDriver {
     Auto,
     Alsa,
+    Pactl,
+    Mpris,
     #[cfg(feature = "pulseaudio")]
     PulseAudio,
+    /// Keeps its state in memory, for tests
+    #[cfg(test)]
+    Mock,
 }
 
 impl Default for SoundDriver {
@@ -797,10 +2221,26 @@ impl SoundConfig {
         None
     }
 
+    fn default_stream_name() -> Option<String> {
+        None
+    }
+
     fn default_natural_mapping() -> bool {
         false
     }
 
+    fn default_alsa_poll_interval() -> Duration {
+        Duration::from_secs(2)
+    }
+
+    fn default_monitor_debounce_ms() -> u64 {
+        250
+    }
+
+    fn default_lazy_init() -> bool {
+        false
+    }
+
     fn default_step_width() -> u32 {
         5
     }
@@ -813,6 +2253,10 @@ impl SoundConfig {
         None
     }
 
+    fn default_cycle_sinks_on_click() -> bool {
+        false
+    }
+
     fn default_show_volume_when_muted() -> bool {
         false
     }
@@ -821,6 +2265,18 @@ impl SoundConfig {
         false
     }
 
+    fn default_show_peak_bar() -> bool {
+        false
+    }
+
+    fn default_bar_width() -> usize {
+        10
+    }
+
+    fn default_bar_color() -> bool {
+        false
+    }
+
     fn default_mappings() -> Option<BTreeMap<String, String>> {
         None
     }
@@ -828,30 +2284,93 @@ impl SoundConfig {
     fn default_max_vol() -> Option<u32> {
         None
     }
-}
 
-impl Sound {
-    fn icon(&self, volume: u32) -> String {
-        let prefix = match self.device_kind {
-            DeviceKind::Source => "microphone",
-            DeviceKind::Sink => "volume",
-        };
+    fn default_auto_mute_at_zero() -> bool {
+        false
+    }
 
-        let suffix = match volume {
-            0 => "muted",
-            1..=20 => "empty",
-            21..=70 => "half",
-            _ => "full",
-        };
+    fn default_display_clamp() -> bool {
+        false
+    }
 
-        format!("{}_{}", prefix, suffix)
+    fn default_warning_volume() -> Option<u32> {
+        None
     }
 
-    fn display(&mut self) -> Result<()> {
-        self.device.get_info()?;
+    fn default_critical_volume() -> Option<u32> {
+        None
+    }
+
+    fn default_link_source() -> bool {
+        false
+    }
+
+    fn default_show_source_indicator() -> bool {
+        false
+    }
+
+    fn default_signal() -> Option<i32> {
+        None
+    }
+
+    fn default_min_width() -> String {
+        "100%".to_owned()
+    }
+
+    fn default_align() -> Align {
+        Align::Right
+    }
+}
 
+/// The name of the icon for a device of `device_kind` at `volume`.
+pub(crate) fn volume_icon(device_kind: DeviceKind, volume: u32) -> String {
+    let prefix = match device_kind {
+        DeviceKind::Source => "microphone",
+        DeviceKind::Sink => "volume",
+    };
+
+    let suffix = match volume {
+        0 => "muted",
+        1..=20 => "empty",
+        21..=70 => "half",
+        71..=100 => "full",
+        // Only sinks can be boosted above 100% in a meaningful way.
+        _ if device_kind == DeviceKind::Sink => "overamplified",
+        _ => "full",
+    };
+
+    format!("{}_{}", prefix, suffix)
+}
+
+/// The environment variables for `on_click`, e.g. `I3RS_VOLUME` for `{volume}`.
+fn on_click_env(values: &HashMap<&str, String>) -> Vec<(String, String)> {
+    values
+        .iter()
+        .map(|(placeholder, value)| {
+            let name = placeholder.trim_matches(|c| c == '{' || c == '}');
+            (format!("I3RS_{}", name.to_uppercase()), value.clone())
+        })
+        .collect()
+}
+
+impl Sound {
+    fn format_bar(&self, volume: u32) -> String {
+        if self.bar_color {
+            format_percent_bar_colored(volume as f32, self.bar_width, self.bar_style)
+        } else {
+            format_percent_bar_styled(volume as f32, self.bar_width, self.bar_style)
+        }
+    }
+
+    /// The values of the placeholders of `format`, also passed to `on_click`.
+    fn values(&self) -> HashMap<&str, String> {
         let volume = self.device.volume();
         let output_name = self.device.output_name();
+        // Drivers without descriptions only know the name.
+        let device = self
+            .device
+            .output_description()
+            .unwrap_or_else(|| output_name.clone());
         let mapped_output_name = if let Some(m) = &self.mappings {
             match m.get(&output_name) {
                 Some(mapping) => mapping.to_string(),
@@ -860,37 +2379,218 @@ impl Sound {
         } else {
             output_name
         };
-        let values = map!("{volume}" => format!("{:02}", volume),
-                          "{output_name}" => mapped_output_name
-        );
-        let text = self.format.render_static_str(&values)?;
+        let displayed_volume = if self.display_clamp {
+            min(volume, 100)
+        } else {
+            volume
+        };
+        let balance = self
+            .device
+            .balance()
+            .map(|balance| format!("{:+}", balance))
+            .unwrap_or_default();
+        let peak = self
+            .device
+            .peak_volume()
+            .map(|peak| format!("{:02.0}", peak * 100.0))
+            .unwrap_or_default();
+        let volume = match self.volume_scale {
+            VolumeScale::Percent => format!("{:02}", displayed_volume),
+            VolumeScale::Fraction => format!("{:.2}", displayed_volume as f64 / 100.0),
+        };
+        let sink_index = self
+            .device
+            .index()
+            .map(|index| index.to_string())
+            .unwrap_or_default();
+        map!("{volume}" => volume,
+             "{balance}" => balance,
+             "{peak}" => peak,
+             "{sink_index}" => sink_index,
+             "{output_name}" => mapped_output_name,
+             "{device}" => device
+        )
+    }
+
+    /// Highlights the block for `MUTE_FLASH_DURATION`, as feedback for toggling mute when
+    /// the text doesn't change. An update requested for later reverts the state.
+    fn flash(&mut self) -> Result<()> {
+        let until = Instant::now() + MUTE_FLASH_DURATION;
+        self.flash_until = Some(until);
+        self.tx_update_request.send(Task {
+            id: self.id.clone(),
+            update_time: until,
+        })?;
+        Ok(())
+    }
+
+    fn display(&mut self) -> Result<()> {
+        self.device.get_info()?;
+
+        let volume = self.device.volume();
+        let text = self.format.render_static_str(&self.values())?;
+        self.text.set_description(self.device.output_description());
+
+        // Badge shown after the text while the linked source is muted
+        let source_badge = match self.linked_source {
+            Some(ref mut source) => {
+                source.get_info()?;
+                if source.muted() {
+                    self.config
+                        .icons
+                        .get("microphone_muted")
+                        .map(|icon| icon.trim_end().to_owned())
+                        .unwrap_or_default()
+                } else {
+                    String::new()
+                }
+            }
+            None => String::new(),
+        };
 
         if self.device.muted() {
-            self.text.set_icon(&self.icon(0));
+            self.text.set_icon(&volume_icon(self.device_kind, 0));
             if self.show_volume_when_muted {
                 if self.bar {
-                    self.text.set_text(format_percent_bar(volume as f32));
+                    self.text
+                        .set_text(format!("{}{}", self.format_bar(volume), source_badge));
                 } else {
-                    self.text.set_text(text);
+                    self.text.set_text(format!("{}{}", text, source_badge));
                 }
             } else {
-                self.text.set_text("");
+                self.text.set_text(source_badge.trim_start().to_owned());
             }
             self.text.set_state(State::Warning);
         } else {
-            self.text.set_icon(&self.icon(volume));
+            if self.show_source_indicator && self.device.recording() == Some(true) {
+                self.text.set_icon("microphone_active");
+            } else {
+                self.text.set_icon(&volume_icon(self.device_kind, volume));
+            }
+            let peak_bar = if self.show_peak_bar {
+                let peak = self.device.peak_volume().unwrap_or(0.0) * 100.0;
+                format!(" {}", self.format_bar(peak.round() as u32))
+            } else {
+                String::new()
+            };
             self.text.set_text(if self.bar {
-                format_percent_bar(volume as f32)
+                format!("{}{}{}", self.format_bar(volume), peak_bar, source_badge)
+            } else {
+                format!("{}{}{}", text, peak_bar, source_badge)
+            });
+            let reached = |threshold: Option<u32>| threshold.map_or(false, |t| volume >= t);
+            self.text.set_state(if reached(self.critical_volume) {
+                State::Critical
+            } else if reached(self.warning_volume) || !source_badge.is_empty() {
+                State::Warning
             } else {
-                text
+                State::Idle
             });
-            self.text.set_state(State::Idle);
+        }
+
+        if self
+            .flash_until
+            .map_or(false, |until| Instant::now() < until)
+        {
+            self.text.set_state(State::Info);
         }
 
         Ok(())
     }
 }
 
+/// Settings of devices using the ALSA driver
+#[derive(Clone, Debug)]
+pub(crate) struct AlsaOptions {
+    /// ALSA device name, "default" if not set
+    pub device: Option<String>,
+    pub natural_mapping: bool,
+    pub poll_interval: Duration,
+    pub monitor_debounce: Duration,
+    /// Don't read the volume until the first update, so a briefly unavailable `amixer`
+    /// doesn't prevent the block from being created
+    pub lazy_init: bool,
+}
+
+/// Creates the device of `device_kind` named `name`, using `pactl` or MPRIS if selected,
+/// otherwise preferring PulseAudio if the feature is enabled and `driver` allows it,
+/// and falling back to ALSA.
+pub(crate) fn new_device(
+    driver: SoundDriver,
+    device_kind: DeviceKind,
+    name: Option<String>,
+    alsa: AlsaOptions,
+) -> Result<Box<dyn SoundDevice>> {
+    #[cfg(not(feature = "pulseaudio"))]
+    type PulseAudioSoundDevice = AlsaSoundDevice;
+
+    if let SoundDriver::Pactl = driver {
+        return Ok(Box::new(PactlSoundDevice::new(device_kind, name)?));
+    }
+    if let SoundDriver::Mpris = driver {
+        return Ok(Box::new(MprisSoundDevice::new(name)?));
+    }
+    #[cfg(test)]
+    {
+        if let SoundDriver::Mock = driver {
+            return Ok(Box::new(MockSoundDevice::new(50, false)));
+        }
+    }
+
+    // try to create a pulseaudio device if feature is enabled and `driver != "alsa"`
+    let pulseaudio_device: Result<PulseAudioSoundDevice> = match driver {
+        #[cfg(feature = "pulseaudio")]
+        SoundDriver::Auto | SoundDriver::PulseAudio => {
+            let sound_device = PulseAudioSoundDevice::new(device_kind);
+
+            match name.as_ref() {
+                None => sound_device,
+                Some(name) => sound_device.map(|device| device.with_name(name.to_string())),
+            }
+        }
+        _ => Err(BlockError(
+            "sound".into(),
+            "PulseAudio feature or driver disabled".into(),
+        )),
+    };
+
+    // prefer PulseAudio if available and selected, fallback to ALSA
+    let device: Box<dyn SoundDevice> = match pulseaudio_device {
+        Ok(dev) => Box::new(dev),
+        Err(_) => Box::new(AlsaSoundDevice::new(
+            name.unwrap_or_else(|| match device_kind {
+                DeviceKind::Sink => "Master".into(),
+                DeviceKind::Source => "Capture".into(),
+            }),
+            alsa.device.unwrap_or_else(|| "default".into()),
+            alsa.natural_mapping,
+            alsa.poll_interval,
+            alsa.monitor_debounce,
+            alsa.lazy_init,
+        )?),
+    };
+
+    Ok(device)
+}
+
+/// Creates a device controlling the stream of the application named `stream_name`,
+/// which is only possible with PulseAudio.
+fn new_stream_device(driver: SoundDriver, stream_name: String) -> Result<Box<dyn SoundDevice>> {
+    match driver {
+        #[cfg(feature = "pulseaudio")]
+        SoundDriver::Auto | SoundDriver::PulseAudio => Ok(Box::new(
+            PulseAudioSoundDevice::new(DeviceKind::Sink)?.with_stream(stream_name)?,
+        )),
+        _ => Err(BlockError(
+            "sound".into(),
+            format!(
+                "can't control the stream of '{}' without the pulseaudio driver",
+                stream_name
+            ),
+        )),
+    }
+}
+
 impl ConfigBlock for Sound {
     type Config = SoundConfig;
 
@@ -905,51 +2605,81 @@ impl ConfigBlock for Sound {
             step_width = 50;
         }
 
-        #[cfg(not(feature = "pulseaudio"))]
-        type PulseAudioSoundDevice = AlsaSoundDevice;
-
-        // try to create a pulseaudio device if feature is enabled and `driver != "alsa"`
-        let pulseaudio_device: Result<PulseAudioSoundDevice> = match block_config.driver {
-            #[cfg(feature = "pulseaudio")]
-            SoundDriver::Auto | SoundDriver::PulseAudio => {
-                let sound_device = PulseAudioSoundDevice::new(block_config.device_kind);
-
-                match block_config.name.as_ref() {
-                    None => sound_device,
-                    Some(name) => sound_device.map(|device| device.with_name(name.to_string())),
-                }
-            }
-            _ => Err(BlockError(
-                "sound".into(),
-                "PulseAudio feature or driver disabled".into(),
-            )),
+        let alsa = AlsaOptions {
+            device: block_config.device,
+            natural_mapping: block_config.natural_mapping,
+            poll_interval: block_config.alsa_poll_interval,
+            monitor_debounce: Duration::from_millis(block_config.monitor_debounce_ms),
+            lazy_init: block_config.lazy_init,
         };
-
-        // prefer PulseAudio if available and selected, fallback to ALSA
-        let device: Box<dyn SoundDevice> = match pulseaudio_device {
-            Ok(dev) => Box::new(dev),
-            Err(_) => Box::new(AlsaSoundDevice::new(
-                block_config.name.unwrap_or_else(|| "Master".into()),
-                block_config.device.unwrap_or_else(|| "default".into()),
-                block_config.natural_mapping,
-            )?),
+        let mut device = match block_config.stream_name {
+            Some(stream_name) => new_stream_device(block_config.driver, stream_name)?,
+            None => new_device(
+                block_config.driver,
+                block_config.device_kind,
+                block_config.name,
+                alsa.clone(),
+            )?,
         };
+        // The default source, whose mute state is shown next to the sink's volume. Changes
+        // of the source are reported by the sink's monitor, as with the audio block.
+        let linked_source =
+            if block_config.link_source && block_config.device_kind == DeviceKind::Sink {
+                Some(new_device(
+                    block_config.driver,
+                    DeviceKind::Source,
+                    None,
+                    alsa,
+                )?)
+            } else {
+                None
+            };
+        device.set_channel_aggregate(block_config.channel_aggregate);
+
+        let mut text = ButtonWidget::new(config.clone(), &id).with_icon("volume_empty");
+        if !block_config.min_width.is_empty() {
+            text = text.with_min_width(&block_config.min_width, block_config.align);
+        }
 
         let mut sound = Self {
-            text: ButtonWidget::new(config.clone(), &id).with_icon("volume_empty"),
+            text,
             id: id.clone(),
             device,
             device_kind: block_config.device_kind,
+            linked_source,
             format: FormatTemplate::from_string(&block_config.format)?,
             step_width,
+            step_mode: block_config.step_mode,
             config,
             on_click: block_config.on_click,
+            cycle_sinks_on_click: block_config.cycle_sinks_on_click,
             show_volume_when_muted: block_config.show_volume_when_muted,
             bar: block_config.bar,
+            show_peak_bar: block_config.show_peak_bar,
+            bar_width: block_config.bar_width,
+            bar_style: block_config.bar_style,
+            bar_color: block_config.bar_color,
             mappings: block_config.mappings,
             max_vol: block_config.max_vol,
+            auto_mute_at_zero: block_config.auto_mute_at_zero,
+            display_clamp: block_config.display_clamp,
+            volume_scale: block_config.volume_scale,
+            warning_volume: block_config.warning_volume,
+            critical_volume: block_config.critical_volume,
+            show_source_indicator: block_config.show_source_indicator,
+            signal: match block_config.signal {
+                Some(signal) => Some(convert_to_valid_signal(signal)?),
+                None => None,
+            },
+            tx_update_request: tx_update_request.clone(),
+            flash_until: None,
         };
 
+        if sound.show_peak_bar || block_config.format.contains("{peak}") {
+            sound
+                .device
+                .monitor_peak(id.clone(), tx_update_request.clone())?;
+        }
         sound.device.monitor(id, tx_update_request)?;
 
         Ok(sound)
@@ -969,28 +2699,91 @@ impl Block for Sound {
         vec![&self.text]
     }
 
+    fn signal(&mut self, signal: i32) -> Result<()> {
+        if self.signal == Some(signal) {
+            self.tx_update_request.send(Task {
+                id: self.id.clone(),
+                update_time: Instant::now(),
+            })?;
+        }
+        Ok(())
+    }
+
     fn click(&mut self, e: &I3BarEvent) -> Result<()> {
         if let Some(ref name) = e.name {
             if name.as_str() == self.id {
                 match e.button {
-                    MouseButton::Right => self.device.toggle()?,
+                    MouseButton::Right => {
+                        self.device.toggle()?;
+                        self.flash()?
+                    }
+                    MouseButton::Middle => self.device.set_balance(0)?,
+                    MouseButton::Back | MouseButton::Forward => {
+                        if let Some(balance) = self.device.balance() {
+                            let step = self.step_width as i32;
+                            self.device.set_balance(if e.button == MouseButton::Back {
+                                balance - step
+                            } else {
+                                balance + step
+                            })?;
+                        }
+                    }
                     MouseButton::Left => {
                         if let Some(ref cmd) = self.on_click {
-                            spawn_child_async("sh", &["-c", cmd])
+                            self.device.get_info()?;
+                            // Device names can be set by others, e.g. Bluetooth devices, so
+                            // they are passed in the environment rather than the command.
+                            let env = on_click_env(&self.values());
+                            let env: Vec<(&str, &str)> = env
+                                .iter()
+                                .map(|(name, value)| (name.as_str(), value.as_str()))
+                                .collect();
+                            spawn_child_async_with_env("sh", &["-c", cmd], &env)
                                 .block_error("sound", "could not spawn child")?;
+                        } else if self.cycle_sinks_on_click {
+                            self.device.select_next_sink()?;
                         }
                     }
                     _ => {
-                        use LogicalDirection::*;
-                        match self.config.scrolling.to_logical_direction(e.button) {
-                            Some(Up) => self
-                                .device
-                                .set_volume(self.step_width as i32, self.max_vol)?,
-                            Some(Down) => self
-                                .device
-                                .set_volume(-(self.step_width as i32), self.max_vol)?,
-                            None => (),
+                        // Horizontal scrolling changes the balance.
+                        if let Some(balance) = self.device.balance() {
+                            let device = &mut self.device;
+                            handle_horizontal_scroll(
+                                self.config.scrolling,
+                                e,
+                                i64::from(self.step_width),
+                                |step| device.set_balance(balance + step as i32),
+                            )?;
                         }
+
+                        let device = &mut self.device;
+                        let max_vol = self.max_vol;
+                        let auto_mute_at_zero = self.auto_mute_at_zero;
+                        let step_mode = self.step_mode;
+                        handle_scroll(
+                            self.config.scrolling,
+                            e,
+                            i64::from(self.step_width),
+                            |step| {
+                                let step = match step_mode {
+                                    StepMode::Absolute => step,
+                                    // At least 1, so the volume can be raised from 0.
+                                    StepMode::Relative => {
+                                        let delta =
+                                            (i64::from(device.volume()) * step.abs() + 50) / 100;
+                                        step.signum() * max(delta, 1)
+                                    }
+                                };
+                                if auto_mute_at_zero && step > 0 && device.muted() {
+                                    device.toggle()?;
+                                }
+                                device.set_volume(step as i32, max_vol)?;
+                                if auto_mute_at_zero && device.volume() == 0 && !device.muted() {
+                                    device.toggle()?;
+                                }
+                                Ok(())
+                            },
+                        )?;
                     }
                 }
                 self.display()?;
@@ -1004,3 +2797,89 @@ impl Block for Sound {
         &self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::blocks::sound::{on_click_env, MockSoundDevice, Sound, SoundConfig};
+    use crate::blocks::{Block, ConfigBlock};
+    use crate::input::{I3BarEvent, MouseButton};
+    use crate::scheduler::Task;
+    use crossbeam_channel::Receiver;
+    use std::time::Instant;
+
+    fn mock_sound(config: &str) -> (Sound, Receiver<Task>) {
+        let block_config: SoundConfig =
+            toml::from_str(&format!("driver = \"mock\"\n{}", config)).unwrap();
+        let (tx, rx) = crossbeam_channel::unbounded();
+        let sound = Sound::new(block_config, Default::default(), tx).unwrap();
+        (sound, rx)
+    }
+
+    fn full_text(sound: &Sound) -> String {
+        sound.view()[0].get_rendered()["full_text"]
+            .as_str()
+            .unwrap()
+            .to_owned()
+    }
+
+    fn click(sound: &mut Sound, button: MouseButton) {
+        let event = I3BarEvent {
+            name: Some(sound.id.clone()),
+            instance: None,
+            x: 0,
+            y: 0,
+            button,
+        };
+        sound.click(&event).unwrap();
+    }
+
+    #[test]
+    fn test_display() {
+        let (mut sound, _) = mock_sound("format = \"{volume}% {output_name} {balance}\"");
+        sound.update().unwrap();
+        assert!(full_text(&sound).contains("50% mock +0"));
+
+        sound.device = Box::new(MockSoundDevice::new(7, false));
+        sound.update().unwrap();
+        assert!(full_text(&sound).contains("07% mock"));
+    }
+
+    #[test]
+    fn test_click() {
+        let (mut sound, rx) = mock_sound("max_vol = 60");
+        click(&mut sound, MouseButton::WheelUp);
+        assert_eq!(sound.device.volume(), 55);
+        click(&mut sound, MouseButton::WheelUp);
+        click(&mut sound, MouseButton::WheelUp);
+        assert_eq!(sound.device.volume(), 60);
+        click(&mut sound, MouseButton::WheelDown);
+        assert_eq!(sound.device.volume(), 55);
+
+        click(&mut sound, MouseButton::Right);
+        assert!(sound.device.muted());
+        assert!(!full_text(&sound).contains("55"));
+        // The flash is reverted by an update requested for later.
+        assert!(rx.try_iter().any(|task| task.update_time > Instant::now()));
+        click(&mut sound, MouseButton::Right);
+        assert!(full_text(&sound).contains("55"));
+    }
+
+    #[test]
+    fn test_auto_mute_at_zero() {
+        let (mut sound, _rx) = mock_sound("auto_mute_at_zero = true\nstep_width = 50");
+        click(&mut sound, MouseButton::WheelDown);
+        assert_eq!(sound.device.volume(), 0);
+        assert!(sound.device.muted());
+        click(&mut sound, MouseButton::WheelUp);
+        assert_eq!(sound.device.volume(), 50);
+        assert!(!sound.device.muted());
+    }
+
+    #[test]
+    fn test_on_click_env() {
+        let (sound, _rx) = mock_sound("");
+        let env = on_click_env(&sound.values());
+        assert!(env.contains(&("I3RS_VOLUME".to_owned(), "50".to_owned())));
+        assert!(env.contains(&("I3RS_OUTPUT_NAME".to_owned(), "mock".to_owned())));
+    }
+}
@@ -1,5 +1,6 @@
 use std::fmt;
-use std::process::Command;
+use std::process::{Command, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
 use std::time::{Duration, Instant};
@@ -15,13 +16,19 @@ use crate::de::deserialize_duration;
 use crate::errors::*;
 use crate::input::{I3BarEvent, MouseButton};
 use crate::scheduler::Task;
-use crate::util::format_speed;
+use crate::util::{format_speed, FormatTemplate, SPINNER};
 use crate::widget::{I3BarWidget, State};
 use crate::widgets::button::ButtonWidget;
 
 pub struct SpeedTest {
     vals: Arc<Mutex<(bool, Vec<f32>)>>,
+    running: Arc<AtomicBool>,
+    last_run: Option<Instant>,
     text: Vec<ButtonWidget>,
+    format: Option<FormatTemplate>,
+    spinner_frame: Option<usize>,
+    /// Text of the first widget, restored if a measurement fails
+    last_text: String,
     id: String,
     config: SpeedTestConfig,
     send: Sender<()>,
@@ -69,6 +76,18 @@ pub struct SpeedTestConfig {
     /// Minimum unit to display for throughput indicators.
     #[serde(default = "SpeedTestConfig::default_speed_min_unit")]
     pub speed_min_unit: Unit,
+
+    /// Only measure when the block is clicked, instead of on every interval
+    #[serde(default = "SpeedTestConfig::default_on_demand")]
+    pub on_demand: bool,
+
+    /// Show the results in a single widget using this format, instead of one widget each
+    #[serde(default = "SpeedTestConfig::default_format")]
+    pub format: Option<String>,
+
+    /// Id of the speedtest.net server to measure against
+    #[serde(default = "SpeedTestConfig::default_server_id")]
+    pub server_id: Option<u32>,
 }
 
 impl SpeedTestConfig {
@@ -87,51 +106,98 @@ impl SpeedTestConfig {
     fn default_speed_digits() -> usize {
         3
     }
+
+    fn default_on_demand() -> bool {
+        false
+    }
+
+    fn default_format() -> Option<String> {
+        None
+    }
+
+    fn default_server_id() -> Option<u32> {
+        None
+    }
 }
 
-fn get_values(bytes: bool) -> Result<String> {
-    let mut cmd = Command::new("speedtest-cli");
-    cmd.arg("--simple");
-    if bytes {
-        cmd.arg("--bytes");
+/// Runs speedtest-cli, calling `tick` regularly while it is running.
+fn get_values<F: FnMut()>(server_id: Option<u32>, mut tick: F) -> Result<String> {
+    let mut command = Command::new("speedtest-cli");
+    command.arg("--json").stdout(Stdio::piped());
+    if let Some(server_id) = server_id {
+        command.arg("--server").arg(server_id.to_string());
     }
+
+    let mut child = command
+        .spawn()
+        .block_error("speedtest", "could not run speedtest-cli")?;
+    // The JSON output is small enough to fit in the pipe, so it's fine to wait before reading it.
+    while child
+        .try_wait()
+        .block_error("speedtest", "could not wait for speedtest-cli")?
+        .is_none()
+    {
+        tick();
+        thread::sleep(Duration::from_millis(200));
+    }
+
     String::from_utf8(
-        cmd.output()
+        child
+            .wait_with_output()
             .block_error("speedtest", "could not get speedtest-cli output")?
             .stdout,
     )
     .block_error("speedtest", "could not parse speedtest-cli output")
 }
 
+/// Returns the ping in ms, and the download and upload speeds in bytes per second.
 fn parse_values(output: &str) -> Result<Vec<f32>> {
-    let mut vals: Vec<f32> = Vec::with_capacity(3);
-
-    for line in output.lines() {
-        let mut word = line.split_whitespace();
-        word.next();
-        vals.push(
-            word.next()
-                .block_error("speedtest", "missing data")?
-                .parse::<f32>()
-                .block_error("speedtest", "Unable to parse data")?,
-        );
-    }
-
-    Ok(vals)
+    let json: serde_json::value::Value =
+        serde_json::from_str(output).block_error("speedtest", "Failed to parse JSON output")?;
+
+    ["/ping", "/download", "/upload"]
+        .iter()
+        .enumerate()
+        .map(|(i, key)| {
+            let val = json
+                .pointer(key)
+                .and_then(|v| v.as_f64())
+                .block_error("speedtest", "missing data")? as f32;
+            // speedtest-cli reports speeds in bits per second
+            Ok(if i == 0 { val } else { val / 8.0 })
+        })
+        .collect()
 }
 
 fn make_thread(
     recv: Receiver<()>,
     done: Sender<Task>,
     values: Arc<Mutex<(bool, Vec<f32>)>>,
-    config: SpeedTestConfig,
+    running: Arc<AtomicBool>,
     id: String,
+    server_id: Option<u32>,
 ) {
     thread::Builder::new()
         .name("speedtest".into())
         .spawn(move || loop {
             if recv.recv().is_ok() {
-                if let Ok(output) = get_values(config.bytes) {
+                // Let the block show that a measurement is in progress
+                running.store(true, Ordering::SeqCst);
+                done.send(Task {
+                    id: id.clone(),
+                    update_time: Instant::now(),
+                })
+                .unwrap();
+
+                // Redraw regularly, so the spinner turns
+                let tick = || {
+                    done.send(Task {
+                        id: id.clone(),
+                        update_time: Instant::now(),
+                    })
+                    .unwrap()
+                };
+                if let Ok(output) = get_values(server_id, tick) {
                     if let Ok(vals) = parse_values(&output) {
                         if vals.len() == 3 {
                             let (ref mut update, ref mut values) = *values
@@ -140,15 +206,16 @@ fn make_thread(
                             *values = vals;
 
                             *update = true;
-
-                            done.send(Task {
-                                id: id.clone(),
-                                update_time: Instant::now(),
-                            })
-                            .unwrap();
                         }
                     }
                 }
+
+                running.store(false, Ordering::SeqCst);
+                done.send(Task {
+                    id: id.clone(),
+                    update_time: Instant::now(),
+                })
+                .unwrap();
             }
         })
         .unwrap();
@@ -161,15 +228,31 @@ impl ConfigBlock for SpeedTest {
         // Create all the things we are going to send and take for ourselves.
         let (send, recv): (Sender<()>, Receiver<()>) = unbounded();
         let vals = Arc::new(Mutex::new((false, vec![])));
+        let running = Arc::new(AtomicBool::new(false));
         let id = Uuid::new_v4().to_simple().to_string();
 
         // Make the update thread
-        make_thread(recv, done, vals.clone(), block_config.clone(), id.clone());
+        make_thread(
+            recv,
+            done,
+            vals.clone(),
+            running.clone(),
+            id.clone(),
+            block_config.server_id,
+        );
 
         let ty = if block_config.bytes { "MB/s" } else { "Mb/s" };
-        Ok(SpeedTest {
-            vals,
-            text: vec![
+        let format = match block_config.format {
+            Some(ref format) => Some(
+                FormatTemplate::from_string(format)
+                    .block_error("speedtest", "Invalid format specified")?,
+            ),
+            None => None,
+        };
+        let text = if format.is_some() {
+            vec![ButtonWidget::new(config, &id).with_icon("ping")]
+        } else {
+            vec![
                 ButtonWidget::new(config.clone(), &id)
                     .with_icon("ping")
                     .with_text("0ms"),
@@ -179,7 +262,17 @@ impl ConfigBlock for SpeedTest {
                 ButtonWidget::new(config, &id)
                     .with_icon("net_up")
                     .with_text(&format!("0{}", ty)),
-            ],
+            ]
+        };
+
+        Ok(SpeedTest {
+            vals,
+            running,
+            last_run: None,
+            text,
+            spinner_frame: None,
+            last_text: if format.is_some() { "" } else { "0ms" }.to_owned(),
+            format,
             id,
             send,
             config: block_config,
@@ -194,17 +287,31 @@ impl Block for SpeedTest {
             .lock()
             .block_error("speedtest", "mutext poisoned")?;
 
-        if *updated {
+        let next_update = if self.config.on_demand {
+            None
+        } else {
+            Some(self.config.interval.into())
+        };
+
+        if self.running.load(Ordering::SeqCst) {
+            // Show a spinner in the first widget until the measurement is done
+            let frame = self
+                .spinner_frame
+                .map_or(0, |frame| (frame + 1) % SPINNER.len());
+            self.spinner_frame = Some(frame);
+            self.text[0].set_text(SPINNER[frame].to_string());
+            for text in &mut self.text {
+                text.set_state(State::Info);
+            }
+            Ok(next_update)
+        } else if *updated {
             *updated = false;
+            self.spinner_frame = None;
 
             if vals.len() == 3 {
-                self.text[0].set_text(format!("{}ms", vals[0]));
-                let (down_bytes, up_bytes) = if self.config.bytes {
-                    (vals[1] * 1_000_000.0, vals[2] * 1_000_000.0)
-                } else {
-                    (vals[1] * 125_000.0, vals[2] * 125_000.0)
-                };
-                self.text[1].set_text(format!(
+                let ping = format!("{}ms", vals[0]);
+                let (down_bytes, up_bytes) = (vals[1], vals[2]);
+                let download = format!(
                     "{}/s",
                     format_speed(
                         down_bytes as u64,
@@ -212,8 +319,8 @@ impl Block for SpeedTest {
                         &self.config.speed_min_unit.to_string(),
                         !self.config.bytes
                     )
-                ));
-                self.text[2].set_text(format!(
+                );
+                let upload = format!(
                     "{}/s",
                     format_speed(
                         up_bytes as u64,
@@ -221,7 +328,22 @@ impl Block for SpeedTest {
                         &self.config.speed_min_unit.to_string(),
                         !self.config.bytes
                     )
-                ));
+                );
+
+                if let Some(ref format) = self.format {
+                    let values = map!(
+                        "{ping}" => ping,
+                        "{download}" => download,
+                        "{upload}" => upload
+                    );
+                    self.last_text = format.render_static_str(&values)?;
+                    self.text[0].set_text(self.last_text.clone());
+                } else {
+                    self.last_text = ping.clone();
+                    self.text[0].set_text(ping);
+                    self.text[1].set_text(download);
+                    self.text[2].set_text(upload);
+                }
 
                 // TODO: remove clippy workaround
                 #[allow(clippy::unknown_clippy_lints)]
@@ -231,18 +353,40 @@ impl Block for SpeedTest {
                             25.0 ; 60.0 => State::Info,
                             60.0 ; 100.0 => State::Warning
                 }));
+                for text in &mut self.text[1..] {
+                    text.set_state(State::Idle);
+                }
             }
 
             Ok(None)
         } else {
-            self.send.send(())?;
-            Ok(Some(self.config.interval.into()))
+            // Results are kept until the next measurement, which is either triggered by a click,
+            // or once the interval has passed (a failed measurement also lands here).
+            let due = self
+                .last_run
+                .map_or(true, |t| t.elapsed() >= self.config.interval);
+            if self.spinner_frame.take().is_some() {
+                // The measurement failed, show the previous result again
+                self.text[0].set_text(self.last_text.clone());
+            }
+            if !self.config.on_demand && due {
+                self.last_run = Some(Instant::now());
+                self.send.send(())?;
+            }
+            for text in &mut self.text[1..] {
+                text.set_state(State::Idle);
+            }
+            Ok(next_update)
         }
     }
 
     fn click(&mut self, e: &I3BarEvent) -> Result<()> {
         if let Some(ref name) = e.name {
-            if name.as_str() == self.id && e.button == MouseButton::Left {
+            if name.as_str() == self.id
+                && e.button == MouseButton::Left
+                && !self.running.load(Ordering::SeqCst)
+            {
+                self.last_run = Some(Instant::now());
                 self.send.send(())?;
             }
         }
@@ -0,0 +1,578 @@
+use std::fs::{self, File, OpenOptions, Permissions};
+use std::io::{ErrorKind, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use serde_json::value::Value;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::subprocess::spawn_child_async;
+use crate::util::{xdg_config_home, FormatTemplate};
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+const API_URL: &str = "https://api.spotify.com/v1/me/player";
+const AUTHORIZE_URL: &str = "https://accounts.spotify.com/authorize";
+const TOKEN_URL: &str = "https://accounts.spotify.com/api/token";
+const SCOPES: &str = "user-read-currently-playing user-modify-playback-state";
+/// How long to wait for the redirect after opening the consent page
+const LOGIN_TIMEOUT: Duration = Duration::from_secs(300);
+
+pub struct Spotify {
+    id: String,
+    text: ButtonWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    client_id: String,
+    redirect_port: u16,
+    token_file: PathBuf,
+    tokens: Option<Tokens>,
+    /// Set while the login thread is waiting for the redirect
+    logging_in: Arc<AtomicBool>,
+    visible: bool,
+    tx_update_request: Sender<Task>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SpotifyConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "SpotifyConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Client ID of the application registered on the Spotify developer dashboard
+    pub client_id: String,
+
+    /// Port of the local redirect URI, `http://127.0.0.1:<port>/callback`
+    #[serde(default = "SpotifyConfig::default_redirect_port")]
+    pub redirect_port: u16,
+
+    /// File where the access and refresh tokens are stored
+    #[serde(default = "SpotifyConfig::default_token_file")]
+    pub token_file: Option<String>,
+
+    /// Format override
+    #[serde(default = "SpotifyConfig::default_format")]
+    pub format: String,
+}
+
+impl SpotifyConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(5)
+    }
+
+    fn default_redirect_port() -> u16 {
+        8888
+    }
+
+    fn default_token_file() -> Option<String> {
+        None
+    }
+
+    fn default_format() -> String {
+        "{artist} - {title}".to_owned()
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Tokens {
+    access_token: String,
+    refresh_token: String,
+}
+
+impl Tokens {
+    fn load(path: &Path) -> Option<Self> {
+        let json: Value = serde_json::from_slice(&fs::read(path).ok()?).ok()?;
+        Some(Tokens {
+            access_token: json.pointer("/access_token")?.as_str()?.to_owned(),
+            refresh_token: json.pointer("/refresh_token")?.as_str()?.to_owned(),
+        })
+    }
+
+    fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .block_error("spotify", "failed to create the token file's directory")?;
+        }
+        let json = json!({
+            "access_token": self.access_token,
+            "refresh_token": self.refresh_token,
+        });
+        // Only readable by the user, also if the file was created by an older version.
+        let mut file = OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(path)
+            .block_error("spotify", "failed to open the token file")?;
+        file.set_permissions(Permissions::from_mode(0o600))
+            .block_error("spotify", "failed to restrict the token file's permissions")?;
+        file.write_all(json.to_string().as_bytes())
+            .block_error("spotify", "failed to write the token file")
+    }
+
+    /// Builds tokens from a response of the token endpoint. Refreshing may not return a
+    /// new refresh token, in which case the `previous` one stays valid.
+    fn from_response(response: &Value, previous: Option<&str>) -> Result<Self> {
+        let access_token = response
+            .pointer("/access_token")
+            .and_then(Value::as_str)
+            .block_error("spotify", "no access token in the response")?;
+        let refresh_token = response
+            .pointer("/refresh_token")
+            .and_then(Value::as_str)
+            .or(previous)
+            .block_error("spotify", "no refresh token in the response")?;
+
+        Ok(Tokens {
+            access_token: access_token.to_owned(),
+            refresh_token: refresh_token.to_owned(),
+        })
+    }
+}
+
+/// Runs curl with `input` on its standard input, which keeps the tokens passed that way
+/// out of its command line, visible to other users in `ps`.
+fn curl_with_input(args: &[&str], input: &str) -> Result<Output> {
+    let mut curl = Command::new("curl")
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .block_error("spotify", "failed to run curl")?;
+    curl.stdin
+        .take()
+        .block_error("spotify", "failed to write to curl")?
+        .write_all(input.as_bytes())
+        .block_error("spotify", "failed to write to curl")?;
+    curl.wait_with_output()
+        .block_error("spotify", "failed to run curl")
+}
+
+/// POSTs the `form` to the token endpoint.
+fn request_token(form: &[(&str, &str)]) -> Result<Value> {
+    // The form is encoded here, as `--data-urlencode @-` would encode the separators too.
+    let body = form
+        .iter()
+        .map(|(key, value)| format!("{}={}", url_encode(key), url_encode(value)))
+        .collect::<Vec<_>>()
+        .join("&");
+    let output = curl_with_input(
+        &[
+            "--fail",
+            "--silent",
+            "-m",
+            "10",
+            "--data-binary",
+            "@-",
+            TOKEN_URL,
+        ],
+        &body,
+    )?;
+    if !output.status.success() {
+        return Err(BlockError(
+            "spotify".to_owned(),
+            "failed to get an access token".to_owned(),
+        ));
+    }
+
+    serde_json::from_slice(&output.stdout).block_error("spotify", "invalid token response")
+}
+
+fn url_encode(text: &str) -> String {
+    text.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn base64_url_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+    let mut encoded = String::new();
+    for chunk in bytes.chunks(3) {
+        let n = chunk
+            .iter()
+            .enumerate()
+            .fold(0u32, |n, (i, &b)| n | (u32::from(b) << (16 - 8 * i)));
+        // Without padding, as required by PKCE.
+        for i in 0..=chunk.len() {
+            encoded.push(ALPHABET[((n >> (18 - 6 * i)) & 0x3f) as usize] as char);
+        }
+    }
+    encoded
+}
+
+/// Returns `len` random bytes, hex-encoded.
+fn random_hex(len: usize) -> Result<String> {
+    let mut random = vec![0u8; len];
+    File::open("/dev/urandom")
+        .and_then(|mut urandom| urandom.read_exact(&mut random))
+        .block_error("spotify", "failed to read /dev/urandom")?;
+    Ok(random.iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+/// Generates the PKCE code verifier and its S256 challenge.
+fn pkce_pair() -> Result<(String, String)> {
+    let verifier = random_hex(32)?;
+
+    let mut openssl = Command::new("openssl")
+        .args(&["dgst", "-sha256", "-binary"])
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .block_error("spotify", "failed to run openssl")?;
+    openssl
+        .stdin
+        .take()
+        .block_error("spotify", "failed to write to openssl")?
+        .write_all(verifier.as_bytes())
+        .block_error("spotify", "failed to write to openssl")?;
+    let digest = openssl
+        .wait_with_output()
+        .block_error("spotify", "failed to hash the code verifier")?
+        .stdout;
+
+    Ok((verifier, base64_url_encode(&digest)))
+}
+
+/// Runs the OAuth2 authorization code flow with PKCE: opens the consent page in the
+/// browser, waits for the redirect on `redirect_port` and exchanges the code for tokens.
+fn authorize(client_id: &str, redirect_port: u16, token_file: &Path) -> Result<()> {
+    let redirect_uri = format!("http://127.0.0.1:{}/callback", redirect_port);
+    let (verifier, challenge) = pkce_pair()?;
+    // Only a redirect with this state comes from the consent page we opened, which keeps
+    // other local processes and web pages from completing the login with their own code.
+    let state = random_hex(16)?;
+    let listener = TcpListener::bind(("127.0.0.1", redirect_port))
+        .block_error("spotify", "failed to listen for the OAuth redirect")?;
+
+    let url = format!(
+        "{}?response_type=code&client_id={}&scope={}&redirect_uri={}&code_challenge_method=S256&code_challenge={}&state={}",
+        AUTHORIZE_URL,
+        url_encode(client_id),
+        url_encode(SCOPES),
+        url_encode(&redirect_uri),
+        challenge,
+        state
+    );
+    spawn_child_async("xdg-open", &[&url]).block_error("spotify", "failed to open the browser")?;
+
+    let code = wait_for_code(&listener, &state)?;
+    let response = request_token(&[
+        ("grant_type", "authorization_code"),
+        ("code", &code),
+        ("redirect_uri", &redirect_uri),
+        ("client_id", client_id),
+        ("code_verifier", &verifier),
+    ])?;
+    Tokens::from_response(&response, None)?.save(token_file)
+}
+
+/// Waits up to `LOGIN_TIMEOUT` for the redirect carrying `state`, and returns its code.
+/// Requests without that state are answered with an error and ignored.
+fn wait_for_code(listener: &TcpListener, state: &str) -> Result<String> {
+    let deadline = Instant::now() + LOGIN_TIMEOUT;
+    listener
+        .set_nonblocking(true)
+        .block_error("spotify", "failed to listen for the OAuth redirect")?;
+
+    loop {
+        let stream = match listener.accept() {
+            Ok((stream, _)) => stream,
+            Err(ref e) if e.kind() == ErrorKind::WouldBlock => {
+                if Instant::now() > deadline {
+                    return Err(BlockError(
+                        "spotify".to_owned(),
+                        "timed out waiting for the OAuth redirect".to_owned(),
+                    ));
+                }
+                thread::sleep(Duration::from_millis(200));
+                continue;
+            }
+            Err(_) => {
+                return Err(BlockError(
+                    "spotify".to_owned(),
+                    "failed to accept the OAuth redirect".to_owned(),
+                ))
+            }
+        };
+
+        if let Some(params) = read_redirect(stream, state) {
+            return params.block_error("spotify", "authorization was denied");
+        }
+    }
+}
+
+/// Reads a request to the redirect URI and answers it. Returns `None` if it doesn't
+/// carry `state`, otherwise the code, if the user granted access.
+fn read_redirect(mut stream: TcpStream, state: &str) -> Option<Option<String>> {
+    stream.set_nonblocking(false).ok()?;
+    stream.set_read_timeout(Some(Duration::from_secs(5))).ok()?;
+    let mut buffer = [0; 4096];
+    let len = stream.read(&mut buffer).ok()?;
+    let request = String::from_utf8_lossy(&buffer[..len]);
+
+    // The first line of the redirect is "GET /callback?code=...&state=... HTTP/1.1"
+    let params: Vec<&str> = request
+        .split_whitespace()
+        .nth(1)
+        .map(|path| path.split(&['?', '&'][..]).skip(1).collect())
+        .unwrap_or_default();
+    let param = |name: &str| {
+        params
+            .iter()
+            .find_map(|param| param.strip_prefix(name)?.strip_prefix('='))
+    };
+
+    if param("state") != Some(state) {
+        let _ = stream.write_all(
+            b"HTTP/1.1 400 Bad Request\r\nContent-Type: text/plain\r\nConnection: close\r\n\r\n\
+              i3status-rust: unexpected login request.\n",
+        );
+        return None;
+    }
+    let _ = stream.write_all(
+        b"HTTP/1.1 200 OK\r\nContent-Type: text/plain\r\nConnection: close\r\n\r\n\
+          i3status-rust: you can close this page now.\n",
+    );
+    Some(param("code").map(str::to_owned))
+}
+
+impl ConfigBlock for Spotify {
+    type Config = SpotifyConfig;
+
+    fn new(
+        block_config: Self::Config,
+        config: Config,
+        tx_update_request: Sender<Task>,
+    ) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let token_file = block_config
+            .token_file
+            .map(PathBuf::from)
+            .unwrap_or_else(|| xdg_config_home().join("i3status-rust/spotify_token.json"));
+
+        Ok(Spotify {
+            text: ButtonWidget::new(config, &id).with_icon("music"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("spotify", "Invalid format specified")?,
+            client_id: block_config.client_id,
+            redirect_port: block_config.redirect_port,
+            tokens: Tokens::load(&token_file),
+            token_file,
+            logging_in: Arc::new(AtomicBool::new(false)),
+            visible: false,
+            tx_update_request,
+        })
+    }
+}
+
+impl Spotify {
+    fn refresh_tokens(&mut self) -> Result<()> {
+        let refresh_token = match self.tokens {
+            Some(ref tokens) => tokens.refresh_token.clone(),
+            None => return Ok(()),
+        };
+
+        match request_token(&[
+            ("grant_type", "refresh_token"),
+            ("refresh_token", &refresh_token),
+            ("client_id", &self.client_id),
+        ]) {
+            Ok(response) => {
+                let tokens = Tokens::from_response(&response, Some(&refresh_token))?;
+                tokens.save(&self.token_file)?;
+                self.tokens = Some(tokens);
+                Ok(())
+            }
+            Err(e) => {
+                // The refresh token was revoked, a new login is needed.
+                self.tokens = None;
+                Err(e)
+            }
+        }
+    }
+
+    /// Calls the player API, refreshing the access token once if it has expired.
+    /// Returns the HTTP status and the body.
+    fn api(&mut self, method: &str, endpoint: &str) -> Result<(u32, String)> {
+        for attempt in 0..2 {
+            let access_token = match self.tokens {
+                Some(ref tokens) => tokens.access_token.clone(),
+                None => break,
+            };
+            let output = curl_with_input(
+                &[
+                    "--silent",
+                    "-m",
+                    "5",
+                    "-X",
+                    method,
+                    "--header",
+                    "@-",
+                    "--header",
+                    "Content-Length: 0",
+                    "--write-out",
+                    "\n%{http_code}",
+                    &format!("{}{}", API_URL, endpoint),
+                ],
+                &format!("Authorization: Bearer {}\n", access_token),
+            )?;
+            let output = String::from_utf8_lossy(&output.stdout);
+            let (body, status) = output.split_at(output.rfind('\n').unwrap_or(0));
+            let status = status.trim().parse().unwrap_or(0);
+
+            if status == 401 && attempt == 0 {
+                self.refresh_tokens()?;
+                continue;
+            }
+            return Ok((status, body.to_owned()));
+        }
+
+        Err(BlockError("spotify".to_owned(), "not logged in".to_owned()))
+    }
+}
+
+impl Block for Spotify {
+    fn update(&mut self) -> Result<Option<Update>> {
+        if self.tokens.is_none() {
+            // The login thread may have written the token file in the meantime.
+            self.tokens = Tokens::load(&self.token_file);
+        }
+        if self.tokens.is_none() {
+            self.visible = true;
+            self.text.set_text("click to log in".to_owned());
+            self.text.set_state(State::Warning);
+            return Ok(Some(self.update_interval.into()));
+        }
+
+        let (status, body) = self.api("GET", "/currently-playing")?;
+        // 204 means that nothing is playing.
+        let json: Option<Value> = if status == 200 {
+            serde_json::from_str(&body).ok()
+        } else {
+            None
+        };
+        match json {
+            Some(ref json) if json.pointer("/item").map_or(false, |item| !item.is_null()) => {
+                let artist = json
+                    .pointer("/item/artists")
+                    .and_then(Value::as_array)
+                    .map(|artists| {
+                        artists
+                            .iter()
+                            .filter_map(|artist| artist.pointer("/name").and_then(Value::as_str))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    })
+                    .unwrap_or_default();
+                let progress = json.pointer("/progress_ms").and_then(Value::as_f64);
+                let duration = json.pointer("/item/duration_ms").and_then(Value::as_f64);
+                let progress_percent = match (progress, duration) {
+                    (Some(progress), Some(duration)) if duration > 0.0 => {
+                        (progress / duration * 100.0) as u32
+                    }
+                    _ => 0,
+                };
+
+                let values = map!(
+                    "{artist}" => artist,
+                    "{title}" => json.pointer("/item/name").and_then(Value::as_str).unwrap_or("").to_owned(),
+                    "{progress_percent}" => progress_percent.to_string()
+                );
+                self.text.set_text(self.format.render_static_str(&values)?);
+                let is_playing = json
+                    .pointer("/is_playing")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(false);
+                self.text
+                    .set_state(if is_playing { State::Info } else { State::Idle });
+                self.visible = true;
+            }
+            _ => self.visible = false,
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.visible {
+            vec![&self.text]
+        } else {
+            vec![]
+        }
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if !e.matches_name(self.id()) {
+            return Ok(());
+        }
+
+        if self.tokens.is_none() {
+            // Another click while the consent page is open doesn't start a second login.
+            if e.button == MouseButton::Left && !self.logging_in.swap(true, Ordering::SeqCst) {
+                self.text.set_text("logging in...".to_owned());
+                let client_id = self.client_id.clone();
+                let redirect_port = self.redirect_port;
+                let token_file = self.token_file.clone();
+                let id = self.id.clone();
+                let tx_update_request = self.tx_update_request.clone();
+                let logging_in = self.logging_in.clone();
+                thread::Builder::new()
+                    .name("spotify_login".into())
+                    .spawn(move || {
+                        if let Err(e) = authorize(&client_id, redirect_port, &token_file) {
+                            log::warn!(target: "spotify", "login failed: {}", e);
+                        }
+                        logging_in.store(false, Ordering::SeqCst);
+                        tx_update_request
+                            .send(Task {
+                                id,
+                                update_time: Instant::now(),
+                            })
+                            .unwrap();
+                    })
+                    .unwrap();
+            }
+            return Ok(());
+        }
+
+        let endpoint = match e.button {
+            MouseButton::Left => "/previous",
+            MouseButton::Right => "/next",
+            _ => return Ok(()),
+        };
+        self.api("POST", endpoint)?;
+        self.update()?;
+
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
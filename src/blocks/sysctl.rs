@@ -0,0 +1,174 @@
+use std::fs::read_to_string;
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+pub struct Sysctl {
+    id: String,
+    text: ButtonWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    key: String,
+    value: String,
+    toggle_values: Option<[String; 2]>,
+    toggle_command: FormatTemplate,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct SysctlConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "SysctlConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// The kernel parameter, e.g. "vm.swappiness"
+    pub key: String,
+
+    /// Two values to switch between on click
+    #[serde(default)]
+    pub toggle_values: Option<[String; 2]>,
+
+    /// Command setting the parameter, with {key} and {value} placeholders
+    #[serde(default = "SysctlConfig::default_toggle_command")]
+    pub toggle_command: String,
+
+    /// Format override
+    #[serde(default = "SysctlConfig::default_format")]
+    pub format: String,
+}
+
+impl SysctlConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_toggle_command() -> String {
+        "sudo -n sysctl -w {key}={value}".to_owned()
+    }
+
+    fn default_format() -> String {
+        "{key}: {value}".to_owned()
+    }
+}
+
+impl ConfigBlock for Sysctl {
+    type Config = SysctlConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        Ok(Sysctl {
+            text: ButtonWidget::new(config, &id),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("sysctl", "Invalid format specified")?,
+            key: block_config.key,
+            value: String::new(),
+            toggle_values: block_config.toggle_values,
+            toggle_command: FormatTemplate::from_string(&block_config.toggle_command)
+                .block_error("sysctl", "Invalid toggle_command specified")?,
+        })
+    }
+}
+
+impl Sysctl {
+    /// Reads the parameter from `/proc/sys`, where the dots of the key are slashes, or
+    /// with `sysctl` if it isn't there.
+    fn read_value(&self) -> Result<String> {
+        let path: PathBuf = ["/proc/sys"]
+            .iter()
+            .copied()
+            .chain(self.key.split('.'))
+            .collect();
+        if let Ok(value) = read_to_string(&path) {
+            return Ok(value.trim().to_owned());
+        }
+
+        let output = Command::new("sysctl")
+            .args(&["-n", &self.key])
+            .output()
+            .block_error("sysctl", "failed to run sysctl")?;
+        if !output.status.success() {
+            return Err(BlockError(
+                "sysctl".to_owned(),
+                format!("unknown key '{}'", self.key),
+            ));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().to_owned())
+    }
+}
+
+impl Block for Sysctl {
+    fn update(&mut self) -> Result<Option<Update>> {
+        self.value = self.read_value()?;
+
+        let values = map!(
+            "{key}" => self.key.clone(),
+            "{value}" => self.value.clone()
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+        // Highlight the second value, e.g. the "on" of a 0/1 switch.
+        self.text.set_state(match self.toggle_values {
+            Some([_, ref on]) if *on == self.value => State::Info,
+            _ => State::Idle,
+        });
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if !e.matches_name(self.id()) || e.button != MouseButton::Left {
+            return Ok(());
+        }
+        let new_value = match self.toggle_values {
+            Some([ref first, ref second]) if *first == self.value => second.clone(),
+            Some([ref first, _]) => first.clone(),
+            None => return Ok(()),
+        };
+
+        let values = map!(
+            "{key}" => self.key.clone(),
+            "{value}" => new_value
+        );
+        let command = self.toggle_command.render_static_str(&values)?;
+        let status = Command::new("sh")
+            .args(&["-c", &command])
+            .status()
+            .block_error("sysctl", "failed to run toggle_command")?;
+        if !status.success() {
+            return Err(BlockError(
+                "sysctl".to_owned(),
+                format!("'{}' failed", command),
+            ));
+        }
+
+        self.update()?;
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
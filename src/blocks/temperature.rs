@@ -1,4 +1,6 @@
 use std::collections::HashMap;
+use std::fs::{self, read_to_string};
+use std::path::Path;
 use std::process::Command;
 use std::time::Duration;
 
@@ -28,10 +30,40 @@ pub struct Temperature {
     maximum_info: i64,
     maximum_warning: i64,
     format: FormatTemplate,
+    source: TemperatureSource,
     chip: Option<String>,
     inputs: Option<Vec<String>>,
 }
 
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum TemperatureSource {
+    Auto,
+    LmSensors,
+    Acpi,
+    Sysfs,
+}
+
+impl Default for TemperatureSource {
+    fn default() -> Self {
+        TemperatureSource::Auto
+    }
+}
+
+impl TemperatureSource {
+    /// Picks lm_sensors if `sensors` is installed, otherwise sysfs if the kernel exposes
+    /// any hwmon device, and ACPI as last resort.
+    fn detect() -> Self {
+        if Command::new("sensors").arg("-v").output().is_ok() {
+            TemperatureSource::LmSensors
+        } else if fs::read_dir(HWMON_PATH).map_or(false, |mut dir| dir.next().is_some()) {
+            TemperatureSource::Sysfs
+        } else {
+            TemperatureSource::Acpi
+        }
+    }
+}
+
 #[derive(Deserialize, Debug, Default, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct TemperatureConfig {
@@ -66,6 +98,10 @@ pub struct TemperatureConfig {
     #[serde(default = "TemperatureConfig::default_format")]
     pub format: String,
 
+    /// Where to read the temperatures from
+    #[serde(default)]
+    pub source: TemperatureSource,
+
     /// Chip override
     #[serde(default = "TemperatureConfig::default_chip")]
     pub chip: Option<String>,
@@ -140,6 +176,10 @@ impl ConfigBlock for Temperature {
             maximum_warning: block_config.warning,
             format: FormatTemplate::from_string(&block_config.format)
                 .block_error("temperature", "Invalid format specified for temperature")?,
+            source: match block_config.source {
+                TemperatureSource::Auto => TemperatureSource::detect(),
+                source => source,
+            },
             chip: block_config.chip,
             inputs: block_config.inputs,
         })
@@ -149,8 +189,32 @@ impl ConfigBlock for Temperature {
 type SensorsOutput = HashMap<String, HashMap<String, serde_json::Value>>;
 type InputReadings = HashMap<String, f64>;
 
-impl Block for Temperature {
-    fn update(&mut self) -> Result<Option<Update>> {
+const HWMON_PATH: &str = "/sys/class/hwmon";
+
+/// Matches `name` against `pattern`, in which `*` stands for any number of characters.
+fn matches_wildcard(pattern: &str, name: &str) -> bool {
+    match pattern.find('*') {
+        None => pattern == name,
+        Some(pos) => {
+            let (prefix, rest) = (&pattern[..pos], &pattern[pos + 1..]);
+            name.starts_with(prefix)
+                && (prefix.len()..=name.len())
+                    .filter(|&i| name.is_char_boundary(i))
+                    .any(|i| matches_wildcard(rest, &name[i..]))
+        }
+    }
+}
+
+impl Temperature {
+    fn is_wanted_input(&self, input_name: &str) -> bool {
+        match self.inputs {
+            Some(ref whitelist) => whitelist.iter().any(|input| input == input_name),
+            None => true,
+        }
+    }
+
+    /// Reads the temperatures of the chips reported by `sensors -j`.
+    fn read_lm_sensors(&self) -> Result<Vec<f64>> {
         let mut args = vec!["-j"];
         if let Some(ref chip) = &self.chip {
             args.push(chip);
@@ -164,13 +228,11 @@ impl Block for Temperature {
         let parsed: SensorsOutput = serde_json::from_str(&output)
             .block_error("temperature", "sensors output is invalid")?;
 
-        let mut temperatures: Vec<i64> = Vec::new();
+        let mut temperatures = Vec::new();
         for (_chip, inputs) in parsed {
             for (input_name, input_values) in inputs {
-                if let Some(ref whitelist) = self.inputs {
-                    if !whitelist.contains(&input_name) {
-                        continue;
-                    }
+                if !self.is_wanted_input(&input_name) {
+                    continue;
                 }
 
                 let values_parsed: InputReadings = match serde_json::from_value(input_values) {
@@ -179,17 +241,110 @@ impl Block for Temperature {
                 };
 
                 for (value_name, value) in values_parsed {
-                    if !value_name.starts_with("temp") || !value_name.ends_with("input") {
-                        continue;
+                    if value_name.starts_with("temp") && value_name.ends_with("input") {
+                        temperatures.push(value);
                     }
+                }
+            }
+        }
 
-                    if value > -101f64 && value < 151f64 {
-                        temperatures.push(value as i64);
-                    } else {
-                        // This error is recoverable and therefore should not stop the program
-                        eprintln!("Temperature ({}) outside of range ([-100, 150])", value);
+        Ok(temperatures)
+    }
+
+    /// Reads the thermal zones reported by `acpi -t`, e.g. "Thermal 0: ok, 45.0 degrees C".
+    /// The chip is ignored, inputs are the names of the zones, e.g. "Thermal 0".
+    fn read_acpi(&self) -> Result<Vec<f64>> {
+        let output = Command::new("acpi")
+            .arg("-t")
+            .output()
+            .block_error("temperature", "failed to run acpi")?;
+
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let (zone, reading) = line.split_at(line.find(':')?);
+                if !self.is_wanted_input(zone.trim()) {
+                    return None;
+                }
+                let reading = reading.trim_end().strip_suffix(" degrees C")?;
+                reading.rsplit(' ').next()?.parse().ok()
+            })
+            .collect())
+    }
+
+    /// Reads the `temp*_input` files of the hwmon devices whose name matches the chip.
+    /// Inputs are matched against the labels of the temperatures, if they have one.
+    fn read_sysfs(&self) -> Result<Vec<f64>> {
+        let devices =
+            fs::read_dir(HWMON_PATH).block_error("temperature", "failed to read hwmon devices")?;
+
+        let mut temperatures = Vec::new();
+        for device in devices.flatten() {
+            let device = device.path();
+            let name = read_to_string(device.join("name")).unwrap_or_default();
+            if let Some(ref chip) = self.chip {
+                if !matches_wildcard(chip, name.trim()) {
+                    continue;
+                }
+            }
+
+            let files = match fs::read_dir(&device) {
+                Ok(files) => files,
+                Err(_) => continue,
+            };
+            for file in files.flatten() {
+                let file_name = file.file_name().to_string_lossy().into_owned();
+                let sensor = match file_name
+                    .strip_suffix("_input")
+                    .filter(|sensor| sensor.starts_with("temp"))
+                {
+                    Some(sensor) => sensor.to_owned(),
+                    None => continue,
+                };
+                if self.inputs.is_some() {
+                    let label = read_sysfs_value(&device.join(format!("{}_label", sensor)));
+                    if !label.map_or(false, |label| self.is_wanted_input(&label)) {
+                        continue;
                     }
                 }
+                // Temperatures are given in millidegrees.
+                if let Some(value) =
+                    read_sysfs_value(&file.path()).and_then(|value| value.parse::<f64>().ok())
+                {
+                    temperatures.push(value / 1000.0);
+                }
+            }
+        }
+
+        Ok(temperatures)
+    }
+}
+
+fn read_sysfs_value(path: &Path) -> Option<String> {
+    read_to_string(path)
+        .ok()
+        .map(|value| value.trim().to_owned())
+}
+
+impl Block for Temperature {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let readings = match self.source {
+            TemperatureSource::LmSensors | TemperatureSource::Auto => self.read_lm_sensors()?,
+            TemperatureSource::Acpi => self.read_acpi()?,
+            TemperatureSource::Sysfs => self.read_sysfs()?,
+        };
+
+        let mut temperatures: Vec<i64> = Vec::new();
+        for value in readings {
+            if value > -101f64 && value < 151f64 {
+                temperatures.push(value as i64);
+            } else {
+                // This error is recoverable and therefore should not stop the program
+                log::warn!(
+                    target: "temperature",
+                    "Temperature ({}) outside of range ([-100, 150])",
+                    value
+                );
             }
         }
 
@@ -0,0 +1,111 @@
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct TfWorkspace {
+    text: TextWidget,
+    id: String,
+    update_interval: Duration,
+    format: FormatTemplate,
+    path: String,
+    warn_on_default: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct TfWorkspaceConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "TfWorkspaceConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Directory of the terraform configuration
+    pub path: String,
+
+    /// Format override
+    #[serde(default = "TfWorkspaceConfig::default_format")]
+    pub format: String,
+
+    /// Show a warning while the default workspace is selected
+    #[serde(default = "TfWorkspaceConfig::default_warn_on_default")]
+    pub warn_on_default: bool,
+}
+
+impl TfWorkspaceConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_format() -> String {
+        "{workspace}".to_owned()
+    }
+
+    fn default_warn_on_default() -> bool {
+        false
+    }
+}
+
+impl ConfigBlock for TfWorkspace {
+    type Config = TfWorkspaceConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        Ok(TfWorkspace {
+            id: Uuid::new_v4().to_simple().to_string(),
+            update_interval: block_config.interval,
+            text: TextWidget::new(config).with_text("×"),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("tf_workspace", "Invalid format specified")?,
+            path: block_config.path,
+            warn_on_default: block_config.warn_on_default,
+        })
+    }
+}
+
+impl Block for TfWorkspace {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let output = Command::new("terraform")
+            .args(&["workspace", "show"])
+            .current_dir(&self.path)
+            .output()
+            .block_error("tf_workspace", "failed to run terraform")?;
+        let workspace = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+
+        if output.status.success() && !workspace.is_empty() {
+            self.text
+                .set_state(if self.warn_on_default && workspace == "default" {
+                    State::Warning
+                } else {
+                    State::Idle
+                });
+            let values = map!("{workspace}" => workspace);
+            self.text.set_text(self.format.render_static_str(&values)?);
+        } else {
+            self.text.set_text("×".to_owned());
+            self.text.set_state(State::Warning);
+        }
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -0,0 +1,252 @@
+use std::path::Path;
+use std::process::Command;
+use std::time::Duration;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::de::deserialize_duration;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::subprocess::spawn_child_async;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+/// Runs a command in `repo_path`, returning its output if it succeeded.
+fn run(program: &str, repo_path: &str, args: &[&str]) -> Result<Option<String>> {
+    let output = Command::new(program)
+        .current_dir(repo_path)
+        .args(args)
+        .output()
+        .block_error("vcs", &format!("failed to run {}", program))?;
+
+    if output.status.success() {
+        Ok(Some(
+            String::from_utf8_lossy(&output.stdout).trim().to_owned(),
+        ))
+    } else {
+        Ok(None)
+    }
+}
+
+trait VcsDevice {
+    /// The current branch, or `None` if the path isn't a repository.
+    fn branch(&self) -> Result<Option<String>>;
+    fn dirty(&self) -> Result<bool>;
+    /// The number of commits the branch is (ahead, behind) its upstream.
+    fn ahead_behind(&self) -> Result<(u64, u64)>;
+}
+
+struct GitDevice {
+    repo_path: String,
+}
+
+impl VcsDevice for GitDevice {
+    fn branch(&self) -> Result<Option<String>> {
+        run(
+            "git",
+            &self.repo_path,
+            &["rev-parse", "--abbrev-ref", "HEAD"],
+        )
+    }
+
+    fn dirty(&self) -> Result<bool> {
+        Ok(run("git", &self.repo_path, &["status", "--porcelain"])?
+            .map_or(false, |status| !status.is_empty()))
+    }
+
+    fn ahead_behind(&self) -> Result<(u64, u64)> {
+        // Fails if the branch has no upstream, in which case there's nothing to compare to.
+        Ok(run(
+            "git",
+            &self.repo_path,
+            &["rev-list", "--left-right", "--count", "@{upstream}...HEAD"],
+        )?
+        .and_then(|counts| {
+            let mut counts = counts.split_whitespace().map(str::parse::<u64>);
+            match (counts.next(), counts.next()) {
+                (Some(Ok(behind)), Some(Ok(ahead))) => Some((ahead, behind)),
+                _ => None,
+            }
+        })
+        .unwrap_or((0, 0)))
+    }
+}
+
+struct HgDevice {
+    repo_path: String,
+}
+
+impl VcsDevice for HgDevice {
+    fn branch(&self) -> Result<Option<String>> {
+        run("hg", &self.repo_path, &["identify", "-b"])
+    }
+
+    fn dirty(&self) -> Result<bool> {
+        Ok(run("hg", &self.repo_path, &["status"])?.map_or(false, |status| !status.is_empty()))
+    }
+
+    fn ahead_behind(&self) -> Result<(u64, u64)> {
+        // Comparing against the default path would need network access on every update.
+        Ok((0, 0))
+    }
+}
+
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum VcsDriver {
+    Auto,
+    Git,
+    Hg,
+}
+
+impl Default for VcsDriver {
+    fn default() -> Self {
+        VcsDriver::Auto
+    }
+}
+
+pub struct Vcs {
+    id: String,
+    text: ButtonWidget,
+    update_interval: Duration,
+    format: FormatTemplate,
+    device: Box<dyn VcsDevice>,
+    repo_path: String,
+    on_click: Option<String>,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct VcsConfig {
+    /// Update interval in seconds
+    #[serde(
+        default = "VcsConfig::default_interval",
+        deserialize_with = "deserialize_duration"
+    )]
+    pub interval: Duration,
+
+    /// Path of the repository to show
+    pub repo_path: String,
+
+    /// Which version control system the repository uses
+    #[serde(default)]
+    pub driver: VcsDriver,
+
+    /// Format override
+    #[serde(default = "VcsConfig::default_format")]
+    pub format: String,
+
+    /// Command to run in the repository on left click, e.g. "git fetch"
+    #[serde(default = "VcsConfig::default_on_click")]
+    pub on_click: Option<String>,
+}
+
+impl VcsConfig {
+    fn default_interval() -> Duration {
+        Duration::from_secs(10)
+    }
+
+    fn default_format() -> String {
+        "{branch}{dirty}".to_owned()
+    }
+
+    fn default_on_click() -> Option<String> {
+        None
+    }
+}
+
+impl ConfigBlock for Vcs {
+    type Config = VcsConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+        let repo_path = block_config.repo_path;
+
+        let driver = match block_config.driver {
+            VcsDriver::Auto if Path::new(&repo_path).join(".git").exists() => VcsDriver::Git,
+            VcsDriver::Auto if Path::new(&repo_path).join(".hg").exists() => VcsDriver::Hg,
+            VcsDriver::Auto => {
+                return Err(BlockError(
+                    "vcs".to_owned(),
+                    format!("{} is neither a git nor a mercurial repository", repo_path),
+                ))
+            }
+            driver => driver,
+        };
+        let device: Box<dyn VcsDevice> = match driver {
+            VcsDriver::Hg => Box::new(HgDevice {
+                repo_path: repo_path.clone(),
+            }),
+            _ => Box::new(GitDevice {
+                repo_path: repo_path.clone(),
+            }),
+        };
+
+        Ok(Vcs {
+            text: ButtonWidget::new(config, &id).with_text("×"),
+            id,
+            update_interval: block_config.interval,
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("vcs", "Invalid format specified")?,
+            device,
+            repo_path,
+            on_click: block_config.on_click,
+        })
+    }
+}
+
+impl Block for Vcs {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let branch = match self.device.branch()? {
+            Some(branch) => branch,
+            None => {
+                // Not a repository (anymore), or no commits yet.
+                self.text.set_text("×".to_owned());
+                self.text.set_state(State::Warning);
+                return Ok(Some(self.update_interval.into()));
+            }
+        };
+        let dirty = self.device.dirty()?;
+        let (ahead, behind) = self.device.ahead_behind()?;
+
+        let values = map!(
+            "{branch}" => branch,
+            "{dirty}" => if dirty { "*" } else { "" }.to_owned(),
+            "{ahead}" => ahead.to_string(),
+            "{behind}" => behind.to_string()
+        );
+        self.text.set_text(self.format.render_static_str(&values)?);
+        self.text
+            .set_state(if dirty { State::Warning } else { State::Idle });
+
+        Ok(Some(self.update_interval.into()))
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        vec![&self.text]
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if e.matches_name(self.id()) && e.button == MouseButton::Left {
+            if let Some(ref cmd) = self.on_click {
+                spawn_child_async(
+                    "sh",
+                    &["-c", &format!("cd '{}' && {}", self.repo_path, cmd)],
+                )
+                .block_error("vcs", "could not spawn child")?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
@@ -0,0 +1,118 @@
+use std::env;
+use std::path::Path;
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::scheduler::Task;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::text::TextWidget;
+
+pub struct Venv {
+    text: TextWidget,
+    id: String,
+    format: FormatTemplate,
+    hide_when_inactive: bool,
+    active: bool,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct VenvConfig {
+    /// Format override
+    #[serde(default = "VenvConfig::default_format")]
+    pub format: String,
+
+    /// Hide the block when no environment is active
+    #[serde(default = "VenvConfig::default_hide_when_inactive")]
+    pub hide_when_inactive: bool,
+}
+
+impl VenvConfig {
+    fn default_format() -> String {
+        "{name}".to_owned()
+    }
+
+    fn default_hide_when_inactive() -> bool {
+        true
+    }
+}
+
+impl ConfigBlock for Venv {
+    type Config = VenvConfig;
+
+    fn new(block_config: Self::Config, config: Config, _: Sender<Task>) -> Result<Self> {
+        Ok(Venv {
+            id: Uuid::new_v4().to_simple().to_string(),
+            text: TextWidget::new(config),
+            format: FormatTemplate::from_string(&block_config.format)
+                .block_error("venv", "Invalid format specified")?,
+            hide_when_inactive: block_config.hide_when_inactive,
+            active: false,
+        })
+    }
+}
+
+/// Returns the name and type of the active Python environment.
+fn active_env() -> Option<(String, &'static str)> {
+    // Poetry and plain virtualenvs both set VIRTUAL_ENV to the path of the environment.
+    if let Some(path) = env::var_os("VIRTUAL_ENV") {
+        let name = Path::new(&path)
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string_lossy().into_owned());
+        let kind = if env::var_os("POETRY_ACTIVE").is_some() {
+            "poetry"
+        } else {
+            "venv"
+        };
+        return Some((name, kind));
+    }
+
+    env::var("CONDA_DEFAULT_ENV")
+        .ok()
+        .map(|name| (name, "conda"))
+}
+
+impl Block for Venv {
+    fn update(&mut self) -> Result<Option<Update>> {
+        // The environment is inherited when the bar is started, so there is no need to
+        // check it more than once.
+        let env = active_env();
+        self.active = env.is_some();
+
+        match env {
+            Some((name, kind)) => {
+                let values = map!(
+                    "{name}" => name,
+                    "{type}" => kind.to_owned()
+                );
+                self.text.set_text(self.format.render_static_str(&values)?);
+                self.text.set_state(State::Good);
+            }
+            None => {
+                self.text.set_text("".to_owned());
+                self.text.set_state(State::Idle);
+            }
+        }
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        if self.active || !self.hide_when_inactive {
+            vec![&self.text]
+        } else {
+            vec![]
+        }
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
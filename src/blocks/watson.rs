@@ -12,7 +12,7 @@ use crate::de::deserialize_local_timestamp;
 use crate::errors::*;
 use crate::input::I3BarEvent;
 use crate::scheduler::Task;
-use crate::util::xdg_config_home;
+use crate::util::{xdg_config_home, FormatTemplate};
 use crate::widget::{I3BarWidget, State};
 use crate::widgets::button::ButtonWidget;
 use chrono::offset::Local;
@@ -27,6 +27,7 @@ pub struct Watson {
     text: ButtonWidget,
     state_path: PathBuf,
     show_time: bool,
+    format: Option<FormatTemplate>,
     prev_state: Option<WatsonState>,
     update_interval: Duration,
 }
@@ -46,6 +47,9 @@ pub struct WatsonConfig {
     /// Show time spent
     #[serde(default = "WatsonConfig::default_show_time")]
     pub show_time: bool,
+    /// Format override, replaces the default project/tags/time text while tracking
+    #[serde(default = "WatsonConfig::default_format")]
+    pub format: Option<String>,
 }
 
 impl WatsonConfig {
@@ -60,6 +64,9 @@ impl WatsonConfig {
     fn default_show_time() -> bool {
         false
     }
+    fn default_format() -> Option<String> {
+        None
+    }
 }
 
 impl ConfigBlock for Watson {
@@ -77,6 +84,13 @@ impl ConfigBlock for Watson {
             text: ButtonWidget::new(config, &id),
             state_path: block_config.state_path.clone(),
             show_time: block_config.show_time,
+            format: match block_config.format {
+                Some(ref format) => Some(
+                    FormatTemplate::from_string(format)
+                        .block_error("watson", "Invalid format specified")?,
+                ),
+                None => None,
+            },
             update_interval: block_config.interval,
             prev_state: None,
         };
@@ -142,6 +156,29 @@ impl Block for Watson {
         };
 
         match state {
+            WatsonState::Active {
+                ref project,
+                ref start,
+                ref tags,
+            } if self.format.is_some() => {
+                // Show seconds only if they can actually tick by
+                let with_seconds = self.update_interval < Duration::from_secs(60);
+                let values = map!(
+                    "{project}" => project.clone(),
+                    "{tags}" => tags.join(" "),
+                    "{elapsed}" => format_elapsed(&(Local::now() - *start), with_seconds)
+                );
+                let text = match self.format {
+                    Some(ref format) => format.render_static_str(&values)?,
+                    None => String::new(),
+                };
+                self.text.set_state(State::Good);
+                self.text.set_text(text);
+
+                self.prev_state = Some(state);
+                // keep the elapsed time ticking while tracking
+                Ok(Some(self.update_interval.into()))
+            }
             state @ WatsonState::Active { .. } => {
                 self.text.set_state(State::Good);
                 self.text
@@ -225,6 +262,19 @@ fn format_delta_past(delta: &chrono::Duration) -> String {
         .unwrap_or_else(|| "now".into())
 }
 
+fn format_elapsed(delta: &chrono::Duration, with_seconds: bool) -> String {
+    if with_seconds {
+        format!(
+            "{}:{:02}:{:02}",
+            delta.num_hours(),
+            delta.num_minutes() % 60,
+            delta.num_seconds() % 60
+        )
+    } else {
+        format!("{}:{:02}", delta.num_hours(), delta.num_minutes() % 60)
+    }
+}
+
 fn format_delta_after(delta: &chrono::Duration) -> String {
     let spans = &[
         ("week", delta.num_weeks()),
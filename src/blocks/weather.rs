@@ -11,6 +11,7 @@ use crate::blocks::{Block, ConfigBlock};
 use crate::config::Config;
 use crate::de::deserialize_duration;
 use crate::errors::*;
+use crate::http;
 use crate::input::{I3BarEvent, MouseButton};
 use crate::scheduler::Task;
 use crate::util::FormatTemplate;
@@ -27,10 +28,16 @@ pub enum WeatherService {
     OpenWeatherMap {
         #[serde(default = "WeatherService::getenv_openweathermap_api_key")]
         api_key: Option<String>,
+        #[serde(default)]
+        api_key_command: Option<String>,
         #[serde(default = "WeatherService::getenv_openweathermap_city_id")]
         city_id: Option<String>,
         #[serde(default = "WeatherService::getenv_openweathermap_place")]
         place: Option<String>,
+        #[serde(default)]
+        lat: Option<f64>,
+        #[serde(default)]
+        lon: Option<f64>,
         units: OpenWeatherMapUnits,
     },
 }
@@ -68,28 +75,57 @@ fn malformed_json_error() -> Error {
     BlockError("weather".to_string(), "Malformed JSON.".to_string())
 }
 
+// Maps OpenWeatherMap condition codes to emoji, see https://openweathermap.org/weather-conditions
+fn condition_to_emoji(id: i64) -> &'static str {
+    match id {
+        200..=299 => "\u{26c8}",        // thunderstorm
+        300..=399 => "\u{1f326}",       // drizzle
+        511 | 600..=699 => "\u{1f328}", // snow, freezing rain
+        500..=599 => "\u{1f327}",       // rain
+        700..=799 => "\u{1f32b}",       // fog, mist, haze...
+        800 => "\u{2600}",              // clear
+        801 => "\u{1f324}",             // few clouds
+        802 => "\u{26c5}",              // scattered clouds
+        803..=804 => "\u{2601}",        // broken/overcast clouds
+        _ => "\u{1f321}",
+    }
+}
+
 impl Weather {
     fn update_weather(&mut self) -> Result<()> {
         match self.service {
             WeatherService::OpenWeatherMap {
-                api_key: Some(ref api_key),
+                ref api_key,
+                ref api_key_command,
                 ref city_id,
                 ref place,
+                lat,
+                lon,
                 ref units,
             } => {
+                let api_key = match (api_key, api_key_command) {
+                    (Some(api_key), _) => api_key.clone(),
+                    (None, Some(cmd)) => Command::new("sh")
+                        .args(&["-c", cmd.as_str()])
+                        .output()
+                        .block_error("weather", "Failed to run 'service.api_key_command'.")
+                        .map(|o| String::from_utf8_lossy(&o.stdout).trim().to_owned())?,
+                    (None, None) => {
+                        return Err(BlockError(
+                            "weather".to_string(),
+                            format!(
+                                "Missing member 'service.api_key'. Add the member or configure with the environment variable {}",
+                                OPENWEATHERMAP_API_KEY_ENV
+                            ),
+                        ))
+                    }
+                };
+
                 // TODO: might be good to allow for different geolocation services to be used, similar to how we have `service` for the weather API
                 let geoip_city = if self.autolocate {
-                    let geoip_output = match Command::new("sh")
-                        .args(&["-c", "curl --max-time 3 --silent 'https://ipapi.co/json/'"])
-                        .output()
-                    {
-                        Ok(raw_output) => String::from_utf8(raw_output.stdout)
-                            .block_error("weather", "Failed to decode")?,
-                        Err(_) => {
-                            // We don't want the bar to crash if we can't reach the geoip service
-                            String::from("")
-                        }
-                    };
+                    // We don't want the bar to crash if we can't reach the geoip service
+                    let geoip_output = http::get("https://ipapi.co/json/", Duration::from_secs(3))
+                        .unwrap_or_default();
 
                     if geoip_output.is_empty() {
                         None
@@ -113,52 +149,39 @@ impl Weather {
                     format!("q={}", city)
                 } else if city_id.is_some() {
                     format!("id={}", city_id.as_ref().unwrap())
+                } else if let (Some(lat), Some(lon)) = (lat, lon) {
+                    format!("lat={}&lon={}", lat, lon)
                 } else if place.is_some() {
                     format!("q={}", place.as_ref().unwrap())
                 } else {
                     return Err(BlockError(
                         "weather".to_string(),
                         format!(
-                            "Either 'service.city_id' or 'service.place' must be provided. Add one to your config file or set with the environment variables {} or {}",
+                            "Either 'service.city_id', 'service.lat' and 'service.lon', or 'service.place' must be provided. Add one to your config file or set with the environment variables {} or {}",
                             OPENWEATHERMAP_CITY_ID_ENV.to_string(),
                             OPENWEATHERMAP_PLACE_ENV.to_string(),
                         ),
                     ));
                 };
-                let output = Command::new("sh")
-                    .args(&[
-                        "-c",
-                        // with these options curl will print http response body to stdout, http status code to stderr
-                        &format!(
-                            r#"curl -m 3 --silent \
-                                "https://api.openweathermap.org/data/2.5/weather?{location_query}&appid={api_key}&units={units}" \
-                                --write-out "%{{stderr}} %{{http_code}}""#,
-                            location_query = location_query,
-                            api_key = api_key,
-                            units = match *units {
-                                OpenWeatherMapUnits::Metric => "metric",
-                                OpenWeatherMapUnits::Imperial => "imperial",
-                            },
-                        ),
-                    ])
-                    .output()
-                    .block_error("weather", "Failed to execute curl.")
-                    .and_then(|raw_output| {
-                        let status_code = String::from_utf8(raw_output.stderr)
-                            .block_error("weather", "Invalid curl output")
-                            .and_then(|out|
-                                out.trim().parse::<i32>()
-                                    .block_error("weather", &format!("Unexpected curl output {}", out))
-                            )?;
-
-                        // All 300-399 and >500 http codes should be considered as temporary error,
-                        // and not result in block error, i.e. leave the output empty.
-                        match status_code {
-                            code if (code >= 300 && code < 400) || code >= 500 => Ok("".to_string()),
-                            _ => String::from_utf8(raw_output.stdout)
-                                .block_error("weather", "Received non-UTF8 characters in response."),
-                        }
-                    })?;
+                let url = format!(
+                    "https://api.openweathermap.org/data/2.5/weather?{location_query}&appid={api_key}&units={units}",
+                    location_query = location_query,
+                    api_key = api_key,
+                    units = match *units {
+                        OpenWeatherMapUnits::Metric => "metric",
+                        OpenWeatherMapUnits::Imperial => "imperial",
+                    },
+                );
+                // All 300-399 and >500 http codes should be considered as temporary error,
+                // and not result in block error, i.e. leave the output empty. So should failed
+                // requests.
+                let output = match http::request(&url, &[], Duration::from_secs(3)) {
+                    Ok(response) => match response.status {
+                        code if (300..400).contains(&code) || code >= 500 => String::new(),
+                        _ => response.body,
+                    },
+                    Err(_) => String::new(),
+                };
 
                 // Don't error out on empty responses e.g. for when not
                 // connected to the internet.
@@ -184,11 +207,32 @@ impl Weather {
                     .map(|s| s.to_string())
                     .ok_or_else(malformed_json_error)?;
 
+                let raw_description = json
+                    .pointer("/weather/0/description")
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.to_string())
+                    .unwrap_or_default();
+
+                let raw_condition = json
+                    .pointer("/weather/0/id")
+                    .and_then(|v| v.as_i64())
+                    .unwrap_or(0);
+
                 let raw_temp = json
                     .pointer("/main/temp")
                     .and_then(|v| v.as_f64())
                     .ok_or_else(malformed_json_error)?;
 
+                let raw_feels_like = json
+                    .pointer("/main/feels_like")
+                    .and_then(|v| v.as_f64())
+                    .unwrap_or(raw_temp);
+
+                let raw_pressure = json
+                    .pointer("/main/pressure")
+                    .map_or(Some(0.0), |v| v.as_f64()) // provide default value 0.0
+                    .ok_or_else(malformed_json_error)?;
+
                 let raw_humidity = json
                     .pointer("/main/humidity")
                     .map_or(Some(0.0), |v| v.as_f64()) // provide default value 0.0
@@ -275,22 +319,13 @@ impl Weather {
                                   "{apparent}" => format!("{:.0}",apparent_temp),
                                   "{wind}" => format!("{:.1}", raw_wind_speed),
                                   "{direction}" => convert_wind_direction(raw_wind_direction),
-                                  "{location}" => raw_location);
+                                  "{location}" => raw_location,
+                                  "{icon}" => condition_to_emoji(raw_condition),
+                                  "{description}" => raw_description,
+                                  "{feels_like}" => format!("{:.0}", raw_feels_like),
+                                  "{pressure}" => format!("{:.0}", raw_pressure));
                 Ok(())
             }
-            WeatherService::OpenWeatherMap { ref api_key, .. } => {
-                if api_key.is_none() {
-                    Err(BlockError(
-                        "weather".to_string(),
-                        format!(
-                            "Missing member 'service.api_key'. Add the member or configure with the environment variable {}",
-                            OPENWEATHERMAP_API_KEY_ENV.to_string()
-                        ),
-                    ))
-                } else {
-                    Ok(())
-                }
-            }
         }
     }
 }
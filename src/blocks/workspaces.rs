@@ -0,0 +1,186 @@
+use std::sync::{Arc, Mutex};
+
+use crossbeam_channel::Sender;
+use serde_derive::Deserialize;
+use swayipc::{Connection, EventType};
+use uuid::Uuid;
+
+use crate::blocks::{Block, ConfigBlock, Update};
+use crate::config::Config;
+use crate::errors::*;
+use crate::input::{I3BarEvent, MouseButton};
+use crate::scheduler::Task;
+use crate::util::i3ipc;
+use crate::util::FormatTemplate;
+use crate::widget::{I3BarWidget, State};
+use crate::widgets::button::ButtonWidget;
+
+/// The parts of a workspace the block displays.
+#[derive(Clone, Debug)]
+struct Workspace {
+    name: String,
+    num: i32,
+    focused: bool,
+    urgent: bool,
+}
+
+pub struct Workspaces {
+    id: String,
+    buttons: Vec<ButtonWidget>,
+    workspaces: Arc<Mutex<Vec<Workspace>>>,
+    format_focused: FormatTemplate,
+    format_unfocused: FormatTemplate,
+    format_urgent: FormatTemplate,
+    config: Config,
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct WorkspacesConfig {
+    /// Format of the focused workspace
+    #[serde(default = "WorkspacesConfig::default_format")]
+    pub format_focused: String,
+
+    /// Format of the other workspaces
+    #[serde(default = "WorkspacesConfig::default_format")]
+    pub format_unfocused: String,
+
+    /// Format of workspaces with an urgent window
+    #[serde(default = "WorkspacesConfig::default_format")]
+    pub format_urgent: String,
+}
+
+impl WorkspacesConfig {
+    fn default_format() -> String {
+        "{name}".to_owned()
+    }
+}
+
+fn get_workspaces(conn: &mut Connection) -> Result<Vec<Workspace>> {
+    Ok(conn
+        .get_workspaces()
+        .block_error("workspaces", "failed to get the workspaces")?
+        .into_iter()
+        .map(|workspace| Workspace {
+            name: workspace.name,
+            num: workspace.num,
+            focused: workspace.focused,
+            urgent: workspace.urgent,
+        })
+        .collect())
+}
+
+impl ConfigBlock for Workspaces {
+    type Config = WorkspacesConfig;
+
+    fn new(block_config: Self::Config, config: Config, tx: Sender<Task>) -> Result<Self> {
+        let id = Uuid::new_v4().to_simple().to_string();
+
+        let mut conn = i3ipc::connect("workspaces")?;
+        let workspaces = Arc::new(Mutex::new(get_workspaces(&mut conn)?));
+
+        // Events only describe the workspaces involved, so query all of them to keep
+        // their order and states consistent.
+        let workspaces_clone = workspaces.clone();
+        i3ipc::subscribe(
+            "workspaces",
+            &[EventType::Workspace],
+            id.clone(),
+            tx,
+            move |_| match get_workspaces(&mut conn) {
+                Ok(new_workspaces) => {
+                    *workspaces_clone
+                        .lock()
+                        .expect("lock has been poisoned in `workspaces` block") = new_workspaces;
+                    true
+                }
+                Err(_) => false,
+            },
+        )?;
+
+        Ok(Workspaces {
+            id,
+            buttons: Vec::new(),
+            workspaces,
+            format_focused: FormatTemplate::from_string(&block_config.format_focused)
+                .block_error("workspaces", "Invalid format_focused specified")?,
+            format_unfocused: FormatTemplate::from_string(&block_config.format_unfocused)
+                .block_error("workspaces", "Invalid format_unfocused specified")?,
+            format_urgent: FormatTemplate::from_string(&block_config.format_urgent)
+                .block_error("workspaces", "Invalid format_urgent specified")?,
+            config,
+        })
+    }
+}
+
+impl Workspaces {
+    /// The name of the button of the workspace `name`, which is used to route clicks.
+    fn button_name(&self, name: &str) -> String {
+        format!("{}:{}", self.id, name)
+    }
+}
+
+impl Block for Workspaces {
+    fn update(&mut self) -> Result<Option<Update>> {
+        let workspaces = self
+            .workspaces
+            .lock()
+            .block_error("workspaces", "failed to acquire lock")?
+            .clone();
+
+        let mut buttons = Vec::with_capacity(workspaces.len());
+        for workspace in workspaces {
+            let (format, state) = if workspace.urgent {
+                (&self.format_urgent, State::Critical)
+            } else if workspace.focused {
+                (&self.format_focused, State::Info)
+            } else {
+                (&self.format_unfocused, State::Idle)
+            };
+            let values = map!(
+                "{name}" => workspace.name.clone(),
+                "{num}" => workspace.num.to_string()
+            );
+            buttons.push(
+                ButtonWidget::new(self.config.clone(), &self.button_name(&workspace.name))
+                    .with_text(&format.render_static_str(&values)?)
+                    .with_state(state),
+            );
+        }
+        self.buttons = buttons;
+
+        Ok(None)
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        self.buttons
+            .iter()
+            .map(|button| button as &dyn I3BarWidget)
+            .collect()
+    }
+
+    fn click(&mut self, e: &I3BarEvent) -> Result<()> {
+        if e.button != MouseButton::Left {
+            return Ok(());
+        }
+        let prefix = self.button_name("");
+        let name = match e.name {
+            Some(ref name) if name.starts_with(&prefix) => &name[prefix.len()..],
+            _ => return Ok(()),
+        };
+
+        let command = format!(
+            "workspace \"{}\"",
+            name.replace('\\', "\\\\").replace('"', "\\\"")
+        );
+        i3ipc::connect("workspaces")?
+            .run_command(&command)
+            .block_error("workspaces", "failed to switch workspace")?;
+
+        Ok(())
+    }
+
+    fn id(&self) -> &str {
+        &self.id
+    }
+}
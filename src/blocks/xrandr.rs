@@ -9,12 +9,12 @@ use uuid::Uuid;
 
 use crate::blocks::Update;
 use crate::blocks::{Block, ConfigBlock};
-use crate::config::{Config, LogicalDirection};
+use crate::config::Config;
 use crate::de::deserialize_duration;
 use crate::errors::*;
 use crate::input::{I3BarEvent, MouseButton};
 use crate::scheduler::Task;
-use crate::util::FormatTemplate;
+use crate::util::{handle_scroll, FormatTemplate};
 use crate::widget::I3BarWidget;
 use crate::widgets::button::ButtonWidget;
 
@@ -280,24 +280,22 @@ impl Block for Xrandr {
                             self.current_idx = 0;
                         }
                     }
-                    mb => {
-                        use LogicalDirection::*;
-                        match self.config.scrolling.to_logical_direction(mb) {
-                            Some(Up) => {
-                                if let Some(monitor) = self.monitors.get_mut(self.current_idx) {
-                                    if monitor.brightness <= (100 - self.step_width) {
-                                        monitor.set_brightness(self.step_width as i32);
+                    _ => {
+                        if let Some(monitor) = self.monitors.get_mut(self.current_idx) {
+                            let step_width = self.step_width;
+                            handle_scroll(
+                                self.config.scrolling,
+                                e,
+                                i64::from(step_width),
+                                |step| {
+                                    if (step > 0 && monitor.brightness <= (100 - step_width))
+                                        || (step < 0 && monitor.brightness >= step_width)
+                                    {
+                                        monitor.set_brightness(step as i32);
                                     }
-                                }
-                            }
-                            Some(Down) => {
-                                if let Some(monitor) = self.monitors.get_mut(self.current_idx) {
-                                    if monitor.brightness >= self.step_width {
-                                        monitor.set_brightness(-(self.step_width as i32));
-                                    }
-                                }
-                            }
-                            None => {}
+                                    Ok(())
+                                },
+                            )?;
                         }
                     }
                 }
@@ -29,6 +29,19 @@ pub struct Config {
     pub scrolling: Scrolling,
     #[serde(rename = "block", deserialize_with = "deserialize_blocks")]
     pub blocks: Vec<(String, value::Value)>,
+    /// How the bar reacts to its blocks, from the `[behavior]` section
+    #[serde(default)]
+    pub behavior: Behavior,
+    /// Whether i3bar draws its own separator after the widgets of a block. This is
+    /// not a global option, but set per block from the block's configuration.
+    #[serde(skip)]
+    pub separator: Option<bool>,
+    /// Gap in pixels after the widgets of a block, set like `separator`.
+    #[serde(skip)]
+    pub separator_block_width: Option<u32>,
+    /// Border around the widgets of a block, set like `separator`.
+    #[serde(skip)]
+    pub border: Border,
 }
 
 impl Default for Config {
@@ -38,6 +51,50 @@ impl Default for Config {
             theme: Theme::default(),
             scrolling: Scrolling::default(),
             blocks: Vec::new(),
+            behavior: Behavior::default(),
+            separator: None,
+            separator_block_width: None,
+            border: Border::default(),
+        }
+    }
+}
+
+#[derive(Deserialize, Debug, Default, Clone)]
+#[serde(deny_unknown_fields)]
+pub struct Behavior {
+    /// Shell command run when a block fails, with the block name and the error message in
+    /// the `I3RS_BLOCK_NAME` and `I3RS_ERROR` environment variables
+    #[serde(default)]
+    pub on_error: Option<String>,
+}
+
+/// A border drawn by swaybar around the widgets of a block. i3bar ignores it.
+#[derive(Debug, Default, Clone)]
+pub struct Border {
+    pub color: Option<String>,
+    /// Widths in pixels, swaybar draws 1 pixel wide borders by default
+    pub top: Option<u32>,
+    pub bottom: Option<u32>,
+    pub left: Option<u32>,
+    pub right: Option<u32>,
+}
+
+impl Border {
+    /// Adds the swaybar keys of the configured parts of the border to a rendered widget.
+    pub fn render(&self, rendered: &mut serde_json::Value) {
+        if let Some(ref color) = self.color {
+            rendered["border"] = serde_json::Value::from(color.as_str());
+        }
+        let widths = [
+            ("border_top", self.top),
+            ("border_bottom", self.bottom),
+            ("border_left", self.left),
+            ("border_right", self.right),
+        ];
+        for &(key, width) in &widths {
+            if let Some(width) = width {
+                rendered[key] = serde_json::Value::from(width);
+            }
         }
     }
 }
@@ -52,6 +109,10 @@ impl From<LegacyConfig> for Config {
                 .unwrap_or_default(),
             scrolling: legacy_config.scrolling,
             blocks: legacy_config.blocks,
+            behavior: legacy_config.behavior,
+            separator: None,
+            separator_block_width: None,
+            border: Border::default(),
         }
     }
 }
@@ -71,6 +132,8 @@ pub struct LegacyConfig {
     pub scrolling: Scrolling,
     #[serde(rename = "block", deserialize_with = "deserialize_blocks")]
     pub blocks: Vec<(String, value::Value)>,
+    #[serde(default)]
+    pub behavior: Behavior,
 }
 
 impl Default for LegacyConfig {
@@ -80,31 +143,45 @@ impl Default for LegacyConfig {
             theme: None,
             scrolling: Scrolling::default(),
             blocks: Vec::new(),
+            behavior: Behavior::default(),
         }
     }
 }
 
 #[derive(Deserialize, Copy, Clone, Debug)]
-#[serde(rename_all = "lowercase")]
+#[serde(rename_all = "snake_case")]
 pub enum Scrolling {
     Reverse,
     Natural,
+    /// Natural scrolling for the vertical axis only
+    NaturalVertical,
+    /// Natural scrolling for the horizontal axis only
+    NaturalHorizontal,
 }
 
 #[derive(Copy, Clone, Debug)]
 pub enum LogicalDirection {
     Up,
     Down,
+    Left,
+    Right,
 }
 
 impl Scrolling {
     pub fn to_logical_direction(self, button: MouseButton) -> Option<LogicalDirection> {
-        use LogicalDirection::*;
         use MouseButton::*;
         use Scrolling::*;
-        match (self, button) {
-            (Reverse, WheelUp) | (Natural, WheelDown) => Some(Up),
-            (Reverse, WheelDown) | (Natural, WheelUp) => Some(Down),
+        let (natural_vertical, natural_horizontal) = match self {
+            Reverse => (false, false),
+            Natural => (true, true),
+            NaturalVertical => (true, false),
+            NaturalHorizontal => (false, true),
+        };
+        match (button, natural_vertical, natural_horizontal) {
+            (WheelUp, false, _) | (WheelDown, true, _) => Some(LogicalDirection::Up),
+            (WheelDown, false, _) | (WheelUp, true, _) => Some(LogicalDirection::Down),
+            (WheelLeft, _, false) | (WheelRight, _, true) => Some(LogicalDirection::Left),
+            (WheelRight, _, false) | (WheelLeft, _, true) => Some(LogicalDirection::Right),
             _ => None,
         }
     }
@@ -133,6 +210,9 @@ where
     Ok(blocks)
 }
 
+/// Icon sets consulted for icons missing from the configured set, unless `fallback` is given.
+const DEFAULT_ICONS_FALLBACK: &[&str] = &["none"];
+
 fn deserialize_icons<'de, D>(deserializer: D) -> Result<Map<String, String>, D::Error>
 where
     D: Deserializer<'de>,
@@ -140,7 +220,39 @@ where
     map_type!(Icons, String;
               s => Ok(Icons(icons::get_icons(s).ok_or("cannot find specified icons")?)));
 
-    deserializer.deserialize_any(MapType::<Icons, String>(PhantomData, PhantomData))
+    let mut raw = value::Value::deserialize(deserializer)?;
+    let fallback: Vec<String> = match raw {
+        value::Value::Table(ref mut table) => match table.remove("fallback") {
+            Some(value::Value::String(name)) => vec![name],
+            Some(names) => Vec::<String>::deserialize(names)
+                .map_err(|e: toml::de::Error| D::Error::custom(e.to_string()))?,
+            None => DEFAULT_ICONS_FALLBACK
+                .iter()
+                .map(|&s| s.to_owned())
+                .collect(),
+        },
+        _ => DEFAULT_ICONS_FALLBACK
+            .iter()
+            .map(|&s| s.to_owned())
+            .collect(),
+    };
+
+    let icons = raw
+        .deserialize_any(MapType::<Icons, String>(PhantomData, PhantomData))
+        .map_err(|e: toml::de::Error| D::Error::custom(e.to_string()))?;
+
+    // Icons missing from the configured set are looked up in the fallback sets, in order,
+    // instead of rendering as nothing.
+    let mut combined: Map<String, String> = Map::new();
+    for name in fallback.iter().rev() {
+        combined.extend(
+            icons::get_icons(name)
+                .ok_or_else(|| D::Error::custom(format!("cannot find fallback icons {}", name)))?,
+        );
+    }
+    combined.extend(icons);
+
+    Ok(combined)
 }
 
 fn deserialize_themes<'de, D>(deserializer: D) -> Result<Theme, D::Error>
@@ -163,7 +275,8 @@ pub fn load_config(config_path: &Path) -> errors::Result<Config> {
 }
 #[cfg(test)]
 mod tests {
-    use crate::config::load_config;
+    use crate::config::{load_config, Border};
+    use crate::icons;
     use assert_fs::prelude::{FileWriteStr, PathChild};
     use assert_fs::TempDir;
 
@@ -209,4 +322,98 @@ mod tests {
         let config = load_config(config_file_path.path());
         config.unwrap();
     }
+
+    #[test]
+    fn test_load_config_icons_fallback() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_file_path = temp_dir.child("status.toml");
+        config_file_path
+            .write_str(
+                concat!(
+                    "[icons]\n",
+                    "name = \"material\"\n",
+                    "[icons.overrides]\n",
+                    "cpu = \"C\"\n",
+                    "[[block]]\n",
+                    "block = \"load\"\n",
+                )
+                .as_ref(),
+            )
+            .unwrap();
+        let config = load_config(config_file_path.path()).unwrap();
+        assert_eq!(config.icons.get("cpu").unwrap(), "C");
+        assert_eq!(config.icons.get("time"), icons::MATERIAL.get("time"));
+        // Not part of the material set, so taken from the default fallback.
+        assert_eq!(
+            config.icons.get("backlight_full"),
+            icons::NONE.get("backlight_full")
+        );
+
+        config_file_path
+            .write_str(
+                concat!(
+                    "[icons]\n",
+                    "name = \"material\"\n",
+                    "fallback = []\n",
+                    "[[block]]\n",
+                    "block = \"load\"\n",
+                )
+                .as_ref(),
+            )
+            .unwrap();
+        let config = load_config(config_file_path.path()).unwrap();
+        assert_eq!(config.icons.get("backlight_full"), None);
+    }
+
+    #[test]
+    fn test_load_config_behavior() {
+        let temp_dir = TempDir::new().unwrap();
+        let config_file_path = temp_dir.child("status.toml");
+        config_file_path
+            .write_str(concat!("[[block]]\n", "block = \"load\"\n",).as_ref())
+            .unwrap();
+        let config = load_config(config_file_path.path()).unwrap();
+        assert_eq!(config.behavior.on_error, None);
+
+        config_file_path
+            .write_str(
+                concat!(
+                    "[behavior]\n",
+                    "on_error = \"notify-send \\\"$I3RS_ERROR\\\"\"\n",
+                    "[[block]]\n",
+                    "block = \"load\"\n",
+                )
+                .as_ref(),
+            )
+            .unwrap();
+        let config = load_config(config_file_path.path()).unwrap();
+        assert_eq!(
+            config.behavior.on_error.as_deref(),
+            Some("notify-send \"$I3RS_ERROR\"")
+        );
+    }
+
+    #[test]
+    fn test_border_render() {
+        let mut rendered = json!({ "full_text": "" });
+        Border::default().render(&mut rendered);
+        assert_eq!(rendered, json!({ "full_text": "" }));
+
+        let border = Border {
+            color: Some("#ff0000".to_owned()),
+            bottom: Some(2),
+            left: Some(0),
+            ..Border::default()
+        };
+        border.render(&mut rendered);
+        assert_eq!(
+            rendered,
+            json!({
+                "full_text": "",
+                "border": "#ff0000",
+                "border_bottom": 2,
+                "border_left": 0
+            })
+        );
+    }
 }
@@ -0,0 +1,103 @@
+//! Watching D-Bus signals on a background thread, for blocks that update on signals.
+
+use std::thread;
+use std::time::{Duration, Instant};
+
+use ::dbus::arg::ReadAll;
+use ::dbus::blocking::Connection;
+use ::dbus::channel::{BusType, Channel};
+use ::dbus::message::MatchRule;
+use ::dbus::strings::{Interface, Member, Path};
+use crossbeam_channel::Sender;
+
+use crate::errors::*;
+use crate::scheduler::Task;
+
+/// A private connection to a bus, with handlers for the signals a block is interested in.
+///
+/// Handlers receive the arguments of a signal, read as `S`, and return whether the block
+/// should be updated. Once all handlers are added, `start` processes the signals on a
+/// separate thread.
+pub struct DbusSignalStream {
+    block: &'static str,
+    con: Connection,
+    id: String,
+    update_request: Sender<Task>,
+}
+
+impl DbusSignalStream {
+    pub fn new(
+        block: &'static str,
+        bus: BusType,
+        id: String,
+        update_request: Sender<Task>,
+    ) -> Result<Self> {
+        let channel = Channel::get_private(bus)
+            .block_error(block, "Failed to establish D-Bus connection.")?;
+
+        Ok(DbusSignalStream {
+            block,
+            con: Connection::from(channel),
+            id,
+            update_request,
+        })
+    }
+
+    /// Calls `handler` for the signals `member` of `interface`, sent by the object at
+    /// `path` or by any object if `path` is `None`.
+    pub fn on_signal<S, F>(
+        self,
+        path: Option<&str>,
+        interface: &str,
+        member: &str,
+        mut handler: F,
+    ) -> Result<Self>
+    where
+        S: ReadAll,
+        F: FnMut(S) -> bool + Send + 'static,
+    {
+        let interface = Interface::new(interface.to_owned())
+            .map_err(|e| BlockError(self.block.to_owned(), e))?;
+        let member =
+            Member::new(member.to_owned()).map_err(|e| BlockError(self.block.to_owned(), e))?;
+        let mut rule = MatchRule::new_signal(interface, member);
+        if let Some(path) = path {
+            rule.path =
+                Some(Path::new(path.to_owned()).map_err(|e| BlockError(self.block.to_owned(), e))?);
+        }
+
+        let id = self.id.clone();
+        let update_request = self.update_request.clone();
+        self.con
+            .add_match(rule, move |args: S, _, _| {
+                if handler(args) {
+                    update_request
+                        .send(Task {
+                            id: id.clone(),
+                            update_time: Instant::now(),
+                        })
+                        .unwrap();
+                }
+                // Keep the handler.
+                true
+            })
+            .block_error(self.block, "Failed to add D-Bus match rule.")?;
+
+        Ok(self)
+    }
+
+    /// Processes incoming signals on a separate thread.
+    pub fn start(self) {
+        let block = self.block;
+        let con = self.con;
+        thread::Builder::new()
+            .name(block.into())
+            .spawn(move || loop {
+                if let Err(e) = con.process(Duration::from_secs(60)) {
+                    log::warn!(target: block, "D-Bus connection failed: {}", e);
+                    break;
+                }
+            })
+            .unwrap();
+    }
+}
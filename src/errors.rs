@@ -1,13 +1,19 @@
 pub use std::error::Error as StdError;
 use std::fmt;
 
-pub use self::Error::{BlockError, ConfigurationError, InternalError};
+pub use self::Error::{BlockError, ConfigurationError, InternalError, Unavailable};
 
 /// Result type returned from functions that can have our `Error`s.
 pub type Result<T> = ::std::result::Result<T, Error>;
 
 pub trait ResultExtBlock<T, E> {
     fn block_error(self, block: &str, message: &str) -> Result<T>;
+
+    /// Like `block_error`, but keeps the underlying error and its sources in the message,
+    /// e.g. "reading alsa device: No such file or directory (os error 2)".
+    fn block_error_context(self, block: &str, message: &str) -> Result<T>
+    where
+        E: StdError;
 }
 
 pub trait ResultExtInternal<T, E> {
@@ -19,6 +25,21 @@ impl<T, E> ResultExtBlock<T, E> for ::std::result::Result<T, E> {
     fn block_error(self, block: &str, message: &str) -> Result<T> {
         self.map_err(|_| BlockError(block.to_owned(), message.to_owned()))
     }
+
+    fn block_error_context(self, block: &str, message: &str) -> Result<T>
+    where
+        E: StdError,
+    {
+        self.map_err(|e| {
+            let mut message = format!("{}: {}", message, e);
+            let mut source = e.source();
+            while let Some(cause) = source {
+                message.push_str(&format!(": {}", cause));
+                source = cause.source();
+            }
+            BlockError(block.to_owned(), message)
+        })
+    }
 }
 
 impl<T, E> ResultExtInternal<T, E> for ::std::result::Result<T, E>
@@ -62,6 +83,9 @@ pub enum Error {
     BlockError(String, String),
     ConfigurationError(String, (String, String)),
     InternalError(String, String, Option<(String, String)>),
+    /// Returned by a block when it doesn't apply to this system, e.g. a battery block on a
+    /// computer without a battery. The block is left out of the bar instead of failing.
+    Unavailable(String, String),
 }
 
 impl fmt::Display for Error {
@@ -77,6 +101,9 @@ impl fmt::Display for Error {
                 "Internal error in context '{}': {}",
                 context, message
             )),
+            Unavailable(ref block, ref message) => {
+                f.write_str(&format!("Block '{}' is unavailable: {}", block, message))
+            }
         }
     }
 }
@@ -99,6 +126,9 @@ impl fmt::Debug for Error {
                 "Internal error in context '{}': {}",
                 context, message
             )),
+            Unavailable(ref block, ref message) => {
+                f.write_str(&format!("Block '{}' is unavailable: {}", block, message))
+            }
         }
     }
 }
@@ -109,6 +139,7 @@ impl StdError for Error {
             BlockError(_, _) => "Block error occurred in block '{}'",
             ConfigurationError(_, _) => "Configuration error occurred",
             InternalError(_, _, _) => "Internal error occurred",
+            Unavailable(_, _) => "Block is unavailable",
         }
     }
 
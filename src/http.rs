@@ -0,0 +1,133 @@
+//! HTTP requests for the blocks, made with libcurl. The handles are shared by all blocks
+//! and keep their connections alive, so that polling the same server again reuses the
+//! connection instead of repeating the TLS handshake.
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use curl::easy::{Easy, List};
+use lazy_static::lazy_static;
+
+use crate::errors::*;
+
+lazy_static! {
+    /// The idle handles. A request takes one, or creates it if all are in use, and puts it
+    /// back once done.
+    static ref HANDLES: Mutex<Vec<Easy>> = Mutex::new(Vec::new());
+}
+
+/// A response, whatever its status.
+pub struct Response {
+    pub status: u32,
+    /// The header lines, like `Link: <...>; rel="next"`
+    pub headers: Vec<String>,
+    pub body: String,
+}
+
+impl Response {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find_map(|line| {
+            let mut parts = line.splitn(2, ':');
+            match (parts.next(), parts.next()) {
+                (Some(key), Some(value)) if key.eq_ignore_ascii_case(name) => Some(value.trim()),
+                _ => None,
+            }
+        })
+    }
+}
+
+/// Fetches `url` and returns the response body. Responses with an error status, like 404,
+/// are reported as errors.
+pub fn get(url: &str, timeout: Duration) -> Result<String> {
+    let response = request(url, &[], timeout)?;
+    if response.status >= 400 {
+        return Err(BlockError(
+            "http".to_owned(),
+            format!(
+                "request to '{}' failed with status {}",
+                url, response.status
+            ),
+        ));
+    }
+    Ok(response.body)
+}
+
+/// Like `get`, but waits for the response without blocking the runtime, for async blocks.
+#[cfg(feature = "async_runtime")]
+pub async fn get_async(url: &str, timeout: Duration) -> Result<String> {
+    let url = url.to_owned();
+    tokio::task::spawn_blocking(move || get(&url, timeout))
+        .await
+        .block_error("http", "the request was cancelled")?
+}
+
+/// Sends a GET request to `url` with extra `headers`, like `Accept: application/json`.
+pub fn request(url: &str, headers: &[&str], timeout: Duration) -> Result<Response> {
+    let mut handle = HANDLES.lock().unwrap().pop().unwrap_or_else(Easy::new);
+    let response = perform(&mut handle, url, headers, timeout);
+    HANDLES.lock().unwrap().push(handle);
+    response.block_error_context("http", &format!("request to '{}' failed", url))
+}
+
+fn perform(
+    handle: &mut Easy,
+    url: &str,
+    headers: &[&str],
+    timeout: Duration,
+) -> std::result::Result<Response, curl::Error> {
+    // Forgets the options of the previous request, but keeps its connections.
+    handle.reset();
+    handle.url(url)?;
+    handle.timeout(timeout)?;
+    handle.useragent(concat!("i3status-rs/", env!("CARGO_PKG_VERSION")))?;
+    let mut list = List::new();
+    for header in headers {
+        list.append(header)?;
+    }
+    handle.http_headers(list)?;
+
+    let mut body = Vec::new();
+    let mut header_lines = Vec::new();
+    {
+        let mut transfer = handle.transfer();
+        transfer.write_function(|data| {
+            body.extend_from_slice(data);
+            Ok(data.len())
+        })?;
+        transfer.header_function(|line| {
+            header_lines.push(String::from_utf8_lossy(line).trim_end().to_owned());
+            true
+        })?;
+        transfer.perform()?;
+    }
+
+    Ok(Response {
+        status: handle.response_code()?,
+        headers: header_lines,
+        body: String::from_utf8_lossy(&body).into_owned(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Response;
+
+    #[test]
+    fn test_header() {
+        let response = Response {
+            status: 200,
+            headers: vec![
+                "HTTP/1.1 200 OK".to_owned(),
+                "Content-Type: application/json".to_owned(),
+                "link: <https://api.github.com/notifications?page=2>; rel=\"next\"".to_owned(),
+            ],
+            body: String::new(),
+        };
+        assert_eq!(response.header("content-type"), Some("application/json"));
+        assert_eq!(
+            response.header("Link"),
+            Some("<https://api.github.com/notifications?page=2>; rel=\"next\"")
+        );
+        assert_eq!(response.header("Location"), None);
+    }
+}
@@ -50,6 +50,7 @@ lazy_static! {
         "phone_disconnected" => " PHONE ",
         "ping" => " PING ",
         "pomodoro" => " POMODORO ",
+        "screenshot" => " SHOT ",
         "tasks" => " TSK ",
         "thermometer" => " TEMP ",
         "time" => " ",
@@ -61,6 +62,8 @@ lazy_static! {
         "volume_full" => " VOL ",
         "volume_half" => " VOL ",
         "volume_muted" => " MIC MUTED ",
+        "volume_overamplified" => " VOL+ ",
+        "microphone_active" => " MIC REC ",
         "microphone_empty" => " MIC ",
         "microphone_full" => " MIC ",
         "microphone_half" => " MIC ",
@@ -123,6 +126,7 @@ lazy_static! {
         "phone_disconnected" => " \u{1f4f5} ",
         "ping" => " \u{21ba} ",
         "pomodoro" => " \u{1f345} ",
+        "screenshot" => " \u{f030} ",
         "tasks" => " \u{f0ae} ",
         "thermometer" => " \u{f2c8} ",
         "time" => " \u{f017} ",
@@ -135,6 +139,8 @@ lazy_static! {
         "volume_full" => " \u{f028} ",
         "volume_half" => " \u{f027} ",
         "volume_muted" => " \u{f026} \u{f00d} ",
+        "volume_overamplified" => " \u{f028} \u{f067} ",
+        "microphone_active" => " \u{f130} \u{f111} ",
         "microphone_empty" => " \u{f130} ",
         "microphone_full" => " \u{f130} ",
         "microphone_half" => " \u{f130} ",
@@ -196,6 +202,7 @@ lazy_static! {
         "phone_disconnected" => " \u{1f4f5} ",
         "ping" => " \u{f362} ",
         "pomodoro" => " \u{1f345} ",
+        "screenshot" => " \u{f030} ",
         "tasks" => " \u{f0ae} ",
         "thermometer" => " \u{f2c8} ",
         "time" => " \u{f017} ",
@@ -208,6 +215,8 @@ lazy_static! {
         "volume_full" => " \u{f028} ",
         "volume_half" => " \u{f027} ",
         "volume_muted" => " \u{f6a9} ",
+        "volume_overamplified" => " \u{f028} \u{f067} ",
+        "microphone_active" => " \u{f130} \u{f111} ",
         "microphone_full" => " \u{f130} ",
         "microphone_half" => " \u{f130} ",
         "microphone_empty" => " \u{f130} ",
@@ -255,6 +264,7 @@ lazy_static! {
         "phone" => " \u{e324} ",
         "phone_disconnected" => " \u{1f4f5} ",
         "pomodoro" => " \u{1f345} ",
+        "screenshot" => " \u{e3af} ",
         "tasks" => " \u{e8f9} ",
         "thermometer" => " \u{f2c8} ", // TODO
         "time" => " \u{e192} ",
@@ -266,6 +276,7 @@ lazy_static! {
         "volume_full" => " \u{e050} ",
         "volume_half" => " \u{e04d} ",
         "volume_muted" => " \u{e04e} \u{e04f} ",
+        "volume_overamplified" => " \u{e050} \u{e145} ",
         "xrandr" => " \u{e31e} "
     };
 }
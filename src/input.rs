@@ -15,6 +15,8 @@ pub enum MouseButton {
     Right,
     WheelUp,
     WheelDown,
+    WheelLeft,
+    WheelRight,
     Forward, // On my mouse, these map to forward and back
     Back,
     Unknown,
@@ -84,6 +86,8 @@ where
                 3 => MouseButton::Right,
                 4 => MouseButton::WheelUp,
                 5 => MouseButton::WheelDown,
+                6 => MouseButton::WheelLeft,
+                7 => MouseButton::WheelRight,
                 9 => MouseButton::Forward,
                 8 => MouseButton::Back,
                 _ => MouseButton::Unknown,
@@ -0,0 +1,104 @@
+//! A minimal logger printing to stderr, configured through `RUST_LOG`.
+//!
+//! Blocks log with their name as target, so `RUST_LOG=sound=debug` shows the debug
+//! messages of the sound block, and `RUST_LOG=debug,weather=off` everything but the
+//! weather block. Without `RUST_LOG`, warnings and errors are shown.
+
+use std::env;
+
+use log::{LevelFilter, Log, Metadata, Record};
+
+struct Logger {
+    /// The level of messages without a more specific directive
+    default: LevelFilter,
+    /// Levels of specific targets
+    targets: Vec<(String, LevelFilter)>,
+}
+
+impl Logger {
+    fn parse(spec: &str) -> Self {
+        let mut logger = Logger {
+            default: LevelFilter::Warn,
+            targets: Vec::new(),
+        };
+        for directive in spec.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.find('=') {
+                Some(pos) => {
+                    if let Ok(level) = directive[pos + 1..].parse() {
+                        logger.targets.push((directive[..pos].to_owned(), level));
+                    }
+                }
+                None => {
+                    if let Ok(level) = directive.parse() {
+                        logger.default = level;
+                    }
+                }
+            }
+        }
+        logger
+    }
+
+    fn level(&self, target: &str) -> LevelFilter {
+        self.targets
+            .iter()
+            .rev()
+            .find(|(name, _)| name == target)
+            .map_or(self.default, |&(_, level)| level)
+    }
+
+    fn max_level(&self) -> LevelFilter {
+        self.targets
+            .iter()
+            .map(|&(_, level)| level)
+            .fold(self.default, Ord::max)
+    }
+}
+
+impl Log for Logger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level(metadata.target())
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            eprintln!(
+                "[{}] {}: {}",
+                record.level(),
+                record.target(),
+                record.args()
+            );
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Installs the logger. Must be called once, before anything is logged.
+pub fn init() {
+    let logger = Logger::parse(&env::var("RUST_LOG").unwrap_or_default());
+    log::set_max_level(logger.max_level());
+    log::set_boxed_logger(Box::new(logger)).expect("the logger was already initialized");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Logger;
+    use log::LevelFilter;
+
+    #[test]
+    fn test_parse_default() {
+        let logger = Logger::parse("");
+        assert_eq!(logger.level("sound"), LevelFilter::Warn);
+        assert_eq!(logger.max_level(), LevelFilter::Warn);
+    }
+
+    #[test]
+    fn test_parse_targets() {
+        let logger = Logger::parse("info, sound=debug,weather=off,bogus=level");
+        assert_eq!(logger.level("sound"), LevelFilter::Debug);
+        assert_eq!(logger.level("weather"), LevelFilter::Off);
+        assert_eq!(logger.level("bogus"), LevelFilter::Info);
+        assert_eq!(logger.level("cpu"), LevelFilter::Info);
+        assert_eq!(logger.max_level(), LevelFilter::Debug);
+    }
+}
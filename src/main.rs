@@ -9,9 +9,14 @@ mod de;
 mod util;
 pub mod blocks;
 mod config;
+mod dbus;
 mod errors;
+mod http;
 mod icons;
 mod input;
+mod logging;
+#[cfg(feature = "async_runtime")]
+mod runtime;
 mod scheduler;
 mod signals;
 mod subprocess;
@@ -24,22 +29,28 @@ use cpuprofiler::PROFILER;
 
 use std::collections::HashMap;
 use std::ops::DerefMut;
+use std::path::{Path, PathBuf};
 use std::time::Duration;
 
 use clap::{crate_authors, crate_description, crate_version, App, Arg, ArgMatches};
 use crossbeam_channel::{select, Receiver, Sender};
 
-use crate::blocks::create_block;
 use crate::blocks::Block;
+use crate::blocks::{
+    alias_target, check_block, create_block, run_error_hook, take_alias_name, take_update_limits,
+    FailedBlock, ALIAS_BLOCK,
+};
 use crate::config::{load_config, Config};
 use crate::errors::*;
 use crate::input::{process_events, I3BarEvent};
-use crate::scheduler::{Task, UpdateScheduler};
+use crate::scheduler::{coalesce_requests, Task, UpdateScheduler};
 use crate::signals::process_signals;
 use crate::widget::{I3BarWidget, State};
 use crate::widgets::text::TextWidget;
 
 fn main() {
+    logging::init();
+
     let mut builder = App::new("i3status-rs")
         .version(crate_version!())
         .author(crate_authors!())
@@ -63,6 +74,12 @@ fn main() {
                 .long("never-pause")
                 .takes_value(false),
         )
+        .arg(
+            Arg::with_name("check-config")
+                .help("Check the configuration of all blocks and exit")
+                .long("check-config")
+                .takes_value(false),
+        )
         .arg(
             Arg::with_name("one-shot")
                 .help("Print blocks once and exit")
@@ -91,8 +108,27 @@ fn main() {
     let matches = builder.get_matches();
     let exit_on_error = matches.is_present("exit-on-error");
 
+    if matches.is_present("check-config") {
+        match check_config(&config_path(&matches)) {
+            Ok(()) => println!("Configuration is valid"),
+            Err(error) => {
+                eprintln!("{:?}", error);
+                ::std::process::exit(1);
+            }
+        }
+        return;
+    }
+
     // Run and match for potential error
     if let Err(error) = run(&matches) {
+        if let BlockError(ref block, ref message) = error {
+            let on_error = load_config(&config_path(&matches))
+                .ok()
+                .and_then(|config| config.behavior.on_error);
+            if let Some(command) = on_error {
+                run_error_hook(&command, block, message);
+            }
+        }
         if exit_on_error {
             eprintln!("{:?}", error);
             ::std::process::exit(1);
@@ -115,6 +151,60 @@ fn main() {
     }
 }
 
+fn config_path(matches: &ArgMatches) -> PathBuf {
+    match matches.value_of("config") {
+        Some(config_path) => PathBuf::from(config_path),
+        None => util::xdg_config_home().join("i3status-rust/config.toml"),
+    }
+}
+
+/// Deserializes the configuration of every block without starting them, and reports
+/// the first error along with the position and name of the block.
+fn check_config(config_path: &Path) -> Result<()> {
+    let config = load_config(config_path)?;
+    let mut alias_names = Vec::new();
+    let mut aliases = Vec::new();
+    for (index, &(ref block_name, ref block_config)) in config.blocks.iter().enumerate() {
+        let mut block_config = block_config.clone();
+        let checked = if block_name == ALIAS_BLOCK {
+            alias_target(block_config).map(|target| aliases.push((index, target)))
+        } else {
+            take_alias_name(&mut block_config)
+                .and_then(|alias_name| {
+                    alias_names.extend(alias_name);
+                    take_update_limits(&mut block_config)
+                })
+                .and_then(|_| check_block(block_name, block_config, config.clone()))
+        };
+        checked.map_err(|error| {
+            let block = format!("block #{} ({})", index + 1, block_name);
+            match error {
+                ConfigurationError(message, cause) => {
+                    ConfigurationError(format!("{}: {}", block, message), cause)
+                }
+                BlockError(_, message) => BlockError(block, message),
+                error => error,
+            }
+        })?;
+    }
+
+    for (index, target) in aliases {
+        if !alias_names.contains(&target) {
+            return Err(ConfigurationError(
+                format!(
+                    "block #{} ({}): no block has the alias_name '{}'",
+                    index + 1,
+                    ALIAS_BLOCK,
+                    target
+                ),
+                (String::new(), String::new()),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 fn run(matches: &ArgMatches) -> Result<()> {
     // Now we can start to run the i3bar protocol
     let initialise = if matches.is_present("never-pause") {
@@ -128,11 +218,7 @@ fn run(matches: &ArgMatches) -> Result<()> {
     print!("{{{}}}\n[", initialise);
 
     // Read & parse the config file
-    let config_path = match matches.value_of("config") {
-        Some(config_path) => std::path::PathBuf::from(config_path),
-        None => util::xdg_config_home().join("i3status-rust/config.toml"),
-    };
-    let config = load_config(&config_path)?;
+    let config = load_config(&config_path(matches))?;
 
     // Update request channel
     let (tx_update_requests, rx_update_requests): (Sender<Task>, Receiver<Task>) =
@@ -187,31 +273,75 @@ fn run(matches: &ArgMatches) -> Result<()> {
     }
 
     let mut blocks: Vec<Box<dyn Block>> = Vec::new();
+    // We save the order of the blocks here, because they will be passed to an unordered
+    // HashMap. Aliases are filled in once all blocks are created.
+    let mut order: Vec<Option<String>> = Vec::new();
+    let mut named_blocks: HashMap<String, Option<String>> = HashMap::new();
+    let mut aliases = Vec::new();
+    let mut update_limits = Vec::new();
 
     let mut alternator = false;
     // Initialize the blocks
     for &(ref block_name, ref block_config) in &config.blocks {
-        blocks.push(create_block(
+        if block_name == ALIAS_BLOCK {
+            aliases.push((order.len(), alias_target(block_config.clone())?));
+            order.push(None);
+            continue;
+        }
+
+        let mut block_config = block_config.clone();
+        let alias_name = take_alias_name(&mut block_config)?;
+        let limits = take_update_limits(&mut block_config)?;
+        let config_for_block = if alternator {
+            config_alternating_tint.clone()
+        } else {
+            config.clone()
+        };
+        let block = match create_block(
             block_name,
-            block_config.clone(),
-            if alternator {
-                config_alternating_tint.clone()
-            } else {
-                config.clone()
-            },
+            block_config,
+            config_for_block.clone(),
             tx_update_requests.clone(),
-        )?);
-        alternator = !alternator;
+        ) {
+            Ok(block) => block,
+            Err(error) if !matches.is_present("exit-on-error") => {
+                // The other blocks keep working, with this one showing its error.
+                log::error!(target: block_name, "failed to create the block: {}", error);
+                Some(
+                    Box::new(FailedBlock::new(block_name, error, config_for_block))
+                        as Box<dyn Block>,
+                )
+            }
+            Err(error) => return Err(error),
+        };
+        let id = block.as_ref().map(|block| String::from(block.id()));
+        if let Some(alias_name) = alias_name {
+            named_blocks.insert(alias_name, id.clone());
+        }
+        // Blocks whose `if_command` failed or which are unavailable on this system are left
+        // out entirely, as are their aliases.
+        if let Some(block) = block {
+            update_limits.push((String::from(block.id()), limits));
+            order.push(id);
+            blocks.push(block);
+            alternator = !alternator;
+        }
     }
 
-    // We save the order of the blocks here,
-    // because they will be passed to an unordered HashMap
-    let order = blocks
-        .iter()
-        .map(|x| String::from(x.id()))
-        .collect::<Vec<_>>();
+    // An alias shows the widgets of its target again, so both copies share one block.
+    for (position, target) in aliases {
+        order[position] = named_blocks
+            .get(&target)
+            .ok_or_else(|| format!("no block has the alias_name '{}'", target))
+            .configuration_error("failed to resolve alias")?
+            .clone();
+    }
+    let order = order.into_iter().flatten().collect::<Vec<_>>();
 
     let mut scheduler = UpdateScheduler::new(&blocks);
+    for (id, limits) in update_limits {
+        scheduler.set_limits(id, limits);
+    }
 
     let mut block_map: HashMap<String, &mut dyn Block> = HashMap::new();
 
@@ -233,6 +363,7 @@ fn run(matches: &ArgMatches) -> Result<()> {
     let mut ttnu = crossbeam_channel::after(Duration::from_millis(0));
 
     let one_shot = matches.is_present("one-shot");
+    let mut shutdown = false;
     loop {
         // We use the message passing concept of channel selection
         // to avoid busy wait
@@ -246,11 +377,11 @@ fn run(matches: &ArgMatches) -> Result<()> {
             },
             // Receive async update requests
             recv(rx_update_requests) -> request => if let Ok(req) = request {
-                // Process immediately and forget
-                block_map
-                    .get_mut(&req.id)
-                    .internal_error("scheduler", "could not get required block")?
-                    .update()?;
+                // Process immediately and forget, together with the requests that piled up,
+                // unless requested for later
+                for task in coalesce_requests(req, &rx_update_requests) {
+                    scheduler.update_on_request(task, &mut block_map)?;
+                }
                 util::print_blocks(&order, &block_map, &config)?;
             },
             // Receive update timer events
@@ -274,6 +405,11 @@ fn run(matches: &ArgMatches) -> Result<()> {
                         //TODO not implemented
                         //unimplemented!("SIGUSR2 is meant to be used to reload the config toml, but this feature is yet not implemented");
                     },
+                    signal_hook::SIGTERM => {
+                        //TERM signal that stops the bar, dropping the blocks so they
+                        //can stop their threads and close their connections
+                        shutdown = true;
+                    },
                     _ => {
                         //Real time signal that updates only the blocks listening
                         //for that signal
@@ -289,10 +425,18 @@ fn run(matches: &ArgMatches) -> Result<()> {
         if let Some(time) = scheduler.time_to_next_update() {
             ttnu = crossbeam_channel::after(time)
         }
-        if one_shot {
-            break Ok(());
+        if one_shot || shutdown {
+            break;
         }
     }
+
+    if shutdown {
+        // The blocks go first, so that only the shared connections are left to close.
+        drop(block_map);
+        drop(blocks);
+        blocks::sound::shutdown();
+    }
+    Ok(())
 }
 
 #[cfg(feature = "profiling")]
@@ -327,9 +471,11 @@ fn profile_config(name: &str, runs: &str, config: &Config, update: Sender<Task>)
         .configuration_error("failed to parse --profile-runs as an integer")?;
     for &(ref block_name, ref block_config) in &config.blocks {
         if block_name == name {
-            let mut block =
-                create_block(&block_name, block_config.clone(), config.clone(), update)?;
-            profile(profile_runs, &block_name, block.deref_mut());
+            if let Some(mut block) =
+                create_block(&block_name, block_config.clone(), config.clone(), update)?
+            {
+                profile(profile_runs, &block_name, block.deref_mut());
+            }
             break;
         }
     }
@@ -0,0 +1,156 @@
+//! Asynchronous blocks, for blocks whose updates mostly wait on I/O, like HTTP requests.
+//!
+//! Their updates run on a Tokio runtime shared by all of them, so that a slow server
+//! doesn't hold up the bar. Each block is wrapped in an `AsyncBlockRunner`, which is a
+//! regular `Block` to the rest of the bar: it starts an update on the runtime when the
+//! block is due, and applies the result once the runtime sends it back.
+
+use std::time::Instant;
+
+use crossbeam_channel::{Receiver, Sender};
+use futures::future::BoxFuture;
+use lazy_static::lazy_static;
+use serde_json::value::Value;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::blocks::{Block, Update};
+use crate::errors::*;
+use crate::input::I3BarEvent;
+use crate::scheduler::Task;
+use crate::widget::I3BarWidget;
+
+lazy_static! {
+    static ref RUNTIME: Runtime = Builder::new_multi_thread()
+        .worker_threads(2)
+        .thread_name("async_blocks")
+        .enable_all()
+        .build()
+        .expect("failed to start the async runtime");
+}
+
+/// A block with an asynchronous `update`. It is moved to the runtime while it is being
+/// updated, so it must be `Send`.
+pub trait AsyncBlock: Send + 'static {
+    /// A unique id for the block.
+    fn id(&self) -> &str;
+
+    /// The current "view" of the block, comprised of widgets.
+    fn view(&self) -> Vec<&dyn I3BarWidget>;
+
+    /// Updates the internal state of the block, like `Block::update`.
+    fn update(&mut self) -> BoxFuture<'_, Result<Option<Update>>>;
+
+    /// Sends click events to the block, like `Block::click`. Clicks while the block is
+    /// being updated are dropped.
+    fn click(&mut self, _event: &I3BarEvent) -> Result<()> {
+        Ok(())
+    }
+}
+
+/// A widget as last rendered by an async block, shown while the block is being updated.
+struct RenderedWidget {
+    output: String,
+    rendered: Value,
+}
+
+impl I3BarWidget for RenderedWidget {
+    fn to_string(&self) -> String {
+        self.output.clone()
+    }
+
+    fn get_rendered(&self) -> &Value {
+        &self.rendered
+    }
+}
+
+type UpdateResult<B> = (Box<B>, Result<Option<Update>>);
+
+/// Runs the updates of an `AsyncBlock` on the shared runtime.
+pub struct AsyncBlockRunner<B: AsyncBlock> {
+    id: String,
+    /// The block, unless it is being updated
+    block: Option<Box<B>>,
+    widgets: Vec<RenderedWidget>,
+    tx_result: Sender<UpdateResult<B>>,
+    rx_result: Receiver<UpdateResult<B>>,
+    tx_update_request: Sender<Task>,
+}
+
+impl<B: AsyncBlock> AsyncBlockRunner<B> {
+    pub fn new(block: B, tx_update_request: Sender<Task>) -> Self {
+        let (tx_result, rx_result) = crossbeam_channel::unbounded();
+        let mut runner = AsyncBlockRunner {
+            id: block.id().to_owned(),
+            block: Some(Box::new(block)),
+            widgets: Vec::new(),
+            tx_result,
+            rx_result,
+            tx_update_request,
+        };
+        runner.render();
+        runner
+    }
+
+    /// Keeps the widgets of the block, to show them while it is away.
+    fn render(&mut self) {
+        if let Some(ref block) = self.block {
+            self.widgets = block
+                .view()
+                .into_iter()
+                .map(|widget| RenderedWidget {
+                    output: widget.to_string(),
+                    rendered: widget.get_rendered().clone(),
+                })
+                .collect();
+        }
+    }
+}
+
+impl<B: AsyncBlock> Block for AsyncBlockRunner<B> {
+    fn id(&self) -> &str {
+        &self.id
+    }
+
+    fn view(&self) -> Vec<&dyn I3BarWidget> {
+        self.widgets
+            .iter()
+            .map(|widget| widget as &dyn I3BarWidget)
+            .collect()
+    }
+
+    /// Starts an update on the runtime, which requests another call once it is done. That
+    /// call returns the result of the update, so that the block is scheduled as usual.
+    fn update(&mut self) -> Result<Option<Update>> {
+        if let Ok((block, result)) = self.rx_result.try_recv() {
+            self.block = Some(block);
+            self.render();
+            return result;
+        }
+
+        // Otherwise an update is already running.
+        if let Some(mut block) = self.block.take() {
+            let id = self.id.clone();
+            let tx_result = self.tx_result.clone();
+            let tx_update_request = self.tx_update_request.clone();
+            RUNTIME.spawn(async move {
+                let result = block.update().await;
+                // Both only fail once the bar is shutting down.
+                if tx_result.send((block, result)).is_ok() {
+                    let _ = tx_update_request.send(Task {
+                        id,
+                        update_time: Instant::now(),
+                    });
+                }
+            });
+        }
+        Ok(None)
+    }
+
+    fn click(&mut self, event: &I3BarEvent) -> Result<()> {
+        if let Some(ref mut block) = self.block {
+            block.click(event)?;
+            self.render();
+        }
+        Ok(())
+    }
+}
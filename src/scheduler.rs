@@ -2,12 +2,57 @@ use crate::blocks::Update;
 use std::cmp;
 use std::collections::{BinaryHeap, HashMap};
 use std::fmt;
+use std::sync::Mutex;
 use std::thread;
 use std::time::{Duration, Instant};
 
+use crossbeam_channel::Receiver;
+use lazy_static::lazy_static;
+
 use crate::blocks::Block;
 use crate::errors::*;
 
+/// How long block updates take, shown by the `debug` block
+#[derive(Debug, Clone, Default)]
+pub struct UpdateStats {
+    pub updates: u64,
+    pub total: Duration,
+    pub max: Duration,
+    /// Number of tasks waiting in the schedule
+    pub queued: usize,
+}
+
+impl UpdateStats {
+    pub fn average(&self) -> Duration {
+        if self.updates == 0 {
+            Duration::from_secs(0)
+        } else {
+            Duration::from_secs_f64(self.total.as_secs_f64() / self.updates as f64)
+        }
+    }
+}
+
+lazy_static! {
+    static ref UPDATE_STATS: Mutex<UpdateStats> = Mutex::new(UpdateStats::default());
+}
+
+pub fn update_stats() -> UpdateStats {
+    UPDATE_STATS.lock().unwrap().clone()
+}
+
+/// Updates `block`, recording how long it took.
+pub fn timed_update(block: &mut dyn Block) -> Result<Option<Update>> {
+    let start = Instant::now();
+    let result = block.update();
+    let elapsed = start.elapsed();
+
+    let mut stats = UPDATE_STATS.lock().unwrap();
+    stats.updates += 1;
+    stats.total += elapsed;
+    stats.max = cmp::max(stats.max, elapsed);
+    result
+}
+
 #[derive(Debug, Clone)]
 pub struct Task {
     pub id: String,
@@ -40,8 +85,41 @@ impl cmp::Ord for Task {
     }
 }
 
+/// Returns `first` and the requests already waiting in `pending`, with the requests for an
+/// immediate update of each block only once. A burst of requests from one block, e.g. for
+/// sound events, then costs a single update. Requests for a later update are all kept.
+pub fn coalesce_requests(first: Task, pending: &Receiver<Task>) -> Vec<Task> {
+    let now = Instant::now();
+    let mut tasks: Vec<Task> = Vec::new();
+    for task in std::iter::once(first).chain(pending.try_iter()) {
+        let immediate = task.update_time <= now;
+        if !immediate
+            || !tasks
+                .iter()
+                .any(|other| other.update_time <= now && other.id == task.id)
+        {
+            tasks.push(task);
+        }
+    }
+    tasks
+}
+
+/// Limits to how often a block is updated on its own requests, from its `max_update_rate`
+/// and `throttle_ms` keys
+#[derive(Debug, Default, Clone, Copy)]
+pub struct UpdateLimits {
+    /// Shortest time between two updates
+    pub min_interval: Option<Duration>,
+    /// How long requests are collected before the block is updated once for all of them
+    pub throttle: Option<Duration>,
+}
+
 pub struct UpdateScheduler {
     schedule: BinaryHeap<Task>,
+    limits: HashMap<String, UpdateLimits>,
+    last_updates: HashMap<String, Instant>,
+    /// When the blocks with a request that was put off because of their limits are updated
+    deferred: HashMap<String, Instant>,
 }
 
 impl UpdateScheduler {
@@ -56,7 +134,74 @@ impl UpdateScheduler {
             });
         }
 
-        UpdateScheduler { schedule }
+        UpdateScheduler {
+            schedule,
+            limits: HashMap::new(),
+            last_updates: HashMap::new(),
+            deferred: HashMap::new(),
+        }
+    }
+
+    pub fn set_limits(&mut self, id: String, limits: UpdateLimits) {
+        self.limits.insert(id, limits);
+    }
+
+    /// Updates the block of `task`, as requested by the block itself. Requests for a later
+    /// time are scheduled. If the block was updated less than its minimum interval ago, or
+    /// its requests are throttled, the update is scheduled for later too, together with any
+    /// further requests until then.
+    pub fn update_on_request(
+        &mut self,
+        task: Task,
+        block_map: &mut HashMap<String, &mut dyn Block>,
+    ) -> Result<()> {
+        let now = Instant::now();
+        if task.update_time > now {
+            self.schedule.push(task);
+            return Ok(());
+        }
+        let id = task.id;
+        if self.deferred.contains_key(&id) {
+            return Ok(());
+        }
+        let limits = self.limits.get(&id).copied().unwrap_or_default();
+        let mut update_time = now;
+        if let (Some(min_interval), Some(&last)) = (limits.min_interval, self.last_updates.get(&id))
+        {
+            update_time = cmp::max(update_time, last + min_interval);
+        }
+        if let Some(throttle) = limits.throttle {
+            update_time = cmp::max(update_time, now + throttle);
+        }
+        if update_time > now {
+            self.deferred.insert(id.clone(), update_time);
+            self.schedule.push(Task { id, update_time });
+            return Ok(());
+        }
+
+        let update = timed_update(
+            *block_map
+                .get_mut(&id)
+                .internal_error("scheduler", "could not get required block")?,
+        )?;
+        let now = Instant::now();
+        self.last_updates.insert(id.clone(), now);
+        self.reschedule(id, update, now);
+        Ok(())
+    }
+
+    /// Schedules the next update of the block `id` as asked for by its last update, which
+    /// ended at `now`.
+    fn reschedule(&mut self, id: String, update: Option<Update>, now: Instant) {
+        match update {
+            // After a deferred request, the block's own schedule may still be queued.
+            Some(Update::Every(_)) if self.schedule.iter().any(|next| next.id == id) => {}
+            Some(Update::Every(d)) => self.schedule.push(Task {
+                id,
+                update_time: now + d,
+            }),
+            Some(Update::Once) | None => {} // do not schedule this task again
+        }
     }
 
     pub fn time_to_next_update(&self) -> Option<Duration> {
@@ -92,11 +237,14 @@ impl UpdateScheduler {
                     .internal_error("scheduler", "schedule is empty")?
                     .update_time
         {
-            tasks_next.push(
-                self.schedule
-                    .pop()
-                    .internal_error("scheduler", "schedule is empty")?,
-            )
+            let task = self
+                .schedule
+                .pop()
+                .internal_error("scheduler", "schedule is empty")?;
+            // A block is only updated once, even if it was scheduled twice.
+            if tasks_next.iter().all(|next| next.id != task.id) {
+                tasks_next.push(task);
+            }
         }
 
         let now = Instant::now();
@@ -107,21 +255,164 @@ impl UpdateScheduler {
         let now = Instant::now();
 
         for task in tasks_next {
-            if let Some(dur) = block_map
-                .get_mut(&task.id)
-                .internal_error("scheduler", "could not get required block")?
-                .update()?
-            {
-                match dur {
-                    Update::Every(d) => self.schedule.push(Task {
-                        id: task.id,
-                        update_time: now + d,
-                    }),
-                    Update::Once => {} // do not schedule this task again
-                }
+            let update = timed_update(
+                *block_map
+                    .get_mut(&task.id)
+                    .internal_error("scheduler", "could not get required block")?,
+            )?;
+            self.last_updates.insert(task.id.clone(), now);
+            if self.deferred.get(&task.id) == Some(&task.update_time) {
+                self.deferred.remove(&task.id);
             }
+            self.reschedule(task.id, update, now);
         }
+        UPDATE_STATS.lock().unwrap().queued = self.schedule.len();
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::widget::I3BarWidget;
+
+    struct CountingBlock {
+        id: String,
+        updates: u32,
+    }
+
+    impl Block for CountingBlock {
+        fn id(&self) -> &str {
+            &self.id
+        }
+
+        fn view(&self) -> Vec<&dyn I3BarWidget> {
+            vec![]
+        }
+
+        fn update(&mut self) -> Result<Option<Update>> {
+            self.updates += 1;
+            Ok(None)
+        }
+    }
+
+    fn task(id: &str, update_time: Instant) -> Task {
+        Task {
+            id: id.to_owned(),
+            update_time,
+        }
+    }
+
+    /// Sends an immediate request for the block `block` to `scheduler`.
+    fn request(scheduler: &mut UpdateScheduler, block: &mut CountingBlock) {
+        let mut block_map: HashMap<String, &mut dyn Block> = HashMap::new();
+        let id = block.id.clone();
+        block_map.insert(id.clone(), block);
+        scheduler
+            .update_on_request(task(&id, Instant::now()), &mut block_map)
+            .unwrap();
+    }
+
+    #[test]
+    fn test_coalesce_requests() {
+        let now = Instant::now();
+        let later = now + Duration::from_secs(1);
+        let (tx, rx) = crossbeam_channel::unbounded();
+        tx.send(task("a", now)).unwrap();
+        tx.send(task("b", now)).unwrap();
+        tx.send(task("a", later)).unwrap();
+        tx.send(task("a", later)).unwrap();
+
+        let tasks = coalesce_requests(task("a", now), &rx);
+        let ids: Vec<(&str, bool)> = tasks
+            .iter()
+            .map(|task| (task.id.as_str(), task.update_time > now))
+            .collect();
+        assert_eq!(
+            ids,
+            vec![("a", false), ("b", false), ("a", true), ("a", true)]
+        );
+        assert!(rx.is_empty());
+    }
+
+    #[test]
+    fn test_update_without_limits() {
+        let mut scheduler = UpdateScheduler::new(&[]);
+        let mut block = CountingBlock {
+            id: "a".to_owned(),
+            updates: 0,
+        };
+        request(&mut scheduler, &mut block);
+        request(&mut scheduler, &mut block);
+        assert_eq!(block.updates, 2);
+        assert!(scheduler.schedule.is_empty());
+    }
+
+    #[test]
+    fn test_min_interval() {
+        let mut scheduler = UpdateScheduler::new(&[]);
+        let min_interval = Duration::from_secs(10);
+        scheduler.set_limits(
+            "a".to_owned(),
+            UpdateLimits {
+                min_interval: Some(min_interval),
+                throttle: None,
+            },
+        );
+        let mut block = CountingBlock {
+            id: "a".to_owned(),
+            updates: 0,
+        };
+
+        // The first update isn't limited, the next one is put off until the interval has
+        // passed.
+        request(&mut scheduler, &mut block);
+        assert_eq!(block.updates, 1);
+        let last = scheduler.last_updates["a"];
+        request(&mut scheduler, &mut block);
+        assert_eq!(block.updates, 1);
+        assert_eq!(scheduler.schedule.len(), 1);
+        assert_eq!(
+            scheduler.schedule.peek().unwrap().update_time,
+            last + min_interval
+        );
+        assert_eq!(scheduler.deferred["a"], last + min_interval);
+    }
+
+    #[test]
+    fn test_throttle() {
+        let mut scheduler = UpdateScheduler::new(&[]);
+        let throttle = Duration::from_millis(20);
+        scheduler.set_limits(
+            "a".to_owned(),
+            UpdateLimits {
+                min_interval: None,
+                throttle: Some(throttle),
+            },
+        );
+        let mut block = CountingBlock {
+            id: "a".to_owned(),
+            updates: 0,
+        };
+
+        let before = Instant::now();
+        request(&mut scheduler, &mut block);
+        assert_eq!(block.updates, 0);
+        assert!(scheduler.schedule.peek().unwrap().update_time >= before + throttle);
+
+        // Requests while one is deferred are dropped, the block is updated once for all.
+        request(&mut scheduler, &mut block);
+        request(&mut scheduler, &mut block);
+        assert_eq!(block.updates, 0);
+        assert_eq!(scheduler.schedule.len(), 1);
+
+        let mut block_map: HashMap<String, &mut dyn Block> = HashMap::new();
+        block_map.insert("a".to_owned(), &mut block);
+        scheduler.do_scheduled_updates(&mut block_map).unwrap();
+        assert!(Instant::now() >= before + throttle);
+        assert!(scheduler.schedule.is_empty());
+        assert!(scheduler.deferred.is_empty());
+        assert_eq!(block.updates, 1);
+    }
+}
@@ -17,9 +17,13 @@ pub fn process_signals(sender: Sender<i32>) {
                 let mut signals = (sigmin..sigmax).collect::<Vec<_>>();
                 signals.push(signal_hook::SIGUSR1);
                 signals.push(signal_hook::SIGUSR2);
+                signals.push(signal_hook::SIGTERM);
                 let signals = signal_hook::iterator::Signals::new(&signals).unwrap();
                 for sig in signals.forever() {
-                    sender.send(sig).unwrap();
+                    if sender.send(sig).is_err() && sig == signal_hook::SIGTERM {
+                        // Nobody is left to shut down, e.g. while an error is displayed.
+                        std::process::exit(0);
+                    }
                 }
             }
         })
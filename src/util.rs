@@ -1,3 +1,5 @@
+pub mod i3ipc;
+
 use num_traits::{clamp, ToPrimitive};
 use std::collections::HashMap;
 use std::fmt::Display;
@@ -10,14 +12,19 @@ use std::process::Command;
 
 use regex::Regex;
 use serde::de::DeserializeOwned;
+use serde_derive::Deserialize;
 use serde_json::value::Value;
 
 use crate::blocks::Block;
-use crate::config::Config;
+use crate::config::{Config, LogicalDirection, Scrolling};
 use crate::errors::*;
+use crate::input::I3BarEvent;
 
 pub const USR_SHARE_PATH: &str = "/usr/share/i3status-rust";
 
+/// Frames of the spinner shown by blocks while they wait for a command
+pub const SPINNER: &[char] = &['⠋', '⠙', '⠹', '⠸', '⠼', '⠴', '⠦', '⠧', '⠇', '⠏'];
+
 pub fn escape_pango_text(text: String) -> String {
     text.chars()
         .map(|x| match x {
@@ -149,6 +156,43 @@ pub fn get_file(name: &str) -> Result<String> {
     Ok(file_contents)
 }
 
+/// Translates a vertical scroll event into a signed step according to `scrolling`, and
+/// passes it on to `adjust`: `step` when scrolling up, `-step` when scrolling down. Other
+/// events are ignored.
+pub fn handle_scroll<F>(
+    scrolling: Scrolling,
+    event: &I3BarEvent,
+    step: i64,
+    adjust: F,
+) -> Result<()>
+where
+    F: FnOnce(i64) -> Result<()>,
+{
+    match scrolling.to_logical_direction(event.button) {
+        Some(LogicalDirection::Up) => adjust(step),
+        Some(LogicalDirection::Down) => adjust(-step),
+        _ => Ok(()),
+    }
+}
+
+/// Like `handle_scroll` for horizontal scroll events: `step` when scrolling right,
+/// `-step` when scrolling left.
+pub fn handle_horizontal_scroll<F>(
+    scrolling: Scrolling,
+    event: &I3BarEvent,
+    step: i64,
+    adjust: F,
+) -> Result<()>
+where
+    F: FnOnce(i64) -> Result<()>,
+{
+    match scrolling.to_logical_direction(event.button) {
+        Some(LogicalDirection::Right) => adjust(step),
+        Some(LogicalDirection::Left) => adjust(-step),
+        _ => Ok(()),
+    }
+}
+
 pub fn has_command(block_name: &str, command: &str) -> Result<bool> {
     let exit_status = Command::new("sh")
         .args(&[
@@ -305,32 +349,92 @@ pub fn color_to_rgba(color: (u8, u8, u8, u8)) -> String {
     )
 }
 
+/// The characters used to draw a percentage bar.
+#[derive(Deserialize, Copy, Clone, Debug, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum BarStyle {
+    /// Each cell is filled from the bottom, in eighths
+    Vertical,
+    /// Cells are filled from the left, in eighths
+    Horizontal,
+    /// `#` for filled and `-` for empty cells
+    Ascii,
+}
+
+impl Default for BarStyle {
+    fn default() -> Self {
+        BarStyle::Vertical
+    }
+}
+
 pub fn format_percent_bar(percent: f32) -> String {
+    format_percent_bar_styled(percent, 10, BarStyle::Vertical)
+}
+
+/// Renders `percent` as a bar of `width` cells.
+pub fn format_percent_bar_styled(percent: f32, width: usize, style: BarStyle) -> String {
     let percent = percent.min(100.0);
     let percent = percent.max(0.0);
+    let width = width.max(1);
+    let cell = 100.0 / width as f32;
+
+    match style {
+        BarStyle::Vertical => (0..width)
+            .map(|index| {
+                let fraction = (percent - index as f32 * cell) / cell;
+                // An empty cell is still drawn as a 1/8 block, so the whole bar is always visible.
+                let eighths = clamp((fraction * 8.0).floor(), 0.0, 7.0) as u32;
+                std::char::from_u32(0x2581 + eighths).unwrap_or('\u{2588}')
+            })
+            .collect(),
+        BarStyle::Horizontal => {
+            // 1/8 to 7/8 left blocks
+            let partial = [
+                '\u{258F}', '\u{258E}', '\u{258D}', '\u{258C}', '\u{258B}', '\u{258A}', '\u{2589}',
+            ];
+            let eighths = (percent / cell * 8.0).round() as usize;
+            (0..width)
+                .map(|index| match eighths.saturating_sub(index * 8) {
+                    0 => '\u{2591}', // Light shade for empty cells
+                    n if n >= 8 => '\u{2588}',
+                    n => partial[n - 1],
+                })
+                .collect()
+        }
+        BarStyle::Ascii => {
+            let filled = (percent / cell).round() as usize;
+            (0..width)
+                .map(|index| if index < filled { '#' } else { '-' })
+                .collect()
+        }
+    }
+}
 
-    (0..10)
-        .map(|index| {
-            let bucket_min = (index * 10) as f32;
-            let fraction = percent - bucket_min;
-            //println!("Fraction: {}", fraction);
-            if fraction < 1.25 {
-                '\u{2581}' // 1/8 block for empty so the whole bar is always visible
-            } else if fraction < 2.5 {
-                '\u{2582}' // 2/8 block
-            } else if fraction < 3.75 {
-                '\u{2583}' // 3/8 block
-            } else if fraction < 5.0 {
-                '\u{2584}' // 4/8 block
-            } else if fraction < 6.25 {
-                '\u{2585}' // 5/8 block
-            } else if fraction < 7.5 {
-                '\u{2586}' // 6/8 block
-            } else if fraction < 8.75 {
-                '\u{2587}' // 7/8 block
-            } else {
-                '\u{2588}' // Full block
+/// Like `format_percent_bar_styled`, but colors the filled cells with pango spans, going
+/// from green for the first cell to red for the last one.
+pub fn format_percent_bar_colored(percent: f32, width: usize, style: BarStyle) -> String {
+    let bar = format_percent_bar_styled(percent, width, style);
+    let width = width.max(1);
+    let cell = 100.0 / width as f32;
+
+    bar.chars()
+        .enumerate()
+        .map(|(index, c)| {
+            if percent <= index as f32 * cell {
+                return c.to_string();
             }
+            // Green to yellow to red
+            let position = if width == 1 {
+                percent.min(100.0) / 100.0
+            } else {
+                index as f32 / (width - 1) as f32
+            };
+            let red = (position * 2.0 * 255.0).min(255.0) as u8;
+            let green = ((1.0 - position) * 2.0 * 255.0).min(255.0) as u8;
+            format!(
+                "<span foreground=\"#{:02X}{:02X}00\">{}</span>",
+                red, green, c
+            )
         })
         .collect()
 }
@@ -484,7 +588,35 @@ macro_rules! if_debug {
 
 #[cfg(test)]
 mod tests {
-    use crate::util::{color_from_rgba, has_command};
+    use crate::util::{
+        color_from_rgba, format_percent_bar, format_percent_bar_colored, format_percent_bar_styled,
+        has_command, BarStyle, FormatTemplate,
+    };
+
+    #[test]
+    fn test_format_percent_bar() {
+        assert_eq!(format_percent_bar(0.0), "▁▁▁▁▁▁▁▁▁▁");
+        assert_eq!(format_percent_bar(55.0), "█████▅▁▁▁▁");
+        assert_eq!(format_percent_bar(150.0), "██████████");
+        assert_eq!(
+            format_percent_bar_styled(50.0, 4, BarStyle::Vertical),
+            "██▁▁"
+        );
+        assert_eq!(
+            format_percent_bar_styled(55.0, 4, BarStyle::Horizontal),
+            "██▎░"
+        );
+        assert_eq!(format_percent_bar_styled(30.0, 5, BarStyle::Ascii), "##---");
+    }
+
+    #[test]
+    fn test_format_percent_bar_colored() {
+        assert_eq!(
+            format_percent_bar_colored(60.0, 3, BarStyle::Ascii),
+            "<span foreground=\"#00FF00\">#</span><span foreground=\"#FFFF00\">#</span>-"
+        );
+        assert_eq!(format_percent_bar_colored(0.0, 3, BarStyle::Ascii), "---");
+    }
 
     #[test]
     // we assume sh is always available
@@ -527,4 +659,47 @@ mod tests {
         let rgba = color_from_rgba(invalid);
         assert!(rgba.is_err());
     }
+
+    #[test]
+    fn test_format_template_render() {
+        let values = map!("{volume}" => "50", "{output_name}" => "speakers");
+        let render = |format: &str| {
+            FormatTemplate::from_string(format)
+                .unwrap()
+                .render_static_str(&values)
+                .unwrap()
+        };
+        assert_eq!(render("{volume}% {output_name}"), "50% speakers");
+        assert_eq!(render("{volume}{volume}"), "5050");
+        assert_eq!(render("🔈 {volume}%"), "🔈 50%");
+        assert_eq!(render("no placeholders"), "no placeholders");
+        assert_eq!(render(""), "");
+
+        let owned_values = map_to_owned!("{volume}" => 50);
+        let template = FormatTemplate::from_string("{volume}%").unwrap();
+        assert_eq!(template.render(&owned_values), "50%");
+    }
+
+    #[test]
+    fn test_format_template_braces() {
+        let values = map!("{volume}" => "50");
+        let render = |format: &str| {
+            FormatTemplate::from_string(format)
+                .unwrap()
+                .render_static_str(&values)
+                .unwrap()
+        };
+        // Braces that don't enclose a name are kept.
+        assert_eq!(render("{} { volume } {vol ume}"), "{} { volume } {vol ume}");
+        // There is no escaping, a placeholder within double braces is still replaced.
+        assert_eq!(render("{{volume}}"), "{50}");
+        assert_eq!(render("{volume"), "{volume");
+    }
+
+    #[test]
+    fn test_format_template_unknown_placeholder() {
+        let values = map!("{volume}" => "50");
+        let template = FormatTemplate::from_string("{volume} {unknown}").unwrap();
+        assert!(template.render_static_str(&values).is_err());
+    }
 }
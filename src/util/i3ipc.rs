@@ -0,0 +1,64 @@
+//! Helpers for the blocks talking to i3 or sway over IPC.
+//!
+//! The protocol itself, including finding the socket through `$SWAYSOCK` or `$I3SOCK`,
+//! is handled by the `swayipc` crate; this module only adds the parts the blocks have in
+//! common: connecting with a proper error, and watching events on a separate thread.
+
+use std::thread;
+use std::time::Instant;
+
+use crossbeam_channel::Sender;
+use swayipc::reply::Event;
+use swayipc::{Connection, EventType};
+
+use crate::errors::*;
+use crate::scheduler::Task;
+
+/// Connects to the IPC socket of i3 or sway.
+pub fn connect(block: &str) -> Result<Connection> {
+    Connection::new().block_error(block, "failed to acquire connect to IPC")
+}
+
+/// Subscribes to `events` and calls `on_event` for each of them on a separate thread.
+/// Whenever `on_event` returns `true`, an update of the block `id` is requested.
+///
+/// Subscribing happens before this returns, so that errors are reported right away.
+pub fn subscribe<F>(
+    block: &'static str,
+    events: &[EventType],
+    id: String,
+    update_request: Sender<Task>,
+    mut on_event: F,
+) -> Result<()>
+where
+    F: FnMut(Event) -> bool + Send + 'static,
+{
+    let events = connect(block)?
+        .subscribe(events)
+        .block_error(block, "could not subscribe to IPC events")?;
+
+    thread::Builder::new()
+        .name(block.into())
+        .spawn(move || {
+            for event in events {
+                let event = match event {
+                    Ok(event) => event,
+                    Err(e) => {
+                        log::warn!(target: block, "could not read IPC event: {:?}", e);
+                        break;
+                    }
+                };
+                if on_event(event) {
+                    update_request
+                        .send(Task {
+                            id: id.clone(),
+                            update_time: Instant::now(),
+                        })
+                        .unwrap();
+                }
+            }
+        })
+        .unwrap();
+
+    Ok(())
+}
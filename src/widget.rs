@@ -17,6 +17,31 @@ pub enum Spacing {
     Hidden,
 }
 
+/// Alignment of the widget contents within its `min_width`
+#[derive(Debug, Copy, Clone, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+impl Align {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Align::Left => "left",
+            Align::Center => "center",
+            Align::Right => "right",
+        }
+    }
+}
+
+impl Default for Align {
+    fn default() -> Self {
+        Align::Right
+    }
+}
+
 #[derive(Debug, Copy, Clone, Deserialize)]
 pub enum State {
     Idle,
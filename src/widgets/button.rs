@@ -2,6 +2,7 @@ use serde_json::value::Value;
 
 use super::super::widget::I3BarWidget;
 use crate::config::Config;
+use crate::widget::Align;
 use crate::widget::Spacing;
 use crate::widget::State;
 
@@ -9,6 +10,8 @@ use crate::widget::State;
 pub struct ButtonWidget {
     content: Option<String>,
     icon: Option<String>,
+    description: Option<String>,
+    min_width: Option<(String, Align)>,
     state: State,
     spacing: Spacing,
     id: String,
@@ -22,6 +25,8 @@ impl ButtonWidget {
         ButtonWidget {
             content: None,
             icon: None,
+            description: None,
+            min_width: None,
             state: State::Idle,
             spacing: Spacing::Normal,
             id: String::from(id),
@@ -62,6 +67,14 @@ impl ButtonWidget {
         self
     }
 
+    /// Reserves the width of `min_width` for the contents, so the bar doesn't shift
+    /// when the text gets shorter.
+    pub fn with_min_width(mut self, min_width: &str, align: Align) -> Self {
+        self.min_width = Some((String::from(min_width), align));
+        self.update();
+        self
+    }
+
     pub fn with_spacing(mut self, spacing: Spacing) -> Self {
         self.spacing = spacing;
         self.update();
@@ -78,6 +91,13 @@ impl ButtonWidget {
         self.update();
     }
 
+    /// Sets the long description of the widget, which is not shown by i3bar itself
+    /// but can be read by other bars, e.g. for a tooltip.
+    pub fn set_description(&mut self, description: Option<String>) {
+        self.description = description;
+        self.update();
+    }
+
     pub fn set_state(&mut self, state: State) {
         self.state = state;
         self.update();
@@ -106,13 +126,28 @@ impl ButtonWidget {
                                     _ => String::from(" ")
                                 }
                             ),
-            "separator": false,
+            "separator": self.config.separator.unwrap_or(false),
             "name": self.id.clone(),
-            "separator_block_width": 0,
+            "separator_block_width": self.config.separator_block_width.unwrap_or(0),
             "background": key_bg,
             "color": key_fg,
             "markup": "pango"
         });
+        // i3bar ignores keys it doesn't know, custom ones are prefixed with an underscore.
+        if let Some(ref description) = self.description {
+            self.rendered["_description"] = Value::String(description.clone());
+        }
+        if let Some((ref min_width, align)) = self.min_width {
+            // The icon and spacing are part of the text, so they need to be reserved as well.
+            self.rendered["min_width"] = Value::String(format!(
+                "{}{} ",
+                self.icon.clone().unwrap_or_else(|| String::from(" ")),
+                min_width
+            ));
+            self.rendered["align"] = Value::String(align.as_str().to_owned());
+        }
+
+        self.config.border.render(&mut self.rendered);
 
         self.cached_output = Some(self.rendered.to_string());
     }
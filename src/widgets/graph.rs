@@ -115,12 +115,14 @@ impl GraphWidget {
                                     _ => String::from(" ")
                                 }
                             ),
-            "separator": false,
-            "separator_block_width": 0,
+            "separator": self.config.separator.unwrap_or(false),
+            "separator_block_width": self.config.separator_block_width.unwrap_or(0),
             "background": key_bg.to_owned(),
             "color": key_fg.to_owned()
         });
 
+        self.config.border.render(&mut self.rendered);
+
         self.cached_output = Some(self.rendered.to_string());
     }
 }
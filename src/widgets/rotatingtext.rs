@@ -159,8 +159,8 @@ impl RotatingTextWidget {
                                     Spacing::Hidden => String::from(""),
                                     _ => String::from(" ")
                                 }),
-            "separator": false,
-            "separator_block_width": 0,
+            "separator": self.config.separator.unwrap_or(false),
+            "separator_block_width": self.config.separator_block_width.unwrap_or(0),
             "min_width":
                 if self.content == "" {
                     "".to_string()
@@ -179,6 +179,8 @@ impl RotatingTextWidget {
             "color": key_fg
         });
 
+        self.config.border.render(&mut self.rendered);
+
         self.cached_output = Some(self.rendered.to_string());
     }
 